@@ -0,0 +1,52 @@
+use wasm_bindgen::JsCast;
+use web_sys::{Gamepad, GamepadButton, GamepadMappingType};
+
+const AXIS_THRESHOLD: f64 = 0.5;
+pub const MAX_GAMEPAD_COUNT: usize = 2;
+
+pub type GamepadInput = ((i8, i8), bool);
+
+// Button indices in the standard mapping.
+const SHOT_BUTTONS: [u32; 5] = [0, 1, 2, 3, 9];  // Face buttons and start.
+const DPAD_UP: u32 = 12;
+const DPAD_DOWN: u32 = 13;
+const DPAD_LEFT: u32 = 14;
+const DPAD_RIGHT: u32 = 15;
+
+// Non-standard pads put buttons in any order, so use the first ones for shot.
+const NON_STANDARD_SHOT_BUTTON_COUNT: u32 = 4;
+
+// Input of connected gamepads in order: stick direction and shot button.
+pub fn poll_gamepads() -> [GamepadInput; MAX_GAMEPAD_COUNT] {
+    let mut inputs = [((0, 0), false); MAX_GAMEPAD_COUNT];
+    let gamepads = match web_sys::window().and_then(|window| window.navigator().get_gamepads().ok()) {
+        Some(gamepads) => gamepads,
+        None => return inputs,
+    };
+
+    let connected = gamepads.iter()
+        .filter_map(|value| value.dyn_into::<Gamepad>().ok())
+        .filter(|gamepad| gamepad.connected());
+    for (gamepad, (dir, shot)) in connected.zip(inputs.iter_mut()) {
+        let axes = gamepad.axes();
+        let axis = |index| {
+            let v = axes.get(index).as_f64().unwrap_or(0.0);
+            if v < -AXIS_THRESHOLD { -1 } else if v > AXIS_THRESHOLD { 1 } else { 0 }
+        };
+        let buttons = gamepad.buttons();
+        let pressed = |index| {
+            buttons.get(index).dyn_into::<GamepadButton>().is_ok_and(|button| button.pressed())
+        };
+
+        let (mut x, mut y) = (axis(0), axis(1));
+        if gamepad.mapping() == GamepadMappingType::Standard {
+            if pressed(DPAD_LEFT) { x = -1; } else if pressed(DPAD_RIGHT) { x = 1; }
+            if pressed(DPAD_UP) { y = -1; } else if pressed(DPAD_DOWN) { y = 1; }
+            *shot = SHOT_BUTTONS.iter().any(|&index| pressed(index));
+        } else {
+            *shot = (0..NON_STANDARD_SHOT_BUTTON_COUNT).any(pressed);
+        }
+        *dir = (x, y);
+    }
+    inputs
+}
@@ -1,9 +1,13 @@
 extern crate galangua_ecs;
+extern crate galangua_oo;
 extern crate web_sys;
 
+mod gamepad;
+mod touch_controls;
 mod utils;
 mod wasm_app_framework;
 mod wasm_renderer;
+mod wasm_storage;
 mod wasm_system;
 mod wasm_timer;
 
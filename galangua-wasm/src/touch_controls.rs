@@ -0,0 +1,129 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlElement, Touch, TouchEvent};
+
+const STICK_AREA_ID: &str = "stick-area";
+const STICK_GRIP_ID: &str = "stick-grip";
+const SHOT_BUTTON_ID: &str = "shot-btn";
+
+// Dead zone of the virtual stick, in ratio of the area size.
+const STICK_DEAD_ZONE_X: f64 = 1.0 / 6.0;
+const STICK_DEAD_ZONE_Y: f64 = 1.0 / 4.0;
+
+struct Elements {
+    stick_area: HtmlElement,
+    stick_grip: HtmlElement,
+    shot_button: HtmlElement,
+}
+
+#[derive(Default)]
+struct TouchState {
+    stick_id: Option<i32>,
+    stick_dir: (i8, i8),
+    shot_ids: Vec<i32>,
+}
+
+// Virtual stick and shot button on the page, multiple fingers are tracked by touch identifiers.
+pub struct TouchControls {
+    state: Rc<RefCell<TouchState>>,
+    _listeners: Vec<Closure<dyn FnMut(TouchEvent)>>,
+}
+
+impl TouchControls {
+    pub fn new() -> Option<Self> {
+        let document = web_sys::window()?.document()?;
+        let element = |id| document.get_element_by_id(id)?.dyn_into::<HtmlElement>().ok();
+        let elements = Rc::new(Elements {
+            stick_area: element(STICK_AREA_ID)?,
+            stick_grip: element(STICK_GRIP_ID)?,
+            shot_button: element(SHOT_BUTTON_ID)?,
+        });
+        let state = Rc::new(RefCell::new(TouchState::default()));
+
+        let mut listeners = Vec::new();
+        for (event_name, handler) in [
+            ("touchstart", on_touch_start as fn(&mut TouchState, &Elements, &Touch)),
+            ("touchmove", on_touch_move),
+            ("touchend", on_touch_end),
+            ("touchcancel", on_touch_end),
+        ] {
+            let state = state.clone();
+            let elements = elements.clone();
+            let listener = Closure::wrap(Box::new(move |event: TouchEvent| {
+                let touches = event.changed_touches();
+                let mut state = state.borrow_mut();
+                for touch in (0..touches.length()).filter_map(|i| touches.get(i)) {
+                    handler(&mut state, &elements, &touch);
+                }
+            }) as Box<dyn FnMut(TouchEvent)>);
+            document.add_event_listener_with_callback(event_name, listener.as_ref().unchecked_ref())
+                .ok()?;
+            listeners.push(listener);
+        }
+
+        Some(Self {
+            state,
+            _listeners: listeners,
+        })
+    }
+
+    // Returns stick direction and whether the shot button is held.
+    pub fn input(&self) -> ((i8, i8), bool) {
+        let state = self.state.borrow();
+        (state.stick_dir, !state.shot_ids.is_empty())
+    }
+}
+
+fn on_touch_start(state: &mut TouchState, elements: &Elements, touch: &Touch) {
+    let id = touch.identifier();
+    if hit(&elements.shot_button, touch) {
+        state.shot_ids.push(id);
+    } else if state.stick_id.is_none() && hit(&elements.stick_area, touch) {
+        state.stick_id = Some(id);
+        elements.stick_grip.style().set_property("visibility", "visible").ok();
+        on_touch_move(state, elements, touch);
+    }
+}
+
+fn on_touch_move(state: &mut TouchState, elements: &Elements, touch: &Touch) {
+    if state.stick_id != Some(touch.identifier()) {
+        return;
+    }
+
+    let rect = elements.stick_area.get_bounding_client_rect();
+    let (w, h) = (rect.width(), rect.height());
+    let x = (touch.client_x() as f64 - rect.left()).clamp(0.0, w);
+    let y = (touch.client_y() as f64 - rect.top()).clamp(0.0, h);
+    state.stick_dir = stick_dir(x / w - 0.5, y / h - 0.5);
+
+    let grip = &elements.stick_grip;
+    let (gw, gh) = (grip.offset_width() as f64, grip.offset_height() as f64);
+    let style = grip.style();
+    style.set_property("left", &format!("{}px", (x - gw / 2.0).clamp(0.0, w - gw))).ok();
+    style.set_property("top", &format!("{}px", (y - gh / 2.0).clamp(0.0, h - gh))).ok();
+}
+
+fn on_touch_end(state: &mut TouchState, elements: &Elements, touch: &Touch) {
+    let id = touch.identifier();
+    state.shot_ids.retain(|&shot_id| shot_id != id);
+    if state.stick_id == Some(id) {
+        state.stick_id = None;
+        state.stick_dir = (0, 0);
+        elements.stick_grip.style().set_property("visibility", "hidden").ok();
+    }
+}
+
+fn hit(element: &HtmlElement, touch: &Touch) -> bool {
+    let rect = element.get_bounding_client_rect();
+    let x = touch.client_x() as f64 - rect.left();
+    let y = touch.client_y() as f64 - rect.top();
+    x >= 0.0 && y >= 0.0 && x < rect.width() && y < rect.height()
+}
+
+// `dx` and `dy` are offsets from the center, in ratio of the area size.
+fn stick_dir(dx: f64, dy: f64) -> (i8, i8) {
+    let axis = |d: f64, dead_zone: f64| if d <= -dead_zone { -1 } else if d >= dead_zone { 1 } else { 0 };
+    (axis(dx, STICK_DEAD_ZONE_X), axis(dy, STICK_DEAD_ZONE_Y))
+}
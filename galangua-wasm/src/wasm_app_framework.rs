@@ -2,24 +2,37 @@ use wasm_bindgen::prelude::*;
 
 use galangua_common::framework::{AppTrait, VKey};
 use galangua_ecs::app::GalanguaEcsApp;
+use galangua_oo::app::GalanguaApp;
 
+use super::gamepad::poll_gamepads;
+use super::touch_controls::TouchControls;
 use super::wasm_renderer::WasmRenderer;
+use super::wasm_storage::WasmStorage;
 use super::wasm_system::WasmSystem;
 use super::wasm_timer::WasmTimer;
 
+#[wasm_bindgen]
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum AppVariant {
+    Ecs,
+    Oo,
+}
+
 #[wasm_bindgen]
 pub struct WasmAppFramework {
     app: Box<dyn AppTrait<WasmRenderer>>,
     renderer: WasmRenderer,
+    touch_controls: Option<TouchControls>,
 }
 
 #[wasm_bindgen]
 impl WasmAppFramework {
     pub fn new(
         mut renderer: WasmRenderer,
+        variant: AppVariant,
+        storage: WasmStorage,
         is_touch_device: bool,
         get_now_fn: js_sys::Function,
-        get_item_fn: js_sys::Function, set_item_fn: js_sys::Function,
     ) -> Self {
         let timer = WasmTimer::new(move || {
             let this = JsValue::NULL;
@@ -30,24 +43,20 @@ impl WasmAppFramework {
             }
             0.0
         });
-        let system = WasmSystem::new(
-            move |key| {
-                let this = JsValue::NULL;
-                get_item_fn.call1(&this, &JsValue::from(key)).ok()
-            },
-            move |key, value| {
-                let this = JsValue::NULL;
-                set_item_fn.call2(&this, &JsValue::from(key), &JsValue::from(value)).unwrap();
-            },
-            is_touch_device,
-        );
-        let mut app = GalanguaEcsApp::new(timer, system);
+        let system = WasmSystem::new(storage, is_touch_device);
+        let mut app: Box<dyn AppTrait<WasmRenderer>> = match variant {
+            AppVariant::Ecs => Box::new(GalanguaEcsApp::new(timer, system)),
+            AppVariant::Oo => Box::new(GalanguaApp::new(timer, system)),
+        };
 
         app.init(&mut renderer);
 
+        let touch_controls = if is_touch_device { TouchControls::new() } else { None };
+
         Self {
-            app: Box::new(app),
+            app,
             renderer,
+            touch_controls,
         }
     }
 
@@ -57,25 +66,30 @@ impl WasmAppFramework {
         }
     }
 
-    pub fn on_joystick_axis(&mut self, axis_index: u8, dir: i8) {
-        self.app.on_joystick_axis(axis_index, dir);
+    pub fn on_joystick_axis(&mut self, joystick_index: u8, axis_index: u8, dir: i8) {
+        self.app.on_joystick_axis(joystick_index, axis_index, dir);
     }
 
-    pub fn on_joystick_button(&mut self, button_index: u8, down: bool) {
-        self.app.on_joystick_button(button_index, down);
+    pub fn on_joystick_button(&mut self, joystick_index: u8, button_index: u8, down: bool) {
+        self.app.on_joystick_button(joystick_index, button_index, down);
     }
 
-    pub fn on_touch(&mut self, num: i32, down: bool) {
-        match num {
-            -1 | 0 | 1 => {
-                let keys = [(-1, VKey::Left), (1, VKey::Right)];
-                for i in 0..keys.len() {
-                    let d = down && keys[i].0 == num;
-                    self.app.on_key(keys[i].1, d);
-                }
-            }
-            100 => self.app.on_key(VKey::Space, down),
-            _ => {}
+    // Feeds touch controls and gamepads into the joystick input, call once per frame.
+    // Touch controls are merged into the first joystick.
+    pub fn poll_input(&mut self) {
+        let mut inputs = poll_gamepads();
+        if let Some(touch_controls) = &self.touch_controls {
+            let (touch_dir, touch_shot) = touch_controls.input();
+            let (dir, shot) = &mut inputs[0];
+            if dir.0 == 0 { dir.0 = touch_dir.0; }
+            if dir.1 == 0 { dir.1 = touch_dir.1; }
+            *shot |= touch_shot;
+        }
+        for (index, (dir, shot)) in inputs.iter().enumerate() {
+            let index = index as u8;
+            self.app.on_joystick_axis(index, 0, dir.0);
+            self.app.on_joystick_axis(index, 1, dir.1);
+            self.app.on_joystick_button(index, 0, *shot);
         }
     }
 
@@ -85,6 +99,16 @@ impl WasmAppFramework {
 
     pub fn draw(&mut self) {
         self.app.draw(&mut self.renderer);
+        self.renderer.present();
+    }
+
+    // Call when the size of the area for the screen changes, in CSS pixels.
+    pub fn resize(&mut self, width: f64, height: f64) {
+        self.renderer.resize(width, height);
+    }
+
+    pub fn game_speed(&self) -> u32 {
+        self.app.game_speed()
     }
 }
 
@@ -97,6 +121,11 @@ fn to_vkey(key_code: &str) -> Option<VKey> {
         "ArrowRight" => Some(VKey::Right),
         "ArrowUp" => Some(VKey::Up),
         "ArrowDown" => Some(VKey::Down),
+        "KeyW" => Some(VKey::W),
+        "KeyA" => Some(VKey::A),
+        "KeyS" => Some(VKey::S),
+        "KeyD" => Some(VKey::D),
+        "KeyF" => Some(VKey::F),
         _ => None,
     }
 }
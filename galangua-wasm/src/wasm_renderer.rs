@@ -8,16 +8,27 @@ use std::rc::Rc;
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsCast;
 use wasm_bindgen_futures::JsFuture;
-use web_sys::{HtmlCanvasElement, HtmlImageElement, Request, RequestInit, RequestMode, Response};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement, OffscreenCanvas,
+              Request, RequestInit, RequestMode, Response};
 
 use galangua_common::framework::sprite_sheet::SpriteSheet;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
 
+// Where the game is drawn at logical resolution, before scaled up to the screen.
+enum Buffer {
+    Offscreen(OffscreenCanvas),
+    Canvas(HtmlCanvasElement),
+}
+
 #[wasm_bindgen]
 pub struct WasmRenderer {
     canvas: HtmlCanvasElement,
-    context: web_sys::CanvasRenderingContext2d,
+    screen_context: CanvasRenderingContext2d,
+    buffer: Buffer,
+    context: CanvasRenderingContext2d,
+    width: u32,
+    height: u32,
     images: Rc<RefCell<HashMap<String, HtmlImageElement>>>,
     sprite_sheet: Rc<RefCell<SpriteSheet>>,
 }
@@ -31,22 +42,79 @@ impl WasmRenderer {
             .dyn_into::<HtmlCanvasElement>()
             .map_err(|_| ())
             .unwrap();
-        let context = canvas
-            .get_context("2d")
-            .unwrap()
-            .unwrap()
-            .dyn_into::<web_sys::CanvasRenderingContext2d>()
-            .unwrap();
+        let screen_context = get_context_2d(canvas.get_context("2d"));
+
+        // Logical resolution is given by the canvas element.
+        let (width, height) = (canvas.width(), canvas.height());
+        let (buffer, context) = match OffscreenCanvas::new(width, height) {
+            Ok(offscreen) => {
+                // Offscreen context has the same drawing API.
+                let context = get_context_2d(offscreen.get_context("2d"));
+                (Buffer::Offscreen(offscreen), context)
+            }
+            Err(_) => {
+                let buffer = document.create_element("canvas").unwrap()
+                    .dyn_into::<HtmlCanvasElement>()
+                    .unwrap();
+                buffer.set_width(width);
+                buffer.set_height(height);
+                let context = get_context_2d(buffer.get_context("2d"));
+                (Buffer::Canvas(buffer), context)
+            }
+        };
+        screen_context.set_image_smoothing_enabled(false);
 
         Self {
             canvas,
+            screen_context,
+            buffer,
             context,
+            width,
+            height,
             images: Rc::new(RefCell::new(HashMap::new())),
             sprite_sheet: Rc::new(RefCell::new(SpriteSheet::default())),
         }
     }
 }
 
+impl WasmRenderer {
+    // Fits the screen into the given size in CSS pixels, scaling by integer in device pixels.
+    pub fn resize(&mut self, width: f64, height: f64) {
+        let dpr = web_sys::window().unwrap().device_pixel_ratio();
+        let scale = ((width * dpr / self.width as f64).min(height * dpr / self.height as f64))
+            .floor()
+            .max(1.0) as u32;
+        let (w, h) = (self.width * scale, self.height * scale);
+        self.canvas.set_width(w);
+        self.canvas.set_height(h);
+        let style = self.canvas.style();
+        style.set_property("width", &format!("{}px", w as f64 / dpr)).unwrap();
+        style.set_property("height", &format!("{}px", h as f64 / dpr)).unwrap();
+
+        // Resizing the canvas resets the context state.
+        self.screen_context.set_image_smoothing_enabled(false);
+    }
+
+    pub fn present(&mut self) {
+        let (w, h) = (self.canvas.width() as f64, self.canvas.height() as f64);
+        match &self.buffer {
+            Buffer::Offscreen(offscreen) => {
+                self.screen_context.draw_image_with_offscreen_canvas_and_dw_and_dh(offscreen, 0.0, 0.0, w, h)
+            }
+            Buffer::Canvas(buffer) => {
+                self.screen_context.draw_image_with_html_canvas_element_and_dw_and_dh(buffer, 0.0, 0.0, w, h)
+            }
+        }.expect("present failed");
+    }
+}
+
+fn get_context_2d(context: Result<Option<js_sys::Object>, JsValue>) -> CanvasRenderingContext2d {
+    context
+        .unwrap()
+        .unwrap()
+        .unchecked_into::<CanvasRenderingContext2d>()
+}
+
 impl RendererTrait for WasmRenderer {
     fn load_textures(&mut self, base_path: &str, filenames: &[&str]) {
         for &filename in filenames.iter() {
@@ -97,7 +165,7 @@ impl RendererTrait for WasmRenderer {
     }
 
     fn clear(&mut self) {
-        self.context.fill_rect(0.0, 0.0, self.canvas.width() as f64, self.canvas.height() as f64)
+        self.context.fill_rect(0.0, 0.0, self.width as f64, self.height as f64)
     }
 
     fn set_texture_color_mod(&mut self, _tex_name: &str, _r: u8, _g: u8, _b: u8) {}
@@ -178,7 +246,7 @@ impl RendererTrait for WasmRenderer {
         if let Some(dst) = dst {
             self.context.fill_rect(dst[0].x as f64, dst[0].y as f64, dst[1].x as f64, dst[1].y as f64);
         } else {
-            self.context.fill_rect(0.0, 0.0, self.canvas.width() as f64, self.canvas.height() as f64);
+            self.context.fill_rect(0.0, 0.0, self.width as f64, self.height as f64);
         }
     }
 }
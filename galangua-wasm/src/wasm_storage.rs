@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode, Storage};
+
+use galangua_common::framework::storage::Blob;
+
+const KEY_PREFIX: &str = "galangua:";
+const BLOB_KEY_PREFIX: &str = "galangua:blob:";
+
+const DB_NAME: &str = "galangua";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "blobs";
+
+// Blobs larger than this are put into IndexedDB, like recorded replays.
+const LOCAL_STORAGE_BLOB_LIMIT: usize = 4 * 1024;
+
+// Persistent storage for the browser, small values in localStorage and large blobs in IndexedDB.
+//
+// IndexedDB is asynchronous, so its contents are read at `open` and written in background.
+#[wasm_bindgen]
+pub struct WasmStorage {
+    local: Option<Storage>,
+    db: Option<IdbDatabase>,
+    db_blobs: HashMap<String, String>,
+}
+
+#[wasm_bindgen]
+impl WasmStorage {
+    pub async fn open() -> WasmStorage {
+        let local = web_sys::window().and_then(|window| window.local_storage().ok().flatten());
+        let (db, db_blobs) = match open_db().await {
+            Ok(db) => {
+                let db_blobs = read_all(&db).await.unwrap_or_else(|error| {
+                    web_sys::console::error_2(&"IndexedDB read failed:".into(), &error);
+                    HashMap::new()
+                });
+                (Some(db), db_blobs)
+            }
+            Err(error) => {
+                web_sys::console::error_2(&"IndexedDB unavailable:".into(), &error);
+                (None, HashMap::new())
+            }
+        };
+        Self { local, db, db_blobs }
+    }
+}
+
+impl WasmStorage {
+    pub fn get_str(&self, key: &str) -> Option<String> {
+        self.get_local(&format!("{}{}", KEY_PREFIX, key))
+    }
+
+    pub fn set_str(&mut self, key: &str, value: Option<&str>) {
+        self.set_local(&format!("{}{}", KEY_PREFIX, key), value);
+    }
+
+    pub fn get_blob(&self, namespace: &str) -> Option<Blob> {
+        let text = self.db_blobs.get(namespace).cloned()
+            .or_else(|| self.get_local(&format!("{}{}", BLOB_KEY_PREFIX, namespace)))?;
+        Blob::from_value(&serde_json::from_str(&text).ok()?)
+    }
+
+    pub fn set_blob(&mut self, namespace: &str, blob: &Blob) {
+        let text = blob.to_value().to_string();
+        let key = format!("{}{}", BLOB_KEY_PREFIX, namespace);
+        if text.len() > LOCAL_STORAGE_BLOB_LIMIT && self.db.is_some() {
+            self.set_local(&key, None);
+            self.put_db(namespace, &text);
+            self.db_blobs.insert(namespace.to_string(), text);
+        } else {
+            if self.db_blobs.remove(namespace).is_some() {
+                self.delete_db(namespace);
+            }
+            self.set_local(&key, Some(&text));
+        }
+    }
+
+    fn get_local(&self, key: &str) -> Option<String> {
+        self.local.as_ref()?.get_item(key).ok().flatten()
+    }
+
+    fn set_local(&self, key: &str, value: Option<&str>) {
+        if let Some(local) = &self.local {
+            let result = match value {
+                Some(value) => local.set_item(key, value),
+                None => local.remove_item(key),
+            };
+            if let Err(error) = result {
+                web_sys::console::error_2(&"localStorage write failed:".into(), &error);
+            }
+        }
+    }
+
+    fn put_db(&self, key: &str, text: &str) {
+        let result = self.db.as_ref().unwrap()
+            .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+            .and_then(|transaction| transaction.object_store(STORE_NAME))
+            .and_then(|store| store.put_with_key(&JsValue::from_str(text), &JsValue::from_str(key)));
+        if let Err(error) = result {
+            web_sys::console::error_2(&"IndexedDB write failed:".into(), &error);
+        }
+    }
+
+    fn delete_db(&self, key: &str) {
+        let result = self.db.as_ref().unwrap()
+            .transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)
+            .and_then(|transaction| transaction.object_store(STORE_NAME))
+            .and_then(|store| store.delete(&JsValue::from_str(key)));
+        if let Err(error) = result {
+            web_sys::console::error_2(&"IndexedDB delete failed:".into(), &error);
+        }
+    }
+}
+
+async fn open_db() -> Result<IdbDatabase, JsValue> {
+    let factory = web_sys::window().unwrap().indexed_db()?
+        .ok_or_else(|| JsValue::from_str("no indexedDB"))?;
+    let request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+    let on_upgrade = Closure::once(move |event: web_sys::Event| {
+        let db = event.target()
+            .and_then(|target| target.dyn_into::<IdbRequest>().ok())
+            .and_then(|request| request.result().ok())
+            .and_then(|result| result.dyn_into::<IdbDatabase>().ok());
+        if let Some(db) = db {
+            db.create_object_store(STORE_NAME).ok();
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+    let db = wait_request(&request).await;
+    request.set_onupgradeneeded(None);
+    db?.dyn_into::<IdbDatabase>()
+}
+
+async fn read_all(db: &IdbDatabase) -> Result<HashMap<String, String>, JsValue> {
+    let store = db.transaction_with_str(STORE_NAME)?.object_store(STORE_NAME)?;
+    // Both are sorted by key, requested together before the transaction finishes.
+    let keys_request = store.get_all_keys()?;
+    let values_request = store.get_all()?;
+    let keys: js_sys::Array = wait_request(&keys_request).await?.dyn_into()?;
+    let values: js_sys::Array = wait_request(&values_request).await?.dyn_into()?;
+    Ok(keys.iter().zip(values.iter())
+        .filter_map(|(key, value)| Some((key.as_string()?, value.as_string()?)))
+        .collect())
+}
+
+async fn wait_request(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        let succeeded = request.clone();
+        let on_success = Closure::once_into_js(move |_event: JsValue| {
+            resolve.call1(&JsValue::NULL, &succeeded.result().unwrap_or(JsValue::UNDEFINED)).ok();
+        });
+        let on_error = Closure::once_into_js(move |_event: JsValue| {
+            reject.call1(&JsValue::NULL, &JsValue::from_str("request failed")).ok();
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
@@ -1,46 +1,45 @@
 use wasm_bindgen::prelude::*;
 
+use galangua_common::framework::storage::Blob;
 use galangua_common::framework::SystemTrait;
 
+use super::wasm_storage::WasmStorage;
+
 #[wasm_bindgen]
 extern "C" {
     fn play_se(channel: u32, filename: &str);
 }
 
-pub struct WasmSystem<
-    F: Fn(&str) -> Option<JsValue>,
-    G: Fn(&str, JsValue)
-> {
-    get_item: F,
-    set_item: G,
+pub struct WasmSystem {
+    storage: WasmStorage,
     is_touch_device: bool,
 }
 
-impl<
-    F: Fn(&str) -> Option<JsValue>,
-    G: Fn(&str, JsValue)
-> WasmSystem<F, G> {
-    pub fn new(get_item: F, set_item: G, is_touch_device: bool) -> Self {
+impl WasmSystem {
+    pub fn new(storage: WasmStorage, is_touch_device: bool) -> Self {
         WasmSystem {
-            get_item,
-            set_item,
+            storage,
             is_touch_device,
         }
     }
 }
 
-impl<
-    F: Fn(&str) -> Option<JsValue>,
-    G: Fn(&str, JsValue)
-> SystemTrait for WasmSystem<F, G> {
+impl SystemTrait for WasmSystem {
     fn get_u32(&self, key: &str) -> Option<u32> {
-        (self.get_item)(key)
-            .map(|value| value.as_string()).flatten()
-            .map(|string| string.parse().ok()).flatten()
+        self.storage.get_str(key)
+            .and_then(|string| string.parse().ok())
     }
 
     fn set_u32(&mut self, key: &str, value: u32) {
-        (self.set_item)(key, JsValue::from(value));
+        self.storage.set_str(key, Some(&value.to_string()));
+    }
+
+    fn get_blob(&self, namespace: &str) -> Option<Blob> {
+        self.storage.get_blob(namespace)
+    }
+
+    fn set_blob(&mut self, namespace: &str, blob: &Blob) {
+        self.storage.set_blob(namespace, blob);
     }
 
     fn is_touch_device(&self) -> bool { self.is_touch_device }
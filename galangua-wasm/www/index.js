@@ -1,4 +1,4 @@
-import {WasmAppFramework, WasmRenderer} from 'galangua-wasm'
+import {AppVariant, WasmAppFramework, WasmRenderer, WasmStorage} from 'galangua-wasm'
 import {audioManager} from './audio_manager'
 
 const CHANNEL_COUNT = 3
@@ -16,8 +16,6 @@ const ICON_SOUND_OFF = 'assets/imgs/sound_off.svg'
 
 const CANVAS_ID = 'mycanvas'
 
-const LOCAL_STORAGE_PREFIX = 'galangua:'
-
 window.play_se = function play_se(channel, filename) {
   audioManager.playSe(channel, filename)
 }
@@ -35,92 +33,9 @@ function disableBounce() {
   document.addEventListener('touchmove', (event) => event.preventDefault(), {passive: false})
 }
 
-function setTouchHandler(id, callback) {
-  const elem = document.getElementById(id)
-  elem.addEventListener('touchstart', (e) => { /*e.preventDefault();*/ e.stopPropagation(); callback(true); return false; }, {passive: true})
-  elem.addEventListener('touchend', (_) => callback(false))
-  elem.addEventListener('touchleave', (_) => callback(false))
-}
-
 function setupTouchButtons() {
   const holder = document.getElementById('touch-btn-holder')
-
-  if (isTouchDevice()) {
-    // Touch enable
-    setTouchHandler('shot-btn', (down) => framework.on_touch(100, down))
-    holder.style.display = ''
-
-    const stickGrip = document.getElementById('stick-grip')
-    const stickArea = stickGrip.parentNode
-
-    stickArea.addEventListener('touchstart', (event) => {
-      // e.preventDefault()
-      event.stopPropagation()
-
-      if (event.changedTouches == null || event.changedTouches.length <= 0)
-        return
-      const id = event.changedTouches[0].identifier
-
-      stickGrip.style.visibility = 'visible'
-      const areaRect = stickArea.getBoundingClientRect()
-      const gripRect = stickGrip.getBoundingClientRect()
-      const w = gripRect.width
-      const h = gripRect.height
-      const D = areaRect.width / 6
-      const l = areaRect.width / 2 - D
-      const r = areaRect.width / 2 + D
-      let lr = 0
-
-      const findTargetTouch = (e) => {
-        if (e.changedTouches != null) {
-          for (let i = 0; i < e.changedTouches.length; ++i) {
-            const touch = e.changedTouches[i]
-            if (touch.identifier === id)
-              return touch
-          }
-        }
-        return null
-      }
-
-      const updatePosition = (e) => {
-        const touch = findTargetTouch(e)
-        if (touch != null) {
-          const x = Math.min(Math.max(touch.clientX - areaRect.left, 0), areaRect.width)
-          const y = Math.min(Math.max(touch.clientY - areaRect.top, 0), areaRect.height)
-          stickGrip.style.left = `${Math.min(Math.max(x - w / 2, 0), areaRect.width - w)}px`
-          stickGrip.style.top = `${Math.min(Math.max(y - h / 2, 0), areaRect.height - h)}px`
-
-          lr = x <= l ? -1 : x >= r ? 1 : 0
-          framework.on_touch(lr, true)
-        }
-      }
-
-      const move = (e) => {
-        updatePosition(e)
-      }
-      const end = (e) => {
-        const touch = findTargetTouch(e)
-        if (touch == null)
-          return
-
-        document.removeEventListener('touchmove', move)
-        document.removeEventListener('touchend', end)
-        document.removeEventListener('touchcancel', end)
-        stickGrip.style.visibility = 'hidden'
-        framework.on_touch(0, false)
-      }
-      document.addEventListener('touchmove', move)
-      document.addEventListener('touchend', end)
-      document.addEventListener('touchcancel', end)
-
-      updatePosition(event)
-
-      return false
-    }, {passive: true})
-  } else {
-    // Touch disable
-    holder.style.display = 'none'
-  }
+  holder.style.display = isTouchDevice() ? '' : 'none'
 
   const toggleSound = () => {
     audioManager.toggleEnabled()
@@ -147,59 +62,37 @@ function createCoverScreen(title) {
   return cover
 }
 
-class GamepadManager {
-  constructor() {
-    this.isSupported = 'Gamepad' in window
-    this.xdir = 0
-    this.btn = false
-  }
+disableBounce()
 
-  update() {
-    this.xdir = 0
-    this.btn = false
-
-    if (!this.isSupported)
-      return
-    const gamepads = navigator.getGamepads()
-    if (gamepads.Length < 1)
-      return
-    const gamepad = gamepads[0]
-    if (!gamepad)
-      return
-
-    const THRESHOLD = 0.5
-    const x = gamepad.axes[0]
-    this.xdir = (x < -THRESHOLD) ? -1 : (x > THRESHOLD) ? 1 : 0
-    this.btn = ((gamepad.buttons[0] && gamepad.buttons[0].pressed) ||
-        (gamepad.buttons[1] && gamepad.buttons[1].pressed))
-  }
+// Select the implementation with `?app=oo`.
+function appVariant() {
+  const params = new URLSearchParams(window.location.search)
+  return params.get('app') === 'oo' ? AppVariant.Oo : AppVariant.Ecs
 }
 
-const gamepadManager = new GamepadManager()
-
-disableBounce()
-
-const renderer = WasmRenderer.new(CANVAS_ID)
-const framework = WasmAppFramework.new(
-  renderer, isTouchDevice(),
-  function get_now() {
-    return performance.now()
-  },
-  function get_item(key) {
-    return localStorage.getItem(`${LOCAL_STORAGE_PREFIX}${key}`)
-  },
-  function set_item(key, value) {
-    localStorage.setItem(`${LOCAL_STORAGE_PREFIX}${key}`, value)
+function createFramework(storage) {
+  const renderer = WasmRenderer.new(CANVAS_ID)
+  const framework = WasmAppFramework.new(
+    renderer, appVariant(), storage, isTouchDevice(),
+    function get_now() {
+      return performance.now()
+    })
+
+  const canvasContainer = document.getElementById(CANVAS_ID).parentNode
+  const fitCanvas = () => framework.resize(canvasContainer.clientWidth, canvasContainer.clientHeight)
+  fitCanvas()
+  window.addEventListener('resize', fitCanvas)
+
+  document.addEventListener('keydown', (event) => {
+    framework.on_key(event.code, true)
   })
+  document.addEventListener('keyup', (event) => {
+    framework.on_key(event.code, false)
+  })
+  return framework
+}
 
-document.addEventListener('keydown', (event) => {
-  framework.on_key(event.code, true)
-})
-document.addEventListener('keyup', (event) => {
-  framework.on_key(event.code, false)
-})
-
-const loop = (function() {
+function createLoop(framework) {
   const target_fps = 60
   const ticks = 1000 / target_fps
   const max_skip = 5
@@ -208,18 +101,17 @@ const loop = (function() {
   let prev = performance.now()
   return function loop() {
     const now = performance.now()
-    let n = Math.floor((now - prev + margin) / ticks)
+    const speed = framework.game_speed()
+    let n = Math.floor((now - prev + margin) * speed / (ticks * 100))
     if (n > 0) {
       if (n <= max_skip) {
-        prev += n * ticks
+        prev += n * ticks * 100 / speed
       } else {
         n = max_skip
         prev = now
       }
 
-      gamepadManager.update()
-      framework.on_joystick_axis(0, gamepadManager.xdir)
-      framework.on_joystick_button(0, gamepadManager.btn)
+      framework.poll_input()
 
       for (let i = 0; i < n; ++i)
         framework.update()
@@ -227,15 +119,16 @@ const loop = (function() {
     }
     requestAnimationFrame(loop)
   }
-})()
+}
 
 const cover = createCoverScreen('Loading...')
 audioManager.createContext(CHANNEL_COUNT)
-audioManager.loadAllAudios(AUDIO_ASSETS)
-  .then(() => {
+Promise.all([WasmStorage.open(), audioManager.loadAllAudios(AUDIO_ASSETS)])
+  .then(([storage, _]) => {
+    const framework = createFramework(storage)
     document.body.removeChild(cover)
     setupTouchButtons()
-    requestAnimationFrame(loop)
+    requestAnimationFrame(createLoop(framework))
   })
 
 document.documentElement.addEventListener('touchend', (event) => {
@@ -0,0 +1,9 @@
+use crate::app::game::tractor_beam_table::tractor_beam_sprite_names;
+use crate::framework::sprite_sheet::AnimationTable;
+
+// Sprites which get outlines for visibility.
+pub fn outline_sprite_names(animations: &AnimationTable) -> Vec<String> {
+    let mut names = vec!["ene_shot".to_string()];
+    names.extend(tractor_beam_sprite_names(animations));
+    names
+}
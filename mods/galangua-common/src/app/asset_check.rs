@@ -0,0 +1,172 @@
+use counted_array::counted_array;
+use serde_json::Value;
+use std::collections::BTreeSet;
+
+use crate::app::consts::*;
+use crate::app::game::effect_table::*;
+use crate::app::game::stage_indicator::flag_sprite_names;
+use crate::app::game::tractor_beam_table::{TRACTOR_BEAM_ANIME_NAME, TRACTOR_BEAM_Y_OFFSET_TABLE};
+use crate::framework::sprite_sheet::load_animations;
+
+// Sprites which the apps draw by name directly.
+counted_array!(pub const DIRECT_SPRITE_NAMES: [&str; _] = [
+    "rustacean", "rustacean_captured", "myshot", "ene_shot",
+    "gopher1", "gopher2", "dman1", "dman2",
+    "cpp11", "cpp12", "cpp21", "cpp22",
+    "scorpion1", "scorpion2", "stingray1", "stingray2", "flagship1", "flagship2",
+]);
+
+counted_array!(pub const SE_PATHS: [&str; _] = [
+    SE_COUNT_STAGE, SE_MYSHOT, SE_DAMAGE, SE_BOMB_ZAKO, SE_BOMB_PLAYER, SE_BOMB_CAPTURED,
+    SE_ATTACK_START, SE_TRACTOR_BEAM1, SE_TRACTOR_BEAM2, SE_EXTEND_SHIP, SE_RECAPTURE,
+    SE_GET_ITEM, SE_ITEM_BOMB, SE_SHIELD,
+]);
+
+pub fn referenced_anime_names() -> Vec<&'static str> {
+    let mut names = vec![ENEMY_EXPLOSION_ANIME_NAME, PLAYER_EXPLOSION_ANIME_NAME, TRACTOR_BEAM_ANIME_NAME];
+    names.extend(FLASH_ENEMY_ANIME_NAMES.iter());
+    names.extend(EARNED_POINT_ANIME_NAMES.iter());
+    names
+}
+
+#[derive(Default, Debug)]
+pub struct AssetReport {
+    pub missing_sprites: Vec<String>,
+    pub missing_animes: Vec<String>,
+    pub invalid_animes: Vec<String>,
+    pub missing_sounds: Vec<String>,
+    pub unused_sprites: Vec<String>,
+}
+
+impl AssetReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing_sprites.is_empty() && self.missing_animes.is_empty() && self.invalid_animes.is_empty() &&
+            self.missing_sounds.is_empty()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        lines.extend(self.missing_sprites.iter().map(|s| format!("Missing sprite: {}", s)));
+        lines.extend(self.missing_animes.iter().map(|s| format!("Missing animation: {}", s)));
+        lines.extend(self.invalid_animes.iter().map(|s| format!("Invalid animation: {}", s)));
+        lines.extend(self.missing_sounds.iter().map(|s| format!("Missing sound: {}", s)));
+        lines.extend(self.unused_sprites.iter().map(|s| format!("Unused sprite: {}", s)));
+        lines
+    }
+}
+
+// Checks sprite sheet json text and sound files (`sound_exists` receives a path in `SE_PATHS`).
+pub fn check_assets(sprite_sheet_text: &str, sound_exists: impl Fn(&str) -> bool) -> AssetReport {
+    let mut report = AssetReport::default();
+    let atlas = atlas_sprite_names(sprite_sheet_text);
+    let animations = load_animations(sprite_sheet_text).unwrap_or_default();
+
+    let mut referenced: BTreeSet<&str> = BTreeSet::new();
+    for name in DIRECT_SPRITE_NAMES.iter().copied().chain(flag_sprite_names()) {
+        referenced.insert(name);
+    }
+    for name in referenced_anime_names() {
+        if !animations.contains_key(name) {
+            report.missing_animes.push(name.to_string());
+        }
+    }
+    // Each frame of the tractor beam has its own offset.
+    if let Some(clip) = animations.get(TRACTOR_BEAM_ANIME_NAME) {
+        if clip.frames.len() != TRACTOR_BEAM_Y_OFFSET_TABLE.len() {
+            report.invalid_animes.push(format!("{} has {} frames, {} expected",
+                                               TRACTOR_BEAM_ANIME_NAME, clip.frames.len(),
+                                               TRACTOR_BEAM_Y_OFFSET_TABLE.len()));
+        }
+    }
+    for clip in animations.values() {
+        referenced.extend(clip.frames.iter().map(String::as_str));
+    }
+
+    report.missing_sprites = referenced.iter()
+        .filter(|name| !atlas.contains(**name))
+        .map(|name| name.to_string())
+        .collect();
+    report.unused_sprites = atlas.iter()
+        .filter(|name| !referenced.contains(name.as_str()))
+        .cloned()
+        .collect();
+
+    let sounds: BTreeSet<&str> = SE_PATHS.iter().copied().collect();
+    report.missing_sounds = sounds.into_iter()
+        .filter(|path| !sound_exists(path))
+        .map(|path| path.to_string())
+        .collect();
+    report
+}
+
+pub fn atlas_sprite_names(sprite_sheet_text: &str) -> BTreeSet<String> {
+    let value: Value = serde_json::from_str(sprite_sheet_text).unwrap_or_default();
+    value["frames"].as_object()
+        .map(|frames| frames.keys()
+             .map(|key| key.rfind('.').map_or(key.as_str(), |i| &key[..i]).to_string())
+             .collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    const ROOT: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../..");
+
+    #[test]
+    fn test_assets() {
+        let text = std::fs::read_to_string(format!("{}/assets/chr.json", ROOT)).unwrap();
+        let report = check_assets(&text, |path| Path::new(&format!("{}/{}.ogg", ROOT, path)).exists());
+        assert!(report.is_ok(), "{:?}", report.lines());
+        assert!(report.unused_sprites.is_empty(), "{:?}", report.unused_sprites);
+    }
+
+    // Sprite names written as literals in the apps must be listed in `DIRECT_SPRITE_NAMES`.
+    #[test]
+    fn test_direct_sprite_names_listed() {
+        let text = std::fs::read_to_string(format!("{}/assets/chr.json", ROOT)).unwrap();
+        let atlas = atlas_sprite_names(&text);
+        for app in ["galangua-oo", "galangua-ecs"].iter() {
+            for literal in collect_literals(&Path::new(ROOT).join("mods").join(app).join("src")) {
+                if atlas.contains(&literal) {
+                    assert!(DIRECT_SPRITE_NAMES.contains(&literal.as_str()), "Not listed: {}", literal);
+                }
+            }
+        }
+    }
+
+    fn collect_literals(dir: &Path) -> Vec<String> {
+        let mut literals = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                literals.extend(collect_literals(&path));
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                let src = std::fs::read_to_string(&path).unwrap();
+                literals.extend(string_literals(&src));
+            }
+        }
+        literals
+    }
+
+    fn string_literals(src: &str) -> Vec<String> {
+        let mut literals = Vec::new();
+        let mut current: Option<String> = None;
+        let mut chars = src.chars();
+        while let Some(c) = chars.next() {
+            match (&mut current, c) {
+                (None, '"') => current = Some(String::new()),
+                (Some(_), '"') => literals.push(current.take().unwrap()),
+                (Some(s), '\\') => {
+                    s.push(c);
+                    s.extend(chars.next());
+                }
+                (Some(s), _) => s.push(c),
+                (None, _) => {}
+            }
+        }
+        literals
+    }
+}
@@ -0,0 +1,33 @@
+use lazy_static::lazy_static;
+use std::sync::RwLock;
+
+use crate::framework::asset_manifest::AssetManifest;
+
+lazy_static! {
+    // Built-in one, replaced with the file on disk where the app can read it.
+    static ref ASSET_MANIFEST: RwLock<AssetManifest> = RwLock::new(
+        AssetManifest::load(include_str!("../../../../assets/manifest.json"))
+            .expect("Invalid asset manifest"));
+}
+
+pub fn asset_manifest() -> AssetManifest {
+    ASSET_MANIFEST.read().unwrap().clone()
+}
+
+pub fn set_asset_manifest(manifest: AssetManifest) {
+    *ASSET_MANIFEST.write().unwrap() = manifest;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::asset_check::SE_PATHS;
+
+    #[test]
+    fn test_sounds_listed() {
+        let sounds = asset_manifest().sound_paths();
+        for path in SE_PATHS.iter() {
+            assert!(sounds.iter().any(|s| s == path), "Not in manifest: {}", path);
+        }
+    }
+}
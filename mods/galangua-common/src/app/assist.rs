@@ -0,0 +1,309 @@
+use counted_array::counted_array;
+use serde_json::json;
+
+use crate::app::consts::*;
+use crate::app::game::difficulty::{Difficulty, DIFFICULTIES};
+use crate::app::score_holder::RunKind;
+use crate::framework::storage::Blob;
+use crate::framework::text_layout::{draw_text, Align, TextStyle};
+use crate::framework::types::Vec2I;
+use crate::framework::{RendererTrait, SystemTrait};
+use crate::util::pad::{Pad, PadBit};
+
+// Game speed in percent.
+counted_array!(pub const GAME_SPEEDS: [u32; _] = [100, 75, 50]);
+// Boss stage comes every this count of stages, 0 means never.
+counted_array!(pub const BOSS_INTERVALS: [u16; _] = [0, 3, 5, 8]);
+
+const ITEM_SPEED: usize = 0;
+const ITEM_INVINCIBLE: usize = 1;
+const ITEM_AUTO_FIRE: usize = 2;
+const ITEM_DIFFICULTY: usize = 3;
+const ITEM_ARRANGE: usize = 4;
+const ITEM_BOSS: usize = 5;
+const ITEM_DONE: usize = 6;
+const ITEM_COUNT: usize = ITEM_DONE + 1;
+
+const MENU_Y: i32 = 13 * 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AssistOptions {
+    pub speed: u32,
+    pub invincible: bool,
+    pub auto_fire: bool,
+    pub difficulty: Difficulty,
+    pub arrange: bool,  // Owls drop power-up items.
+    pub boss_interval: u16,
+}
+
+impl Default for AssistOptions {
+    fn default() -> Self {
+        Self {
+            speed: GAME_SPEEDS[0],
+            invincible: false,
+            auto_fire: false,
+            difficulty: Difficulty::Arcade,
+            arrange: false,
+            boss_interval: BOSS_INTERVALS[0],
+        }
+    }
+}
+
+impl AssistOptions {
+    pub fn load(system: &impl SystemTrait) -> Self {
+        let blob = system.get_blob(NS_ASSIST);
+        let data = blob.clone().and_then(|blob| blob.data_of_version(ASSIST_VERSION));
+        let get_u32 = |key: &str| if blob.is_some() {
+            data.as_ref()?[key].as_u64().map(|value| value as u32)
+        } else {
+            system.get_u32(key)
+        };
+
+        let speed = get_u32(KEY_ASSIST_SPEED)
+            .filter(|speed| GAME_SPEEDS.contains(speed))
+            .unwrap_or(GAME_SPEEDS[0]);
+        Self {
+            speed,
+            invincible: get_u32(KEY_ASSIST_INVINCIBLE) == Some(1),
+            auto_fire: get_u32(KEY_ASSIST_AUTO_FIRE) == Some(1),
+            difficulty: get_u32(KEY_DIFFICULTY)
+                .and_then(Difficulty::from_u32)
+                .unwrap_or(Difficulty::Arcade),
+            arrange: get_u32(KEY_ARRANGE) == Some(1),
+            boss_interval: get_u32(KEY_BOSS_INTERVAL)
+                .map(|interval| interval as u16)
+                .filter(|interval| BOSS_INTERVALS.contains(interval))
+                .unwrap_or(BOSS_INTERVALS[0]),
+        }
+    }
+
+    pub fn save(&self, system: &mut impl SystemTrait) {
+        let data = json!({
+            KEY_ASSIST_SPEED: self.speed,
+            KEY_ASSIST_INVINCIBLE: self.invincible as u32,
+            KEY_ASSIST_AUTO_FIRE: self.auto_fire as u32,
+            KEY_DIFFICULTY: self.difficulty as u32,
+            KEY_ARRANGE: self.arrange as u32,
+            KEY_BOSS_INTERVAL: self.boss_interval as u32,
+        });
+        system.set_blob(NS_ASSIST, &Blob::new(ASSIST_VERSION, data));
+    }
+
+    // Difficulty, arrange and boss stages are game modes, not assists.
+    pub fn is_enabled(&self) -> bool {
+        self.speed != GAME_SPEEDS[0] || self.invincible || self.auto_fire
+    }
+
+    // Runs with any assist, difficulties below arcade, arrange mode or boss stages don't update the high score.
+    // The dynamic rank falling while playing is not counted.
+    pub fn run_kind(&self) -> RunKind {
+        if self.is_enabled() {
+            RunKind::Assisted
+        } else if self.difficulty.is_below_arcade() || self.arrange || self.boss_interval != 0 {
+            RunKind::Unranked
+        } else {
+            RunKind::Ranked
+        }
+    }
+
+    fn change_speed(&mut self, dir: i32) {
+        let n = GAME_SPEEDS.len() as i32;
+        let index = GAME_SPEEDS.iter().position(|&speed| speed == self.speed).unwrap_or(0) as i32;
+        self.speed = GAME_SPEEDS[((index + dir + n) % n) as usize];
+    }
+
+    fn change_boss_interval(&mut self, dir: i32) {
+        let n = BOSS_INTERVALS.len() as i32;
+        let index = BOSS_INTERVALS.iter().position(|&interval| interval == self.boss_interval).unwrap_or(0) as i32;
+        self.boss_interval = BOSS_INTERVALS[((index + dir + n) % n) as usize];
+    }
+
+    fn change_difficulty(&mut self, dir: i32) {
+        let n = DIFFICULTIES.len() as i32;
+        let index = self.difficulty as i32;
+        self.difficulty = DIFFICULTIES[((index + dir + n) % n) as usize];
+    }
+}
+
+#[derive(Default)]
+pub struct AssistMenu {
+    cursor: usize,
+}
+
+impl AssistMenu {
+    // Returns false when the menu is closed.
+    pub fn update(&mut self, pad: &Pad, options: &mut AssistOptions) -> bool {
+        if pad.is_trigger(PadBit::U) {
+            self.cursor = (self.cursor + ITEM_COUNT - 1) % ITEM_COUNT;
+        }
+        if pad.is_trigger(PadBit::D) {
+            self.cursor = (self.cursor + 1) % ITEM_COUNT;
+        }
+
+        let dir = if pad.is_trigger(PadBit::L) {
+            -1
+        } else if pad.is_trigger(PadBit::R) || pad.is_trigger(PadBit::A) {
+            1
+        } else {
+            return true;
+        };
+        match self.cursor {
+            ITEM_SPEED => options.change_speed(dir),
+            ITEM_INVINCIBLE => options.invincible = !options.invincible,
+            ITEM_AUTO_FIRE => options.auto_fire = !options.auto_fire,
+            ITEM_DIFFICULTY => options.change_difficulty(dir),
+            ITEM_ARRANGE => options.arrange = !options.arrange,
+            ITEM_BOSS => options.change_boss_interval(dir),
+            ITEM_DONE => return !pad.is_trigger(PadBit::A),
+            _ => {}
+        }
+        true
+    }
+
+    pub fn draw(&self, renderer: &mut impl RendererTrait, options: &AssistOptions) {
+        draw_text(renderer, "font", [&Vec2I::new(0, MENU_Y), &Vec2I::new(WIDTH, 8)],
+                  &TextStyle::centered((0, 255, 255)), "ASSIST OPTIONS");
+
+        let on_off = |value| if value { "ON" } else { "OFF" };
+        let speed = format!("{}%", options.speed);
+        let boss = if options.boss_interval == 0 { String::from("OFF") } else { format!("EVERY {}", options.boss_interval) };
+        let items = [
+            ("GAME SPEED", speed.as_str()),
+            ("INVINCIBLE", on_off(options.invincible)),
+            ("AUTO FIRE", on_off(options.auto_fire)),
+            ("DIFFICULTY", options.difficulty.name()),
+            ("ARRANGE", on_off(options.arrange)),
+            ("BOSS STAGE", boss.as_str()),
+            ("DONE", ""),
+        ];
+        for (i, (label, value)) in items.iter().enumerate() {
+            let y = MENU_Y + (3 + i as i32 * 2) * 8;
+            let color = if i == self.cursor { (255, 255, 0) } else { (255, 255, 255) };
+            if i == self.cursor {
+                draw_text(renderer, "font", [&Vec2I::new(3 * 8, y), &Vec2I::new(8, 8)],
+                          &TextStyle { color, ..TextStyle::default() }, ">");
+            }
+            draw_text(renderer, "font", [&Vec2I::new(5 * 8, y), &Vec2I::new(WIDTH, 8)],
+                      &TextStyle { color, ..TextStyle::default() }, label);
+            draw_text(renderer, "font", [&Vec2I::new(0, y), &Vec2I::new(WIDTH - 5 * 8, 8)],
+                      &TextStyle::right(color), value);
+        }
+
+        draw_text(renderer, "font", [&Vec2I::new(2 * 8, MENU_Y + 17 * 8), &Vec2I::new(WIDTH - 4 * 8, 8 * 8)],
+                  &TextStyle { align: Align::Center, color: (128, 128, 128), wrap: true, ..TextStyle::default() },
+                  "HIGH SCORE IS NOT RECORDED WITH ASSISTS, BELOW ARCADE DIFFICULTY, ARRANGE OR BOSS STAGES");
+    }
+}
+
+// Shown on the title screen, while the menu is closed.
+pub fn draw_assist_hint(renderer: &mut impl RendererTrait, options: &AssistOptions) {
+    let msg = match options.run_kind() {
+        RunKind::Assisted => "{ffff00}ASSIST ON{}  DOWN KEY: CHANGE",
+        RunKind::Unranked => "{ffff00}UNRANKED{}  DOWN KEY: CHANGE",
+        RunKind::Ranked => "DOWN KEY: ASSIST OPTIONS",
+    };
+    draw_text(renderer, "font", [&Vec2I::new(0, 28 * 8), &Vec2I::new(WIDTH, 8)],
+              &TextStyle::centered((128, 128, 128)), msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::framework::VKey;
+    use std::collections::HashMap;
+
+    #[derive(Default)]
+    struct MemorySystem {
+        values: HashMap<String, u32>,
+        blobs: HashMap<String, Blob>,
+    }
+
+    impl SystemTrait for MemorySystem {
+        fn get_u32(&self, key: &str) -> Option<u32> { self.values.get(key).copied() }
+        fn set_u32(&mut self, key: &str, value: u32) { self.values.insert(key.to_string(), value); }
+        fn get_blob(&self, namespace: &str) -> Option<Blob> { self.blobs.get(namespace).cloned() }
+        fn set_blob(&mut self, namespace: &str, blob: &Blob) { self.blobs.insert(namespace.to_string(), blob.clone()); }
+        fn is_touch_device(&self) -> bool { false }
+        fn play_se(&mut self, _channel: u32, _filename: &str) {}
+    }
+
+    fn press(menu: &mut AssistMenu, options: &mut AssistOptions, key: VKey) -> bool {
+        let mut pad = Pad::default();
+        pad.on_key(key, true);
+        pad.update();
+        menu.update(&pad, options)
+    }
+
+    #[test]
+    fn test_menu() {
+        let mut menu = AssistMenu::default();
+        let mut options = AssistOptions::default();
+        assert!(!options.is_enabled());
+
+        assert!(press(&mut menu, &mut options, VKey::Right));
+        assert_eq!(75, options.speed);
+        assert!(press(&mut menu, &mut options, VKey::Left));
+        assert!(press(&mut menu, &mut options, VKey::Left));
+        assert_eq!(50, options.speed);
+        assert!(options.is_enabled());
+
+        assert!(press(&mut menu, &mut options, VKey::Down));
+        assert!(press(&mut menu, &mut options, VKey::Space));
+        assert!(options.invincible);
+
+        assert!(press(&mut menu, &mut options, VKey::Up));
+        assert!(press(&mut menu, &mut options, VKey::Up));
+        assert!(!press(&mut menu, &mut options, VKey::Space));  // Done.
+    }
+
+    #[test]
+    fn test_difficulty() {
+        let mut menu = AssistMenu::default();
+        let mut options = AssistOptions::default();
+        for _ in 0..ITEM_DIFFICULTY {
+            assert!(press(&mut menu, &mut options, VKey::Down));
+        }
+
+        assert!(press(&mut menu, &mut options, VKey::Right));
+        assert_eq!(Difficulty::Easy, options.difficulty);
+        assert!(!options.is_enabled());
+        assert_eq!(RunKind::Unranked, options.run_kind());
+
+        assert!(press(&mut menu, &mut options, VKey::Right));
+        assert_eq!(Difficulty::Normal, options.difficulty);
+        assert_eq!(RunKind::Ranked, options.run_kind());
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let mut system = MemorySystem::default();
+        let options = AssistOptions { speed: 75, auto_fire: true, difficulty: Difficulty::Hard, boss_interval: 5, ..AssistOptions::default() };
+        options.save(&mut system);
+        assert!(system.values.is_empty());
+        assert_eq!(options, AssistOptions::load(&system));
+
+        // Blob of another version is not read.
+        system.set_blob(NS_ASSIST, &Blob::new(ASSIST_VERSION + 1, json!({ KEY_ASSIST_SPEED: 50 })));
+        assert_eq!(AssistOptions::default(), AssistOptions::load(&system));
+    }
+
+    #[test]
+    fn test_load_old_values() {
+        let mut system = MemorySystem::default();
+        system.set_u32(KEY_ASSIST_INVINCIBLE, 1);
+        system.set_u32(KEY_ARRANGE, 1);
+        let options = AssistOptions::load(&system);
+        assert!(options.invincible);
+        assert!(options.arrange);
+    }
+
+    #[test]
+    fn test_run_kind() {
+        let mut options = AssistOptions { arrange: true, ..AssistOptions::default() };
+        assert!(!options.is_enabled());
+        assert_eq!(RunKind::Unranked, options.run_kind());
+
+        options.auto_fire = true;
+        assert_eq!(RunKind::Assisted, options.run_kind());
+    }
+}
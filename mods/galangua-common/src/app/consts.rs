@@ -6,24 +6,31 @@ pub const WIDTH: i32 = 224;
 pub const HEIGHT: i32 = 288;
 pub const ANGLE_DIV: i32 = 24;
 
+pub const NS_SCORES: &str = "scores";
+pub const SCORES_VERSION: u32 = 1;
+pub const NS_ASSIST: &str = "assist";
+pub const ASSIST_VERSION: u32 = 1;
+
+// Saved as u32 values before blobs, read when no blob is saved yet.
+// Also used as the field names in the blobs.
 pub const KEY_HIGH_SCORE: &str = "highScore";
 pub const DEFAULT_HIGH_SCORE: u32 = 1000;
+pub const KEY_ASSIST_SPEED: &str = "assistSpeed";
+pub const KEY_ASSIST_INVINCIBLE: &str = "assistInvincible";
+pub const KEY_ASSIST_AUTO_FIRE: &str = "assistAutoFire";
+pub const KEY_DIFFICULTY: &str = "difficulty";
+pub const KEY_ARRANGE: &str = "arrange";
+pub const KEY_BOSS_INTERVAL: &str = "bossInterval";
 
 pub const DEFAULT_LEFT_SHIP: u32 = 3;
 
 pub const CENTER_X: i32 = WIDTH * ONE / 2;
 pub const PLAYER_Y: i32 = (HEIGHT - 16 - 8) * ONE;
+pub const COOP_PLAYER_X: [i32; 2] = [CENTER_X - 48 * ONE, CENTER_X + 48 * ONE];
 pub const PLAYER_SPEED: i32 = 3 * ONE / 2;
 pub const MYSHOT_SPEED: i32 = 6 * ONE;
 
 pub const MAX_ENE_SHOT_COUNT: usize = 12;
-pub const ENE_SHOT_SPEED1: i32 = 25 * ONE / 10;
-pub const ENE_SHOT_SPEED2: i32 = 40 * ONE / 10;
-
-pub const EXTEND_FIRST_SCORE: u32 = 20_000;
-pub const EXTEND_AFTER_SCORE: u32 = 50_000;
-
-pub const RUSH_THRESHOLD: u32 = 5;
 
 pub const OWL_DESTROY_SHOT_WAIT: u32 = 3 * 60;
 
@@ -46,3 +53,6 @@ pub const SE_TRACTOR_BEAM1: &str = "assets/audio/se_pipipi_2";
 pub const SE_TRACTOR_BEAM2: &str = "assets/audio/se_pipipi_1";
 pub const SE_EXTEND_SHIP: &str = "assets/audio/jingle_1up";
 pub const SE_RECAPTURE: &str = "assets/audio/jingle_item01";
+pub const SE_GET_ITEM: &str = "assets/audio/se_get_1";
+pub const SE_ITEM_BOMB: &str = "assets/audio/se_gyuin";
+pub const SE_SHIELD: &str = "assets/audio/se_pow_1";
@@ -1,9 +1,9 @@
 use rand::seq::SliceRandom;
 use rand::{RngExt, SeedableRng};
 use rand_xoshiro::Xoshiro128Plus;
-use std::cmp::min;
 
 use crate::app::game::appearance_table::*;
+use crate::app::game::difficulty::{assault_counts, shot_enable_counts, DifficultyProfile};
 use crate::app::game::traj::Traj;
 use crate::app::game::traj_command::TrajCommand;
 use crate::app::game::{EnemyType, FormationIndex};
@@ -67,6 +67,7 @@ pub trait Accessor {
 
 pub struct AppearanceManager {
     stage: u16,
+    level: u16,
     paused: bool,
     wait_stationary: bool,
     wait: u32,
@@ -76,12 +77,14 @@ pub struct AppearanceManager {
     orders: Vec<Info>,
     orders_ptr: &'static [Info],
     captured_fighter: Option<FormationIndex>,
+    survivors: Option<Vec<FormationIndex>>,  // Resumed stage: Only these come back.
 }
 
 impl Default for AppearanceManager {
     fn default() -> Self {
         Self {
             stage: 0,
+            level: 0,
             paused: false,
             wait_stationary: false,
             wait: 0,
@@ -91,18 +94,45 @@ impl Default for AppearanceManager {
             orders: Vec::new(),
             orders_ptr: &[],
             captured_fighter: None,
+            survivors: None,
         }
     }
 }
 
 impl AppearanceManager {
-    pub fn restart(&mut self, stage: u16, captured_fighter: Option<FormationIndex>) {
+    pub fn restart(&mut self, stage: u16, captured_fighter: Option<FormationIndex>, difficulty: &DifficultyProfile) {
         *self = Self::default();
         self.stage = stage;
+        self.level = difficulty.level(stage);
         self.done = false;
         self.captured_fighter = captured_fighter;
     }
 
+    // Restarts a stage in the middle, survivors fly into the formation again.
+    pub fn resume(
+        &mut self, stage: u16, survivors: &[FormationIndex], captured_fighter: Option<FormationIndex>,
+        difficulty: &DifficultyProfile,
+    ) {
+        self.restart(stage, captured_fighter, difficulty);
+        self.survivors = Some(survivors.to_vec());
+    }
+
+    // Formation indices which are not spawned yet, assault enemies and captured fighter are excluded.
+    pub fn pending_indices(&self) -> Vec<FormationIndex> {
+        if self.done {
+            return Vec::new();
+        }
+
+        let next_unit = if self.orders.is_empty() { self.unit } else { self.unit + 1 };
+        let rest = &ORDER[(next_unit * 8).min(UNIT_COUNT * 8) as usize..];
+        self.orders_ptr.iter()
+            .filter(|info| info.enemy_type != EnemyType::CapturedFighter)
+            .map(|info| info.fi)
+            .chain(rest.iter().copied())
+            .filter(|fi| fi.1 < ASSAULT_FORMATION_Y && self.is_survivor(fi))
+            .collect()
+    }
+
     pub fn pause(&mut self, value: bool) {
         self.paused = value;
     }
@@ -135,6 +165,11 @@ impl AppearanceManager {
 
             if self.orders.is_empty() {
                 self.set_orders();
+                if self.orders.is_empty() {
+                    // All of the unit are destroyed in the resumed stage.
+                    self.unit += 1;
+                    return None;
+                }
                 // orders is owned by vec, so it lives as long as self and not worry about that.
                 self.orders_ptr = unsafe { extend_lifetime(&self.orders) };
 
@@ -183,8 +218,11 @@ impl AppearanceManager {
     fn create_orders(&mut self) {
         let base = self.unit * 8;
         let entry = &UNIT_TABLE[(self.stage as usize) % UNIT_TABLE.len()][self.unit as usize];
-        let assault_count = ASSAULT_TABLE[min(self.stage as usize, ASSAULT_TABLE.len() - 1)]
-            [self.unit as usize] as usize;
+        let assault_count = if self.survivors.is_some() {
+            0
+        } else {
+            assault_counts(self.level)[self.unit as usize] as usize
+        };
 
         let div = match entry.pat {
             0 => {
@@ -247,6 +285,14 @@ impl AppearanceManager {
             recalc_order_time(&mut self.orders, STEP_WAIT, div);
         }
 
+        if self.survivors.is_some() {
+            let orders = std::mem::take(&mut self.orders);
+            self.orders = orders.into_iter()
+                .filter(|info| self.is_survivor(&info.fi))
+                .collect();
+            recalc_order_time(&mut self.orders, STEP_WAIT, div);
+        }
+
         if self.unit == UNIT_COUNT - 1 {
             if let Some(fi) = self.captured_fighter {
                 let mut info = self.create_info(fi, self.orders.len() as u32);
@@ -256,6 +302,11 @@ impl AppearanceManager {
         }
     }
 
+    fn is_survivor(&self, fi: &FormationIndex) -> bool {
+        self.survivors.as_ref()
+            .is_none_or(|survivors| survivors.contains(fi))
+    }
+
     fn create_info(&self, fi: FormationIndex, count: u32) -> Info {
         let entry = &UNIT_TABLE[(self.stage as usize) % UNIT_TABLE.len()][self.unit as usize];
         let enemy_types = &ENEMY_TYPE_TABLE[(self.unit * 2) as usize ..
@@ -290,7 +341,7 @@ impl AppearanceManager {
     }
 
     fn set_shot_enables(&mut self) {
-        let count = SHOT_ENABLE_TABLE[min(self.stage as usize, SHOT_ENABLE_TABLE.len() - 1)][self.unit as usize];
+        let count = shot_enable_counts(self.level)[self.unit as usize].min(self.orders.len() as u32);
         if count == 0 {
             return;
         }
@@ -319,3 +370,29 @@ fn recalc_order_time(orders: &mut [Info], step_wait: u32, div: u32) {
         order.time = step_wait * (i as u32 / div);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Stationary;
+    impl Accessor for Stationary {
+        fn is_stationary(&self) -> bool { true }
+    }
+
+    #[test]
+    fn test_resume() {
+        let survivors = [ORDER[3], ORDER[20], ORDER[39]];
+        let mut appearance_manager = AppearanceManager::default();
+        appearance_manager.resume(1, &survivors, None, &DifficultyProfile::default());
+        assert!(appearance_manager.pending_indices() == survivors);
+
+        let mut spawned = Vec::new();
+        while !appearance_manager.done {
+            if let Some(new_borns) = appearance_manager.update(&Stationary) {
+                spawned.extend(new_borns.iter().map(|e| e.fi));
+            }
+        }
+        assert!(spawned == survivors);
+    }
+}
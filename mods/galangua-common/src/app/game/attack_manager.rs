@@ -1,13 +1,22 @@
-use array_macro::*;
-use rand::seq::SliceRandom;
 use rand::{RngExt, SeedableRng};
 use rand_xoshiro::Xoshiro128Plus;
 
-use crate::app::game::formation_table::{X_COUNT, Y_COUNT};
+use crate::app::game::attack_strategy::{ArcadeStrategy, AttackStrategy, Escorts};
+use crate::app::game::difficulty::{DifficultyProfile, MAX_ATTACKER_COUNT};
+use crate::app::game::transform::{TRANSFORM_COUNT, TRANSFORM_FORMATION_Y, TRANSFORM_START_STAGE};
 use crate::app::game::FormationIndex;
 
-const MAX_ATTACKER_COUNT: usize = 3;
-const WAIT: u32 = 30;
+// Rows of bees in formation.
+const BEE_ROWS: [u8; 2] = [4, 5];
+// One of this count of bee attacks transforms.
+const TRANSFORM_RATE: u32 = 4;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum AttackType {
+    Normal,
+    Capture,
+    Transform,
+}
 
 pub trait Accessor {
     fn can_capture_attack(&self) -> bool;
@@ -16,18 +25,51 @@ pub trait Accessor {
     fn is_enemy_formation_at(&self, formation_index: &FormationIndex) -> bool;
 }
 
-#[derive(Default)]
 pub struct AttackManager {
     enable: bool,
     paused: bool,
     wait: u32,
     attackers: [Option<FormationIndex>; MAX_ATTACKER_COUNT],
-    cycle: u32,
+    attacker_count: usize,
+    stage: u16,
+    transformer: Option<FormationIndex>,
+    strategy: Box<dyn AttackStrategy>,
+}
+
+impl Default for AttackManager {
+    fn default() -> Self {
+        Self::new(Box::new(ArcadeStrategy::default()))
+    }
 }
 
 impl AttackManager {
-    pub fn restart(&mut self, _stage: u16) {
-        *self = Self::default();
+    pub fn new(strategy: Box<dyn AttackStrategy>) -> Self {
+        Self {
+            enable: false,
+            paused: false,
+            wait: 0,
+            attackers: Default::default(),
+            attacker_count: 0,
+            stage: 0,
+            transformer: None,
+            strategy,
+        }
+    }
+
+    // Strategy is kept over stages.
+    pub fn set_strategy(&mut self, strategy: Box<dyn AttackStrategy>) {
+        self.strategy = strategy;
+    }
+
+    pub fn restart(&mut self, stage: u16, difficulty: &DifficultyProfile) {
+        self.enable = false;
+        self.paused = false;
+        self.wait = 0;
+        self.attackers = Default::default();
+        self.attacker_count = self.strategy.attacker_count(difficulty).min(MAX_ATTACKER_COUNT);
+        self.stage = stage;
+        self.transformer = None;
+        self.strategy.restart();
     }
 
     pub fn set_enable(&mut self, value: bool) {
@@ -42,7 +84,7 @@ impl AttackManager {
         self.attackers.iter().all(|x| x.is_none())
     }
 
-    pub fn update(&mut self, accessor: &impl Accessor) -> Option<(FormationIndex, bool)> {
+    pub fn update(&mut self, accessor: &impl Accessor) -> Option<(FormationIndex, AttackType)> {
         self.check_liveness(accessor);
 
         if self.wait > 0 {
@@ -54,15 +96,18 @@ impl AttackManager {
             return None;
         }
 
-        let mut result: Option<(FormationIndex, bool)> = None;
-        if self.attackers.iter().any(|x| x.is_none()) {
+        let mut result: Option<(FormationIndex, AttackType)> = None;
+        if self.attackers[..self.attacker_count].iter().any(|x| x.is_none()) {
             result = self.pick_attacker(accessor);
-            self.wait = WAIT;
-            self.cycle += 1;
+            self.wait = self.strategy.wait();
         }
         result
     }
 
+    pub fn choose_escorts(&self, leader: &FormationIndex, accessor: &impl Accessor) -> Escorts {
+        self.strategy.choose_escorts(leader, accessor)
+    }
+
     pub fn put_attacker(&mut self, formation_index: &FormationIndex) {
         let slot_index = self.attackers.iter().position(|x| x.is_none()).unwrap();
         self.attackers[slot_index] = Some(*formation_index);
@@ -75,72 +120,37 @@ impl AttackManager {
                 *attacker_opt = None;
             }
         }
-    }
 
-    fn pick_attacker(&mut self, accessor: &impl Accessor) -> Option<(FormationIndex, bool)> {
-        let candidates = self.enum_sides(accessor);
-        match self.cycle % 3 {
-            2 => {
-                self.pick_random(&candidates, &mut [1])
-                    .map(|fi| (fi, (self.cycle / 3) & 1 != 0))
-                    .or_else(|| {
-                        self.pick_captured_fighter_as_attacker(accessor)
-                            .map(|fi| (fi, false))
-                    })
-            }
-            /*0 | 1 |*/ _ => {
-                self.pick_random(&candidates, &mut [2, 3, 4, 5])
-                    .map(|fi| (fi, false))
+        if let Some(fi) = &self.transformer {
+            if !accessor.is_enemy_live_at(fi) {
+                self.transformer = None;
             }
-        }.map(|(fi, capture_attack)| {
-            let capture_attack = capture_attack && accessor.can_capture_attack();
-            (fi, capture_attack)
-        })
-    }
-
-    fn pick_random(&mut self, candidates: &[Option<[u8; 2]>; Y_COUNT], rows: &mut [u32]) -> Option<FormationIndex> {
-        let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
-        rows.shuffle(&mut rng);
-        rows.iter()
-            .find_map(|&row| candidates[row as usize].map(|pos| (pos, row)))
-            .map(|(pos, row)| {
-                let index = rng.random_range(0..2);
-                FormationIndex(pos[index], row as u8)
-            })
+        }
     }
 
-    fn enum_sides(&mut self, accessor: &impl Accessor) -> [Option<[u8; 2]>; Y_COUNT] {
-        array![i => {
-            let is_formation = |j| -> Option<usize> {
-                let fi = FormationIndex(j as u8, i as u8);
-                if accessor.is_enemy_formation_at(&fi) {
-                    Some(j)
+    fn pick_attacker(&mut self, accessor: &impl Accessor) -> Option<(FormationIndex, AttackType)> {
+        self.strategy.pick_attacker(accessor)
+            .map(|(fi, capture_attack)| {
+                let attack_type = if capture_attack && accessor.can_capture_attack() {
+                    AttackType::Capture
+                } else if BEE_ROWS.contains(&fi.1) && self.can_transform(accessor) {
+                    self.transformer = Some(fi);
+                    AttackType::Transform
                 } else {
-                    None
-                }
-            };
-
-            (0..X_COUNT)
-                .find_map(is_formation)
-                .map(|l| {
-                    let r = (l..X_COUNT).rev()
-                        .find_map(is_formation)
-                        .unwrap_or(l);
-                    [l as u8, r as u8]
-                })
-        }; Y_COUNT]
+                    AttackType::Normal
+                };
+                (fi, attack_type)
+            })
     }
 
-    fn pick_captured_fighter_as_attacker(&mut self, accessor: &impl Accessor) -> Option<FormationIndex> {
-        accessor.captured_fighter_index()
-            .and_then(|fi| {
-                if accessor.is_enemy_formation_at(&fi) &&
-                    !accessor.is_enemy_live_at(&FormationIndex(fi.0, fi.1 + 1))
-                {
-                    Some(fi)
-                } else {
-                    None
-                }
-            })
+    // Only one trio is allowed at a time.
+    fn can_transform(&self, accessor: &impl Accessor) -> bool {
+        if self.stage < TRANSFORM_START_STAGE || self.transformer.is_some() ||
+            (0..TRANSFORM_COUNT as u8).any(|i| accessor.is_enemy_live_at(&FormationIndex(i, TRANSFORM_FORMATION_Y)))
+        {
+            return false;
+        }
+        let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
+        rng.random_range(0..TRANSFORM_RATE) == 0
     }
 }
@@ -0,0 +1,210 @@
+use array_macro::*;
+use rand::seq::SliceRandom;
+use rand::{RngExt, SeedableRng};
+use rand_xoshiro::Xoshiro128Plus;
+
+use crate::app::game::attack_manager::Accessor;
+use crate::app::game::difficulty::DifficultyProfile;
+use crate::app::game::formation_table::{X_COUNT, Y_COUNT};
+use crate::app::game::FormationIndex;
+
+// Butterflies escorting an owl, captured fighter is not counted.
+pub const MAX_ESCORT_COUNT: usize = 2;
+
+pub type Escorts = [Option<FormationIndex>; MAX_ESCORT_COUNT];
+
+// Decides which enemy attacks and when, `AttackManager` keeps track of attackers.
+pub trait AttackStrategy: Send + Sync {
+    fn restart(&mut self) {}
+
+    // Upper bound of concurrent attackers.
+    fn attacker_count(&self, difficulty: &DifficultyProfile) -> usize;
+
+    // Frames to wait after an attacker is picked.
+    fn wait(&self) -> u32;
+
+    // Returns attacker and whether it tries a capture attack.
+    fn pick_attacker(&mut self, accessor: &dyn Accessor) -> Option<(FormationIndex, bool)>;
+
+    fn choose_escorts(&self, leader: &FormationIndex, accessor: &dyn Accessor) -> Escorts;
+}
+
+#[derive(Clone)]
+pub struct ArcadeParams {
+    pub attacker_count: Option<usize>,  // None: follows difficulty.
+    pub wait: u32,
+    pub wave_length: u32,               // Attacks in a wave, the last one is an owl.
+    pub zako_rows: &'static [u32],
+    pub owl_rows: &'static [u32],
+    pub capture_rate: u32,              // One of this count of owl attacks tries capturing.
+    pub escort_count: usize,
+}
+
+impl Default for ArcadeParams {
+    fn default() -> Self {
+        Self {
+            attacker_count: None,
+            wait: 30,
+            wave_length: 3,
+            zako_rows: &[2, 3, 4, 5],
+            owl_rows: &[1],
+            capture_rate: 2,
+            escort_count: MAX_ESCORT_COUNT,
+        }
+    }
+}
+
+// Original scheduler: zako from both sides of the formation, then an owl or the captured fighter.
+#[derive(Default)]
+pub struct ArcadeStrategy {
+    params: ArcadeParams,
+    cycle: u32,
+}
+
+impl ArcadeStrategy {
+    pub fn new(params: ArcadeParams) -> Self {
+        Self {
+            params,
+            cycle: 0,
+        }
+    }
+}
+
+impl AttackStrategy for ArcadeStrategy {
+    fn restart(&mut self) {
+        self.cycle = 0;
+    }
+
+    fn attacker_count(&self, difficulty: &DifficultyProfile) -> usize {
+        self.params.attacker_count.unwrap_or_else(|| difficulty.attacker_count())
+    }
+
+    fn wait(&self) -> u32 {
+        self.params.wait
+    }
+
+    fn pick_attacker(&mut self, accessor: &dyn Accessor) -> Option<(FormationIndex, bool)> {
+        let wave_length = self.params.wave_length.max(1);
+        let wave = self.cycle / wave_length;
+        let is_owl_turn = self.cycle % wave_length == wave_length - 1;
+        self.cycle += 1;
+
+        let candidates = enum_sides(accessor);
+        if is_owl_turn {
+            let capture_attack = self.params.capture_rate > 0 &&
+                wave % self.params.capture_rate == self.params.capture_rate - 1;
+            pick_random(&candidates, self.params.owl_rows)
+                .map(|fi| (fi, capture_attack))
+                .or_else(|| {
+                    pick_captured_fighter_as_attacker(accessor)
+                        .map(|fi| (fi, false))
+                })
+        } else {
+            pick_random(&candidates, self.params.zako_rows)
+                .map(|fi| (fi, false))
+        }
+    }
+
+    fn choose_escorts(&self, leader: &FormationIndex, accessor: &dyn Accessor) -> Escorts {
+        let mut escorts = Escorts::default();
+        let xs = [leader.0.checked_sub(1), Some(leader.0 + 1)];
+        let formations = xs.iter()
+            .flatten()
+            .filter(|&&x| (x as usize) < X_COUNT)
+            .map(|&x| FormationIndex(x, leader.1 + 1))
+            .filter(|fi| accessor.is_enemy_formation_at(fi))
+            .take(self.params.escort_count);
+        for (slot, fi) in escorts.iter_mut().zip(formations) {
+            *slot = Some(fi);
+        }
+        escorts
+    }
+}
+
+fn pick_random(candidates: &[Option<[u8; 2]>; Y_COUNT], rows: &[u32]) -> Option<FormationIndex> {
+    let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
+    let mut rows = rows.to_vec();
+    rows.shuffle(&mut rng);
+    rows.iter()
+        .find_map(|&row| candidates[row as usize].map(|pos| (pos, row)))
+        .map(|(pos, row)| {
+            let index = rng.random_range(0..2);
+            FormationIndex(pos[index], row as u8)
+        })
+}
+
+// Leftmost and rightmost enemies in formation for each row.
+fn enum_sides(accessor: &dyn Accessor) -> [Option<[u8; 2]>; Y_COUNT] {
+    array![i => {
+        let is_formation = |j| -> Option<usize> {
+            let fi = FormationIndex(j as u8, i as u8);
+            if accessor.is_enemy_formation_at(&fi) {
+                Some(j)
+            } else {
+                None
+            }
+        };
+
+        (0..X_COUNT)
+            .find_map(is_formation)
+            .map(|l| {
+                let r = (l..X_COUNT).rev()
+                    .find_map(is_formation)
+                    .unwrap_or(l);
+                [l as u8, r as u8]
+            })
+    }; Y_COUNT]
+}
+
+fn pick_captured_fighter_as_attacker(accessor: &dyn Accessor) -> Option<FormationIndex> {
+    accessor.captured_fighter_index()
+        .and_then(|fi| {
+            if accessor.is_enemy_formation_at(&fi) &&
+                !accessor.is_enemy_live_at(&FormationIndex(fi.0, fi.1 + 1))
+            {
+                Some(fi)
+            } else {
+                None
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FullFormation;
+    impl Accessor for FullFormation {
+        fn can_capture_attack(&self) -> bool { true }
+        fn captured_fighter_index(&self) -> Option<FormationIndex> { None }
+        fn is_enemy_live_at(&self, _formation_index: &FormationIndex) -> bool { true }
+        fn is_enemy_formation_at(&self, formation_index: &FormationIndex) -> bool {
+            (formation_index.1 as usize) < Y_COUNT
+        }
+    }
+
+    #[test]
+    fn test_arcade_wave() {
+        let mut strategy = ArcadeStrategy::default();
+        for wave in 0..4 {
+            for _ in 0..2 {
+                let (fi, capture_attack) = strategy.pick_attacker(&FullFormation).unwrap();
+                assert!(fi.1 >= 2 && !capture_attack);
+            }
+            let (fi, capture_attack) = strategy.pick_attacker(&FullFormation).unwrap();
+            assert_eq!(1, fi.1);
+            assert_eq!(wave % 2 == 1, capture_attack);
+        }
+    }
+
+    #[test]
+    fn test_escorts() {
+        let strategy = ArcadeStrategy::default();
+        let escorts = strategy.choose_escorts(&FormationIndex(4, 1), &FullFormation);
+        assert!(escorts == [Some(FormationIndex(3, 2)), Some(FormationIndex(5, 2))]);
+
+        let strategy = ArcadeStrategy::new(ArcadeParams { escort_count: 1, ..ArcadeParams::default() });
+        let escorts = strategy.choose_escorts(&FormationIndex(4, 1), &FullFormation);
+        assert!(escorts == [Some(FormationIndex(3, 2)), None]);
+    }
+}
@@ -0,0 +1,206 @@
+use counted_array::counted_array;
+
+use crate::app::consts::*;
+use crate::app::game::traj_command::TrajCommand;
+use crate::app::game::traj_command_table::*;
+use crate::app::game::transform::TRANSFORM_FORMATION_Y;
+use crate::app::game::FormationIndex;
+use crate::framework::types::Vec2I;
+use crate::framework::RendererTrait;
+use crate::util::math::{round_vec, ONE};
+
+pub const BOSS_HOME_POS: Vec2I = Vec2I::new(CENTER_X, 64 * ONE);
+// Slot of transformed enemies, they don't appear in boss stages.
+pub const BOSS_FORMATION_INDEX: FormationIndex = FormationIndex(0, TRANSFORM_FORMATION_Y);
+
+pub const BOSS_COLL_OFFSET: Vec2I = Vec2I::new(-32, -10);
+pub const BOSS_COLL_SIZE: Vec2I = Vec2I::new(64, 28);
+
+const BASE_LIFE: u32 = 40;
+// Crashing fighter doesn't destroy the boss at once.
+const MAX_DAMAGE: u32 = 8;
+
+const BAR_X: i32 = 4 * 8;
+const BAR_Y: i32 = 3 * 8 + 2;
+const BAR_WIDTH: i32 = WIDTH - 8 * 8;
+
+const DEFEAT_INTERVAL: u32 = 12;
+const BONUS_FRAMES: u32 = 90;
+
+const BOSS_PHASE_TABLES: [&[TrajCommand]; 3] = [
+    &BOSS_PHASE1_TABLE,
+    &BOSS_PHASE2_TABLE,
+    &BOSS_PHASE3_TABLE,
+];
+
+struct BossPart {
+    sprites: [&'static str; 2],
+    offset: Vec2I,  // Center of the sprite, in dots.
+}
+
+// Parts explode in this order, the core is the last.
+counted_array!(const BOSS_PARTS: [BossPart; _] = [
+    BossPart { sprites: ["stingray1", "stingray2"], offset: Vec2I::new(-28, 4) },
+    BossPart { sprites: ["stingray1", "stingray2"], offset: Vec2I::new(28, 4) },
+    BossPart { sprites: ["gopher1", "gopher2"], offset: Vec2I::new(-9, 14) },
+    BossPart { sprites: ["gopher1", "gopher2"], offset: Vec2I::new(9, 14) },
+    BossPart { sprites: ["dman1", "dman2"], offset: Vec2I::new(-15, -4) },
+    BossPart { sprites: ["dman1", "dman2"], offset: Vec2I::new(15, -4) },
+    BossPart { sprites: ["cpp11", "cpp12"], offset: Vec2I::new(0, -2) },
+]);
+
+const CORE_DAMAGED_SPRITES: [&str; 2] = ["cpp21", "cpp22"];
+
+// Boss stage comes every `interval` stages, 0 means never.
+pub fn is_boss_stage(stage: u16, interval: u16) -> bool {
+    interval > 0 && (stage as u32 + 1).is_multiple_of(interval as u32)
+}
+
+#[derive(Clone)]
+pub struct BossLife {
+    life: u32,
+    max_life: u32,
+}
+
+impl BossLife {
+    pub fn new(level: u16) -> Self {
+        let max_life = BASE_LIFE + level as u32 / 2;
+        Self { life: max_life, max_life }
+    }
+
+    // Returns true when destroyed.
+    pub fn set_damage(&mut self, power: u32) -> bool {
+        self.life = self.life.saturating_sub(power.min(MAX_DAMAGE));
+        self.life == 0
+    }
+
+    // Goes to the next phase at each third of the life.
+    pub fn phase(&self) -> usize {
+        let n = BOSS_PHASE_TABLES.len() as u32;
+        (n - 1 - (self.life * n).saturating_sub(1) / self.max_life) as usize
+    }
+
+    pub fn phase_table(&self) -> &'static [TrajCommand] {
+        BOSS_PHASE_TABLES[self.phase()]
+    }
+
+    pub fn draw_bar(&self, renderer: &mut (impl RendererTrait + ?Sized)) {
+        if self.life == 0 {
+            return;
+        }
+        let width = BAR_WIDTH * self.life as i32 / self.max_life as i32;
+        renderer.set_draw_color(96, 0, 0);
+        renderer.fill_rect(Some([&Vec2I::new(BAR_X, BAR_Y), &Vec2I::new(BAR_WIDTH, 4)]));
+        renderer.set_draw_color(255, 64, 64);
+        renderer.fill_rect(Some([&Vec2I::new(BAR_X, BAR_Y), &Vec2I::new(width, 4)]));
+    }
+}
+
+// Draws parts which are not broken yet.
+pub fn draw_boss(
+    renderer: &mut (impl RendererTrait + ?Sized), pos: &Vec2I, pat: usize, life: &BossLife,
+    broken_parts: usize,
+) {
+    let pos = round_vec(pos);
+    let damaged = life.phase() == BOSS_PHASE_TABLES.len() - 1;
+    for (i, part) in BOSS_PARTS.iter().enumerate().skip(broken_parts) {
+        let sprite = if damaged && i == BOSS_PARTS.len() - 1 { CORE_DAMAGED_SPRITES[pat] } else { part.sprites[pat] };
+        renderer.set_sprite_texture_color_mod(sprite, 255, 255, 255);
+        renderer.draw_sprite(sprite, &(&(&pos + &part.offset) - &Vec2I::new(8, 8)));
+    }
+}
+
+pub enum BossDefeatEvent {
+    ExplodePart(Vec2I),
+    Bonus,
+}
+
+// Sequence after the boss is shot down: parts explode one by one, then the bonus is earned.
+#[derive(Clone, Default)]
+pub struct BossDefeat {
+    count: u32,
+    bonus: u32,
+}
+
+impl BossDefeat {
+    pub fn new(bonus: u32) -> Self {
+        Self { count: 0, bonus }
+    }
+
+    pub fn update(&mut self, pos: &Vec2I) -> Option<BossDefeatEvent> {
+        let count = self.count;
+        self.count += 1;
+        if !count.is_multiple_of(DEFEAT_INTERVAL) {
+            return None;
+        }
+        match (count / DEFEAT_INTERVAL) as usize {
+            i if i < BOSS_PARTS.len() => Some(BossDefeatEvent::ExplodePart(pos + &(&BOSS_PARTS[i].offset * ONE))),
+            i if i == BOSS_PARTS.len() => Some(BossDefeatEvent::Bonus),
+            _ => None,
+        }
+    }
+
+    pub fn broken_parts(&self) -> usize {
+        std::cmp::min(self.count.div_ceil(DEFEAT_INTERVAL) as usize, BOSS_PARTS.len())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.count >= BOSS_PARTS.len() as u32 * DEFEAT_INTERVAL + BONUS_FRAMES
+    }
+
+    pub fn draw_bonus(&self, renderer: &mut (impl RendererTrait + ?Sized), pos: &Vec2I) {
+        if self.count <= BOSS_PARTS.len() as u32 * DEFEAT_INTERVAL {
+            return;
+        }
+        let text = format!("{}", self.bonus);
+        let pos = round_vec(pos);
+        renderer.set_texture_color_mod("font", 255, 255, 0);
+        renderer.draw_str("font", pos.x - text.len() as i32 * 4, pos.y - 4, &text);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_boss_stage() {
+        assert!(!is_boss_stage(4, 0));
+        assert!(!is_boss_stage(3, 5));
+        assert!(is_boss_stage(4, 5));
+        assert!(is_boss_stage(9, 5));
+    }
+
+    #[test]
+    fn test_phase() {
+        let mut life = BossLife::new(0);
+        assert_eq!(0, life.phase());
+        for _ in 0..BASE_LIFE / 2 {
+            assert!(!life.set_damage(1));
+        }
+        assert_eq!(1, life.phase());
+        for _ in 0..BASE_LIFE / 2 - MAX_DAMAGE - 1 {
+            assert!(!life.set_damage(1));
+        }
+        assert_eq!(2, life.phase());
+        assert!(!life.set_damage(100));
+        assert!(life.set_damage(100));
+    }
+
+    #[test]
+    fn test_defeat() {
+        let mut defeat = BossDefeat::default();
+        let mut exploded = 0;
+        let mut bonus = 0;
+        while !defeat.is_done() {
+            match defeat.update(&BOSS_HOME_POS) {
+                Some(BossDefeatEvent::ExplodePart(_)) => exploded += 1,
+                Some(BossDefeatEvent::Bonus) => bonus += 1,
+                None => {}
+            }
+        }
+        assert_eq!(BOSS_PARTS.len(), exploded);
+        assert_eq!(1, bonus);
+        assert_eq!(BOSS_PARTS.len(), defeat.broken_parts());
+    }
+}
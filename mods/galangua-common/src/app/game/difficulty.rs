@@ -0,0 +1,247 @@
+use counted_array::counted_array;
+use std::cmp::min;
+
+use crate::app::game::appearance_table::{ASSAULT_TABLE, SHOT_ENABLE_TABLE};
+use crate::util::math::ONE;
+
+// Level is a stage equivalent which enemy parameters are calculated from,
+// saturated at this value.
+pub const MAX_LEVEL: u16 = 64;
+
+const ENE_SHOT_SPEED1: i32 = 25 * ONE / 10;
+const ENE_SHOT_SPEED2: i32 = 40 * ONE / 10;
+const MAX_ACCELERATE_SPEED: i32 = 5 * ONE;
+
+// Upper bound of `attacker_count` in all difficulties.
+pub const MAX_ATTACKER_COUNT: usize = 4;
+
+// Dynamic rank steps.
+const RANK_UP_STAGE_CLEAR: i32 = 1;
+const RANK_DOWN_SHIP_LOST: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Arcade,  // Original curve, without dynamic rank.
+}
+
+counted_array!(pub const DIFFICULTIES: [Difficulty; _] = [
+    Difficulty::Easy,
+    Difficulty::Normal,
+    Difficulty::Hard,
+    Difficulty::Arcade,
+]);
+
+impl Difficulty {
+    pub fn name(self) -> &'static str {
+        match self {
+            Difficulty::Easy => "EASY",
+            Difficulty::Normal => "NORMAL",
+            Difficulty::Hard => "HARD",
+            Difficulty::Arcade => "ARCADE",
+        }
+    }
+
+    pub fn from_u32(value: u32) -> Option<Self> {
+        DIFFICULTIES.get(value as usize).copied()
+    }
+
+    // Curve easier than the arcade game, the high score is not updated.
+    pub fn is_below_arcade(self) -> bool {
+        let (params, arcade) = (self.params(), Difficulty::Arcade.params());
+        params.level_rate < arcade.level_rate || params.level_offset < arcade.level_offset
+    }
+
+    fn params(self) -> &'static Params {
+        &PARAMS[self as usize]
+    }
+}
+
+struct Params {
+    level_rate: u32,  // Level per stage, in percent.
+    level_offset: i32,
+    max_rank: i32,
+    attacker_count: usize,
+    rush_threshold: u32,
+}
+
+const PARAMS: [Params; 4] = [
+    // Easy
+    Params {
+        level_rate: 50,
+        level_offset: 0,
+        max_rank: 2,
+        attacker_count: 2,
+        rush_threshold: 3,
+    },
+    // Normal
+    Params {
+        level_rate: 100,
+        level_offset: 0,
+        max_rank: 4,
+        attacker_count: 3,
+        rush_threshold: 5,
+    },
+    // Hard
+    Params {
+        level_rate: 150,
+        level_offset: 4,
+        max_rank: 8,
+        attacker_count: 4,
+        rush_threshold: 8,
+    },
+    // Arcade
+    Params {
+        level_rate: 100,
+        level_offset: 0,
+        max_rank: 0,
+        attacker_count: 3,
+        rush_threshold: 5,
+    },
+];
+
+// Tunable difficulty, chosen difficulty with rank which rises while the player is doing well.
+#[derive(Clone)]
+pub struct DifficultyProfile {
+    difficulty: Difficulty,
+    rank: i32,
+}
+
+impl Default for DifficultyProfile {
+    fn default() -> Self {
+        Self::new(Difficulty::Arcade)
+    }
+}
+
+impl DifficultyProfile {
+    pub fn new(difficulty: Difficulty) -> Self {
+        Self {
+            difficulty,
+            rank: 0,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn rank(&self) -> i32 {
+        self.rank
+    }
+
+    pub fn level(&self, stage: u16) -> u16 {
+        let params = self.difficulty.params();
+        let level = (stage as u32 * params.level_rate / 100) as i32 + params.level_offset + self.rank;
+        level.clamp(0, MAX_LEVEL as i32) as u16
+    }
+
+    pub fn attacker_count(&self) -> usize {
+        self.difficulty.params().attacker_count
+    }
+
+    pub fn rush_threshold(&self) -> u32 {
+        self.difficulty.params().rush_threshold
+    }
+
+    // Arcade keeps the original aimed shots for regular attackers, see `TrajCommand::ExtraPattern`.
+    pub fn is_shot_pattern_enabled(&self) -> bool {
+        self.difficulty != Difficulty::Arcade
+    }
+
+    pub fn stage_cleared(&mut self) {
+        self.add_rank(RANK_UP_STAGE_CLEAR);
+    }
+
+    pub fn ship_lost(&mut self) {
+        self.add_rank(-RANK_DOWN_SHIP_LOST);
+    }
+
+    fn add_rank(&mut self, add: i32) {
+        let max_rank = self.difficulty.params().max_rank;
+        self.rank = (self.rank + add).clamp(-max_rank, max_rank);
+    }
+}
+
+// Parameters depending on level.
+
+pub fn accelerated_speed(speed: i32, level: u16) -> i32 {
+    speed + (MAX_ACCELERATE_SPEED - speed) * level as i32 / MAX_LEVEL as i32
+}
+
+pub fn ene_shot_speed(level: u16) -> i32 {
+    let per = level as i32 * ONE / MAX_LEVEL as i32;
+    (ENE_SHOT_SPEED2 - ENE_SHOT_SPEED1) * per / ONE + ENE_SHOT_SPEED1
+}
+
+// Shot count while an enemy attacks.
+pub fn attack_shot_count(level: u16) -> u32 {
+    min(2 + level / 8, 5) as u32
+}
+
+// Frames between shots while an enemy attacks.
+pub fn attack_shot_interval(level: u16) -> u32 {
+    20 - attack_shot_count(level) * 2
+}
+
+pub fn assault_counts(level: u16) -> &'static [u32; 5] {
+    &ASSAULT_TABLE[min(level as usize, ASSAULT_TABLE.len() - 1)]
+}
+
+pub fn shot_enable_counts(level: u16) -> &'static [u32; 5] {
+    &SHOT_ENABLE_TABLE[min(level as usize, SHOT_ENABLE_TABLE.len() - 1)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arcade_level_is_stage() {
+        let profile = DifficultyProfile::default();
+        assert_eq!(0, profile.level(0));
+        assert_eq!(10, profile.level(10));
+        assert_eq!(MAX_LEVEL, profile.level(200));
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut profile = DifficultyProfile::new(Difficulty::Normal);
+        for _ in 0..10 {
+            profile.stage_cleared();
+        }
+        assert_eq!(4, profile.rank());
+        assert_eq!(14, profile.level(10));
+
+        profile.ship_lost();
+        assert_eq!(12, profile.level(10));
+
+        for _ in 0..10 {
+            profile.ship_lost();
+        }
+        assert_eq!(0, profile.level(2));
+    }
+
+    #[test]
+    fn test_below_arcade() {
+        assert!(Difficulty::Easy.is_below_arcade());
+        assert!(!Difficulty::Normal.is_below_arcade());
+        assert!(!Difficulty::Hard.is_below_arcade());
+        assert!(!Difficulty::Arcade.is_below_arcade());
+    }
+
+    #[test]
+    fn test_arcade_has_no_rank() {
+        let mut profile = DifficultyProfile::new(Difficulty::Arcade);
+        profile.stage_cleared();
+        profile.ship_lost();
+        assert_eq!(0, profile.rank());
+    }
+
+    #[test]
+    fn test_ene_shot_speed() {
+        assert_eq!(ENE_SHOT_SPEED1, ene_shot_speed(0));
+        assert_eq!(ENE_SHOT_SPEED2, ene_shot_speed(MAX_LEVEL));
+    }
+}
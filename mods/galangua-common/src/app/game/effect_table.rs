@@ -1,18 +1,55 @@
+use crate::app::game::tractor_beam_table::{TRACTOR_BEAM_ANIME_NAME, TRACTOR_BEAM_Y_OFFSET_TABLE};
 use crate::app::game::EarnedPointType;
+use crate::framework::sprite_sheet::{AnimationClip, AnimationTable, LoopMode};
+
+// Clips used when the sprite sheet does not define them, same as the ones in `chr.json`.
+pub fn builtin_animations() -> AnimationTable {
+    let mut table = AnimationTable::default();
+    for name in FLASH_ENEMY_ANIME_NAMES.iter() {
+        table.insert(name, once_clip(&[name], FLASH_ENEMY_FRAME));
+    }
+    for name in EARNED_POINT_ANIME_NAMES.iter() {
+        table.insert(name, once_clip(&[name], EARNED_POINT_FRAME));
+    }
+    table.insert(ENEMY_EXPLOSION_ANIME_NAME,
+                 once_clip(&["ene_exp1", "ene_exp2", "ene_exp3", "ene_exp4", "ene_exp5"], 4));
+    table.insert(PLAYER_EXPLOSION_ANIME_NAME,
+                 once_clip(&["pl_exp1", "pl_exp2", "pl_exp3", "pl_exp4"], 8));
+    let beams: Vec<String> = (0..TRACTOR_BEAM_Y_OFFSET_TABLE.len()).map(|i| format!("beam{:02}", i)).collect();
+    table.insert(TRACTOR_BEAM_ANIME_NAME, AnimationClip {
+        durations: vec![1; beams.len()],
+        frames: beams,
+        loop_mode: LoopMode::Once,
+    });
+    table
+}
+
+fn once_clip(frames: &[&str], duration: u32) -> AnimationClip {
+    AnimationClip {
+        frames: frames.iter().map(|s| s.to_string()).collect(),
+        durations: vec![duration; frames.len()],
+        loop_mode: LoopMode::Once,
+    }
+}
 
 pub const FLASH_ENEMY_FRAME: u32 = 2;
 
-pub const FLASH_ENEMY_SPRITE_NAMES: [&str; 4] = [
+pub const FLASH_ENEMY_ANIME_NAMES: [&str; 7] = [
     "gopher_flash",
     "dman_flash",
     "cpp_flash",
     "rustacean_flash",
+    "scorpion_flash",
+    "stingray_flash",
+    "flagship_flash",
 ];
 
 //
 
 pub fn to_earned_point_type(point: u32) -> Option<EarnedPointType> {
     match point {
+        3000 => Some(EarnedPointType::Point3000),
+        2000 => Some(EarnedPointType::Point2000),
         1600 => Some(EarnedPointType::Point1600),
         1000 => Some(EarnedPointType::Point1000),
         800 => Some(EarnedPointType::Point800),
@@ -21,16 +58,34 @@ pub fn to_earned_point_type(point: u32) -> Option<EarnedPointType> {
     }
 }
 
-pub const EARNED_POINT_FRAME: u32 = 64;
-pub const EARNED_POINT_SPRITE_TABLE: [[&str; 1]; 4] = [
-    ["pts1600"],
-    ["pts1000"],
-    ["pts800"],
-    ["pts400"],
+const EARNED_POINT_FRAME: u32 = 64;
+
+pub const EARNED_POINT_ANIME_NAMES: [&str; 6] = [
+    "pts3000",
+    "pts2000",
+    "pts1600",
+    "pts1000",
+    "pts800",
+    "pts400",
 ];
 
-pub const ENEMY_EXPLOSION_SPRITE_TABLE: [&str; 5] = ["ene_exp1", "ene_exp2", "ene_exp3", "ene_exp4", "ene_exp5"];
-pub const ENEMY_EXPLOSION_FRAME: u32 = 4;
+pub const ENEMY_EXPLOSION_ANIME_NAME: &str = "ene_exp";
+
+pub const PLAYER_EXPLOSION_ANIME_NAME: &str = "pl_exp";
 
-pub const PLAYER_EXPLOSION_SPRITE_TABLE: [&str; 4] = ["pl_exp1", "pl_exp2", "pl_exp3", "pl_exp4"];
-pub const PLAYER_EXPLOSION_FRAME: u32 = 8;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::asset_check::referenced_anime_names;
+    use crate::framework::sprite_sheet::load_animations;
+
+    #[test]
+    fn test_builtin_animations() {
+        let text = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets/chr.json")).unwrap();
+        let animations = load_animations(&text).unwrap();
+        let builtin = builtin_animations();
+        for name in referenced_anime_names() {
+            assert_eq!(animations.get(name), builtin.get(name).as_deref(), "{}", name);
+        }
+    }
+}
@@ -1,20 +1,23 @@
 use crate::app::consts::*;
 use crate::app::game::FormationIndex;
 use crate::framework::types::Vec2I;
-use crate::util::math::ONE;
+use crate::util::math::{ONE, SIN_TABLE};
 
 use super::formation_table::*;
 
-#[derive(Copy, Clone, PartialEq)]
-enum MovingPat {
-    Slide,
-    Scale,
-}
+const WAVE_HEIGHT: i32 = 6 * ONE;
+const ROTATE_RATE: i32 = ONE / 8;  // About 7 degrees at max.
+const SWEEP_X: i32 = 24 * ONE;
+const SWEEP_Y: i32 = 12 * ONE;
 
 pub struct Formation {
     xtbl: [i32; X_COUNT],
     ytbl: [i32; Y_COUNT],
+    base_ytbl: [i32; Y_COUNT],
+    column_dy: [i32; X_COUNT],
+    moving_pats: &'static [MovingPat],
     moving_pat: MovingPat,
+    moving_phase: usize,
     moving_count: u32,
     done_appearance: bool,
 }
@@ -24,19 +27,26 @@ impl Default for Formation {
         let mut formation = Self {
             xtbl: Default::default(),
             ytbl: Default::default(),
+            base_ytbl: Default::default(),
+            column_dy: Default::default(),
+            moving_pats: &[],
             moving_pat: MovingPat::Slide,
+            moving_phase: 0,
             moving_count: 0,
             done_appearance: false,
         };
-        formation.restart();
+        formation.restart(0);
         formation
     }
 }
 
 impl Formation {
-    pub fn restart(&mut self) {
+    pub fn restart(&mut self, stage: u16) {
+        let layout = formation_layout(stage);
         *self = Self {
+            moving_pats: layout.moving_pats,
             moving_pat: MovingPat::Slide,
+            moving_phase: 0,
             moving_count: 0,
             done_appearance: false,
             ..*self
@@ -44,10 +54,12 @@ impl Formation {
 
         for j in 0..X_COUNT {
             self.xtbl[j] = BASE_X_TABLE[j] * ONE;
+            self.column_dy[j] = layout.column_dy[j] * ONE;
         }
         for i in 0..Y_COUNT {
-            self.ytbl[i] = BASE_Y_TABLE[i] * ONE;
+            self.base_ytbl[i] = (layout.base_y + i as i32 * layout.row_pitch) * ONE;
         }
+        self.ytbl = self.base_ytbl;
     }
 
     pub fn done_appearance(&mut self) {
@@ -58,6 +70,27 @@ impl Formation {
         match self.moving_pat {
             MovingPat::Slide => self.update_formation_slide(),
             MovingPat::Scale => self.update_formation_scale(),
+            MovingPat::Wave | MovingPat::Rotate | MovingPat::Sweep => {}
+        }
+
+        self.moving_count += 1;
+        if (self.moving_count & 255) == 0 {
+            self.next_moving_pat();
+        }
+    }
+
+    // Patterns are switched when the formation is back to the base position.
+    fn next_moving_pat(&mut self) {
+        if self.moving_pat == MovingPat::Slide {
+            if !self.done_appearance {
+                return;
+            }
+        } else {
+            self.moving_phase += 1;
+        }
+        if let Some(&pat) = self.moving_pats.get(self.moving_phase % self.moving_pats.len().max(1)) {
+            self.moving_pat = pat;
+            self.moving_count = 0;
         }
     }
 
@@ -70,18 +103,12 @@ impl Formation {
         for i in 0..X_COUNT {
             self.xtbl[i] += dx;
         }
-
-        self.moving_count += 1;
-        if self.done_appearance && (self.moving_count & 255) == 0 {
-            self.moving_pat = MovingPat::Scale;
-            self.moving_count = 0;
-        }
     }
 
     fn update_formation_scale(&mut self) {
         let t = (self.moving_count as i32) & 255;
         let bx = WIDTH / 2;
-        let by = BASE_Y;
+        let by = self.base_ytbl[0] / ONE;
         let space = WIDTH - X_COUNT as i32 * 16;
         let factor_x = (space / 2 * ONE) / ((X_COUNT as i32 - 1) * 16 / 2);
         let factor_x = if t >= 128 { -factor_x } else { factor_x };
@@ -93,14 +120,62 @@ impl Formation {
         }
 
         for i in 0..Y_COUNT {
-            let pos_y = BASE_Y_TABLE[i];
+            let pos_y = self.base_ytbl[i] / ONE;
             self.ytbl[i] += (pos_y - by) * factor_y / 128;
         }
-
-        self.moving_count += 1;
     }
 
     pub fn pos(&self, index: &FormationIndex) -> Vec2I {
-        Vec2I::new(self.xtbl[index.0 as usize], self.ytbl[index.1 as usize])
+        let j = index.0 as usize;
+        let pos = Vec2I::new(self.xtbl[j], self.ytbl[index.1 as usize] + self.column_dy[j]);
+
+        let t = (self.moving_count & 255) as usize;
+        match self.moving_pat {
+            MovingPat::Slide | MovingPat::Scale => pos,
+            MovingPat::Wave => {
+                // Rises and falls from left to right, fading in and out.
+                let envelope = SIN_TABLE[t / 2];
+                let wave = SIN_TABLE[(t * 4 + j * 32) & 255];
+                let dy = WAVE_HEIGHT * envelope / ONE * wave / ONE;
+                Vec2I::new(pos.x, pos.y + dy)
+            }
+            MovingPat::Rotate => {
+                let center = self.center();
+                let rate = ROTATE_RATE * SIN_TABLE[t] / ONE;
+                let dx = pos.x - center.x;
+                let dy = pos.y - center.y;
+                Vec2I::new(pos.x - dy * rate / ONE, pos.y + dx * rate / ONE)
+            }
+            MovingPat::Sweep => {
+                let s = SIN_TABLE[t];
+                Vec2I::new(pos.x + SWEEP_X * s / ONE, pos.y + SWEEP_Y * s / ONE)
+            }
+        }
+    }
+
+    fn center(&self) -> Vec2I {
+        Vec2I::new(WIDTH / 2 * ONE, (self.ytbl[0] + self.ytbl[Y_COUNT - 1]) / 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_back_to_base_position() {
+        let fi = FormationIndex(1, 2);
+        for stage in 0..16 {
+            let mut formation = Formation::default();
+            formation.restart(stage);
+            let base = formation.pos(&fi);
+            formation.done_appearance();
+            for _ in 0..256 * 8 {
+                formation.update();
+                if formation.moving_count == 0 {
+                    assert_eq!(base, formation.pos(&fi));
+                }
+            }
+        }
     }
 }
@@ -17,11 +17,60 @@ lazy_static! {
             cx - ((X_COUNT - 1) as i32) * w / 2 + (j as i32) * w
         ; X_COUNT]
     };
-    pub static ref BASE_Y_TABLE: [i32; Y_COUNT] = {
-        let h = 16;
+}
 
-        array![i =>
-            BASE_Y + (i as i32) * h
-        ; Y_COUNT]
-    };
+#[derive(Copy, Clone, PartialEq)]
+pub enum MovingPat {
+    Slide,
+    Scale,
+    Wave,
+    Rotate,
+    Sweep,
+}
+
+// Shape of the formation and its movement after appearance.
+pub struct FormationLayout {
+    pub base_y: i32,
+    pub row_pitch: i32,
+    pub column_dy: [i32; X_COUNT],  // Vertical offset of each column.
+    pub moving_pats: &'static [MovingPat],  // Cycled every 256 frames.
+}
+
+// Layouts change every this count of stages.
+const LAYOUT_STAGE_SPAN: u16 = 4;
+
+const FORMATION_LAYOUTS: [FormationLayout; 4] = [
+    // Grid
+    FormationLayout {
+        base_y: BASE_Y,
+        row_pitch: 16,
+        column_dy: [0; X_COUNT],
+        moving_pats: &[MovingPat::Scale],
+    },
+    // Chevron
+    FormationLayout {
+        base_y: BASE_Y,
+        row_pitch: 16,
+        column_dy: [8, 6, 4, 2, 0, 0, 2, 4, 6, 8],
+        moving_pats: &[MovingPat::Scale, MovingPat::Wave],
+    },
+    // Arch
+    FormationLayout {
+        base_y: BASE_Y + 4,
+        row_pitch: 14,
+        column_dy: [0, 2, 4, 6, 8, 8, 6, 4, 2, 0],
+        moving_pats: &[MovingPat::Rotate, MovingPat::Scale],
+    },
+    // Grid
+    FormationLayout {
+        base_y: BASE_Y,
+        row_pitch: 16,
+        column_dy: [0; X_COUNT],
+        moving_pats: &[MovingPat::Sweep, MovingPat::Wave, MovingPat::Rotate],
+    },
+];
+
+pub fn formation_layout(stage: u16) -> &'static FormationLayout {
+    let index = (stage / LAYOUT_STAGE_SPAN) as usize % FORMATION_LAYOUTS.len();
+    &FORMATION_LAYOUTS[index]
 }
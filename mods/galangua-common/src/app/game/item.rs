@@ -0,0 +1,175 @@
+use counted_array::counted_array;
+use rand::{RngExt, SeedableRng};
+use rand_xoshiro::Xoshiro128Plus;
+
+use crate::app::consts::*;
+use crate::framework::types::Vec2I;
+use crate::framework::RendererTrait;
+use crate::util::math::{round_vec, ONE};
+use crate::util::pad::{Pad, PadBit};
+
+pub const ITEM_SPEED: i32 = 3 * ONE / 4;
+pub const ITEM_SIZE: i32 = 10;
+// One of this count of destroyed owls drops an item.
+pub const ITEM_DROP_RATE: u32 = 3;
+pub const EXTRA_MYSHOT_COUNT: usize = 2;
+
+const RAPID_FIRE_FRAMES: u32 = 10 * 60;
+const RAPID_FIRE_INTERVAL: u32 = 6;
+const SHIELD_FRAMES: u32 = 15 * 60;
+const EXTRA_SHOT_FRAMES: u32 = 15 * 60;
+const BLINK_FRAMES: u32 = 2 * 60;  // Indicator blinks before the effect runs out.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ItemType {
+    RapidFire,
+    Shield,
+    Bomb,       // Clears enemy shots.
+    ExtraShot,
+}
+
+counted_array!(pub const ITEM_TYPES: [ItemType; _] = [
+    ItemType::RapidFire, ItemType::Shield, ItemType::Bomb, ItemType::ExtraShot,
+]);
+
+impl ItemType {
+    pub fn label(self) -> &'static str {
+        match self {
+            ItemType::RapidFire => "R",
+            ItemType::Shield => "S",
+            ItemType::Bomb => "B",
+            ItemType::ExtraShot => "W",
+        }
+    }
+
+    pub fn color(self) -> (u8, u8, u8) {
+        match self {
+            ItemType::RapidFire => (255, 64, 64),
+            ItemType::Shield => (64, 160, 255),
+            ItemType::Bomb => (255, 255, 0),
+            ItemType::ExtraShot => (64, 255, 64),
+        }
+    }
+}
+
+pub fn roll_item_drop() -> Option<ItemType> {
+    let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
+    if rng.random_range(0..ITEM_DROP_RATE) == 0 {
+        Some(ITEM_TYPES[rng.random_range(0..ITEM_TYPES.len())])
+    } else {
+        None
+    }
+}
+
+// Returns false when the item goes out of the screen.
+pub fn fall_item(pos: &mut Vec2I) -> bool {
+    pos.y += ITEM_SPEED;
+    pos.y < (HEIGHT + ITEM_SIZE) * ONE
+}
+
+pub fn draw_item(renderer: &mut impl RendererTrait, item_type: ItemType, pos: &Vec2I) {
+    let pos = round_vec(pos);
+    let (r, g, b) = item_type.color();
+    renderer.set_draw_color(r, g, b);
+    renderer.fill_rect(Some([&Vec2I::new(pos.x - ITEM_SIZE / 2, pos.y - ITEM_SIZE / 2), &Vec2I::new(ITEM_SIZE, ITEM_SIZE)]));
+    renderer.set_texture_color_mod("font", 0, 0, 0);
+    renderer.draw_str("font", pos.x - 4, pos.y - 4, item_type.label());
+}
+
+// Timed effects of picked up items, for each player.
+#[derive(Clone, Default)]
+pub struct PowerUps {
+    rapid_fire: u32,
+    shield: u32,
+    extra_shot: u32,
+}
+
+impl PowerUps {
+    pub fn update(&mut self) {
+        for count in [&mut self.rapid_fire, &mut self.shield, &mut self.extra_shot].iter_mut() {
+            **count = count.saturating_sub(1);
+        }
+    }
+
+    // Bomb takes effect immediately, so the caller handles it.
+    pub fn apply(&mut self, item_type: ItemType) {
+        match item_type {
+            ItemType::RapidFire => self.rapid_fire = RAPID_FIRE_FRAMES,
+            ItemType::Shield => self.shield = SHIELD_FRAMES,
+            ItemType::ExtraShot => self.extra_shot = EXTRA_SHOT_FRAMES,
+            ItemType::Bomb => {}
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_fire(&self, pad: &Pad) -> bool {
+        pad.is_trigger(PadBit::A) ||
+            (self.rapid_fire > 0 && pad.is_pressed(PadBit::A) && self.rapid_fire.is_multiple_of(RAPID_FIRE_INTERVAL))
+    }
+
+    pub fn myshot_count(&self, base: usize) -> usize {
+        if self.extra_shot > 0 { base + EXTRA_MYSHOT_COUNT } else { base }
+    }
+
+    pub fn is_shielded(&self) -> bool {
+        self.shield > 0
+    }
+
+    // Shield absorbs one hit.
+    pub fn consume_shield(&mut self) -> bool {
+        let shielded = self.is_shielded();
+        self.shield = 0;
+        shielded
+    }
+
+    // Draws labels of active effects from `x`, to the left when `right_align`.
+    pub fn draw(&self, renderer: &mut impl RendererTrait, x: i32, right_align: bool) {
+        let effects = [
+            (ItemType::RapidFire, self.rapid_fire),
+            (ItemType::Shield, self.shield),
+            (ItemType::ExtraShot, self.extra_shot),
+        ];
+        let actives = effects.iter()
+            .filter(|(_, count)| *count > 0);
+        for (i, (item_type, count)) in actives.enumerate() {
+            if *count < BLINK_FRAMES && *count & 16 != 0 {
+                continue;
+            }
+            let (r, g, b) = item_type.color();
+            let dx = if right_align { -(i as i32 + 1) * 8 } else { i as i32 * 8 };
+            renderer.set_texture_color_mod("font", r, g, b);
+            renderer.draw_str("font", x + dx, 3 * 8, item_type.label());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_power_ups() {
+        let mut power_ups = PowerUps::default();
+        power_ups.apply(ItemType::ExtraShot);
+        power_ups.apply(ItemType::Shield);
+        assert_eq!(2 + EXTRA_MYSHOT_COUNT, power_ups.myshot_count(2));
+
+        assert!(power_ups.consume_shield());
+        assert!(!power_ups.consume_shield());
+
+        for _ in 0..EXTRA_SHOT_FRAMES {
+            power_ups.update();
+        }
+        assert_eq!(2, power_ups.myshot_count(2));
+    }
+
+    #[test]
+    fn test_fall_item() {
+        let mut pos = Vec2I::new(0, (HEIGHT + ITEM_SIZE) * ONE - ITEM_SPEED - 1);
+        assert!(fall_item(&mut pos));
+        assert!(!fall_item(&mut pos));
+    }
+}
@@ -1,29 +1,45 @@
 pub mod appearance_manager;
 pub mod appearance_table;
 pub mod attack_manager;
+pub mod attack_strategy;
+pub mod boss;
+pub mod difficulty;
 pub mod effect_table;
 pub mod formation;
 pub mod formation_table;
+pub mod item;
+pub mod player_turn;
+pub mod score_rules;
+pub mod shot_pattern;
 pub mod stage_indicator;
 pub mod star_manager;
 pub mod tractor_beam_table;
 pub mod traj;
 pub mod traj_command;
 pub mod traj_command_table;
+pub mod transform;
 
 #[derive(Clone, Copy, PartialEq)]
 pub struct FormationIndex(pub u8, pub u8);  // x, y
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub enum EnemyType {
     Bee,
     Butterfly,
     Owl,
     CapturedFighter,
+    // Transformed from bee.
+    Scorpion,
+    Stingray,
+    Flagship,
+    // Boss stage.
+    Boss,
 }
 
 #[derive(Clone, Copy)]
 pub enum EarnedPointType {
+    Point3000,
+    Point2000,
     Point1600,
     Point1000,
     Point800,
@@ -0,0 +1,92 @@
+use crate::app::consts::DEFAULT_LEFT_SHIP;
+use crate::app::game::difficulty::DifficultyProfile;
+use crate::app::game::FormationIndex;
+
+// Game progress of a player, kept while the other player is playing.
+#[derive(Clone)]
+pub struct PlayerProgress {
+    pub stage: u16,
+    pub left_ship: u32,
+    pub capture_enemy_fi: Option<FormationIndex>,  // Owl which holds the captured fighter.
+    pub difficulty: DifficultyProfile,
+    pub survivors: Option<Vec<FormationIndex>>,    // None: Starts the stage from the beginning.
+}
+
+impl PlayerProgress {
+    pub fn new(difficulty: DifficultyProfile) -> Self {
+        Self {
+            stage: 0,
+            left_ship: DEFAULT_LEFT_SHIP,
+            capture_enemy_fi: None,
+            difficulty,
+            survivors: None,
+        }
+    }
+
+    pub fn captured_fighter(&self) -> Option<FormationIndex> {
+        self.capture_enemy_fi.map(|fi| FormationIndex(fi.0, fi.1 - 1))
+    }
+}
+
+// Alternates players in two-player game, the turn moves when the player is lost.
+#[derive(Default)]
+pub struct PlayerTurns {
+    current: usize,
+    waiting: Option<PlayerProgress>,
+}
+
+impl PlayerTurns {
+    pub fn new(player_count: usize, progress: &PlayerProgress) -> Self {
+        Self {
+            current: 0,
+            waiting: if player_count > 1 { Some(progress.clone()) } else { None },
+        }
+    }
+
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    pub fn is_two_players(&self) -> bool {
+        self.waiting.is_some()
+    }
+
+    pub fn can_switch(&self) -> bool {
+        self.waiting.as_ref()
+            .is_some_and(|progress| progress.left_ship > 0)
+    }
+
+    // Keeps the progress of the current player and returns the next one's.
+    pub fn switch(&mut self, progress: PlayerProgress) -> PlayerProgress {
+        assert!(self.can_switch());
+        self.current ^= 1;
+        self.waiting.replace(progress).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch() {
+        let progress = PlayerProgress::new(DifficultyProfile::default());
+        let mut turns = PlayerTurns::new(2, &progress);
+        assert!(turns.can_switch());
+
+        let mut progress1 = progress.clone();
+        progress1.stage = 3;
+        progress1.left_ship = 0;
+        progress1.survivors = Some(vec![FormationIndex(4, 1)]);
+        let progress2 = turns.switch(progress1);
+        assert_eq!(1, turns.current());
+        assert_eq!(0, progress2.stage);
+        assert!(progress2.survivors.is_none());
+
+        // Player 1 has no ship, so player 2 continues.
+        assert!(!turns.can_switch());
+
+        let single = PlayerTurns::new(1, &progress);
+        assert!(!single.is_two_players() && !single.can_switch());
+    }
+}
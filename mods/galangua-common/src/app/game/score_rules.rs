@@ -0,0 +1,140 @@
+use crate::app::assist::AssistOptions;
+use crate::app::game::EnemyType;
+
+// Point of an enemy, staying in the formation or diving.
+#[derive(Clone, Copy)]
+pub struct EnemyPoint {
+    pub formation: u32,
+    pub diving: u32,
+}
+
+impl EnemyPoint {
+    pub const fn new(formation: u32, diving: u32) -> Self {
+        Self { formation, diving }
+    }
+
+    pub fn get(&self, is_formation: bool) -> u32 {
+        if is_formation { self.formation } else { self.diving }
+    }
+}
+
+#[derive(Clone)]
+pub struct ScoreRules {
+    pub bee: EnemyPoint,
+    pub butterfly: EnemyPoint,
+    pub captured_fighter: EnemyPoint,
+    pub transformed: u32,
+    pub owl_formation: u32,
+    pub owl_escorts: [u32; 3],  // Diving owl, indexed by the number of escorts with it.
+    pub boss: u32,
+    pub boss_bonus: u32,        // Earned with the final blow, shown after the defeat sequence.
+    pub group_bonus: [u32; 3],  // Destroying all of a transformed trio: scorpion, stingray and flagship.
+    pub rescue_bonus: u32,      // Destroying an owl which holds the captured fighter.
+    pub extend_first: u32,
+    pub extend_every: u32,
+}
+
+impl Default for ScoreRules {
+    fn default() -> Self {
+        Self {
+            bee: EnemyPoint::new(50, 100),
+            butterfly: EnemyPoint::new(80, 160),
+            captured_fighter: EnemyPoint::new(500, 1000),
+            transformed: 160,
+            owl_formation: 150,
+            owl_escorts: [400, 800, 1600],
+            boss: 3000,
+            boss_bonus: 10000,
+            group_bonus: [1000, 2000, 3000],
+            rescue_bonus: 0,
+            extend_first: 20_000,
+            extend_every: 50_000,
+        }
+    }
+}
+
+impl ScoreRules {
+    // Rules for the game mode chosen with the options, arrange rewards rescuing the captured fighter.
+    pub fn for_mode(assist: &AssistOptions) -> Self {
+        if assist.arrange {
+            Self { rescue_bonus: 1000, ..Default::default() }
+        } else {
+            Self::default()
+        }
+    }
+
+    pub fn enemy_point(&self, enemy_type: EnemyType, is_formation: bool) -> u32 {
+        match enemy_type {
+            EnemyType::Bee => self.bee.get(is_formation),
+            EnemyType::Butterfly => self.butterfly.get(is_formation),
+            EnemyType::CapturedFighter => self.captured_fighter.get(is_formation),
+            EnemyType::Scorpion | EnemyType::Stingray | EnemyType::Flagship => self.transformed,
+            EnemyType::Boss => self.boss,
+            EnemyType::Owl => self.owl_point(is_formation, 0),  // Use `owl_point` to count escorts.
+        }
+    }
+
+    pub fn transformed_group_bonus(&self, enemy_type: EnemyType) -> u32 {
+        match enemy_type {
+            EnemyType::Scorpion => self.group_bonus[0],
+            EnemyType::Stingray => self.group_bonus[1],
+            EnemyType::Flagship => self.group_bonus[2],
+            _ => { panic!("Illegal"); }
+        }
+    }
+
+    pub fn owl_point(&self, is_formation: bool, escort_count: usize) -> u32 {
+        if is_formation {
+            self.owl_formation
+        } else {
+            self.owl_escorts[std::cmp::min(escort_count, self.owl_escorts.len() - 1)]
+        }
+    }
+
+    // Score at which the next ship is given.
+    pub fn next_extend(&self, score: u32) -> u32 {
+        if score < self.extend_first {
+            self.extend_first
+        } else {
+            (score / self.extend_every + 1) * self.extend_every
+        }
+    }
+
+    pub fn is_extended(&self, before: u32, add: u32) -> bool {
+        before + add >= self.next_extend(before)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owl_point() {
+        let rules = ScoreRules::default();
+        assert_eq!(150, rules.owl_point(true, 2));
+        assert_eq!(400, rules.owl_point(false, 0));
+        assert_eq!(1600, rules.owl_point(false, 2));
+        assert_eq!(1600, rules.owl_point(false, 3));
+        assert_eq!(400, rules.enemy_point(EnemyType::Owl, false));
+    }
+
+    #[test]
+    fn test_for_mode() {
+        let mut assist = AssistOptions::default();
+        assert_eq!(0, ScoreRules::for_mode(&assist).rescue_bonus);
+        assist.arrange = true;
+        assert_eq!(1000, ScoreRules::for_mode(&assist).rescue_bonus);
+    }
+
+    #[test]
+    fn test_extend() {
+        let rules = ScoreRules::default();
+        assert!(!rules.is_extended(0, 19_990));
+        assert!(rules.is_extended(19_990, 10));
+        assert!(!rules.is_extended(20_000, 10));
+        assert!(rules.is_extended(49_990, 10));
+        assert!(!rules.is_extended(50_000, 40_000));
+        assert!(rules.is_extended(90_000, 10_000));
+    }
+}
@@ -0,0 +1,187 @@
+use crate::app::consts::PLAYER_SPEED;
+use crate::framework::types::{Vec2I, ZERO_VEC};
+use crate::util::math::{atan2_lut, calc_velocity, clamp, ANGLE, ONE};
+
+// Frames to wait before a lead shot is fired, shorter than `attack_shot_interval`
+// so that a pending shot is fired before the next one.
+pub const LEAD_DELAY: u32 = 8;
+
+const AIM_LIMIT: i32 = ANGLE * ONE * 30 / 360;
+const SPREAD_ANGLE: i32 = ANGLE * ONE * 12 / 360;
+const WIDE_ANGLE: i32 = ANGLE * ONE * 20 / 360;
+// Speed decrease of following bullets in a burst, in percent.
+const BURST_SPEED_STEP: i32 = 15;
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub enum ShotPattern {
+    #[default]
+    Aimed,        // Single bullet to the player.
+    Burst(u32),   // Bullets in a row to the player.
+    Spread(u32),  // Fan of bullets centered to the player.
+    Lead,         // Delayed single bullet to the predicted player position.
+    Ring(u32),    // Bullets to all directions, starting from the player.
+    Wide(u32),    // Fan of bullets straight down, regardless of the player.
+}
+
+impl ShotPattern {
+    pub fn delay(self) -> u32 {
+        match self {
+            ShotPattern::Lead => LEAD_DELAY,
+            _ => 0,
+        }
+    }
+}
+
+// Velocities of bullets fired at once, the first one is aimed except `Wide`.
+pub fn shot_velocities(
+    pattern: ShotPattern, pos: &Vec2I, target: &Vec2I, target_vel: &Vec2I, speed: i32,
+) -> Vec<Vec2I> {
+    match pattern {
+        ShotPattern::Aimed => {
+            vec![calc_velocity(aim_angle(pos, target), speed)]
+        }
+        ShotPattern::Burst(count) => {
+            let angle = aim_angle(pos, target);
+            (0..count as i32)
+                .map(|i| calc_velocity(angle, speed * (100 - BURST_SPEED_STEP * i).max(10) / 100))
+                .collect()
+        }
+        ShotPattern::Spread(count) => {
+            let angle = aim_angle(pos, target);
+            let count = count as i32;
+            (0..count)
+                .map(|i| {
+                    // Center first, then alternately to the both sides.
+                    let side = if i % 2 == 0 { 1 } else { -1 };
+                    calc_velocity(angle + side * (i + 1) / 2 * SPREAD_ANGLE, speed)
+                })
+                .collect()
+        }
+        ShotPattern::Lead => {
+            let frames = if speed > 0 { (target.y - pos.y).abs() / speed } else { 0 };
+            let predicted = target + &(target_vel * frames);
+            vec![calc_velocity(aim_angle(pos, &predicted), speed)]
+        }
+        ShotPattern::Ring(count) => {
+            // Not limited, bullets go to all directions anyway.
+            let d = target - pos;
+            let angle = atan2_lut(d.y, -d.x) + ANGLE * ONE / 2;
+            let count = count.max(1) as i32;
+            (0..count)
+                .map(|i| calc_velocity(angle + ANGLE * ONE * i / count, speed))
+                .collect()
+        }
+        ShotPattern::Wide(count) => {
+            let count = count as i32;
+            (0..count)
+                .map(|i| calc_velocity(ANGLE * ONE / 2 + (i * 2 - (count - 1)) * WIDE_ANGLE / 2, speed))
+                .collect()
+        }
+    }
+}
+
+fn aim_angle(pos: &Vec2I, target: &Vec2I) -> i32 {
+    let d = target - pos;
+    let angle = atan2_lut(d.y, -d.x);  // 0=down
+    clamp(angle, -AIM_LIMIT, AIM_LIMIT) + ANGLE * ONE / 2
+}
+
+// Keeps track of the movement of one player for lead shots.
+#[derive(Default)]
+pub struct TargetTracker {
+    prev: Option<Vec2I>,
+    vel: Vec2I,
+}
+
+impl TargetTracker {
+    pub fn update(&mut self, pos: Option<&Vec2I>) {
+        self.vel = match (pos, &self.prev) {
+            (Some(pos), Some(prev)) => pos - prev,
+            _ => ZERO_VEC,
+        };
+        // Faster than the player moves: restarted or warped, not a movement to lead.
+        if self.vel.x.abs() > PLAYER_SPEED || self.vel.y.abs() > PLAYER_SPEED {
+            self.vel = ZERO_VEC;
+        }
+        self.prev = pos.copied();
+    }
+
+    pub fn vel(&self) -> &Vec2I {
+        &self.vel
+    }
+}
+
+// `targets` are pairs of a player index and a position, the first one of each player is tracked.
+pub fn update_target_trackers(trackers: &mut [TargetTracker], targets: &[(usize, Vec2I)]) {
+    for (player, tracker) in trackers.iter_mut().enumerate() {
+        tracker.update(targets.iter().find(|(index, _)| *index == player).map(|(_, pos)| pos));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::game::difficulty::{attack_shot_interval, MAX_LEVEL};
+
+    #[test]
+    fn test_spread() {
+        let pos = Vec2I::new(0, 0);
+        let target = Vec2I::new(0, 100 * ONE);
+        let vels = shot_velocities(ShotPattern::Spread(3), &pos, &target, &ZERO_VEC, 4 * ONE);
+        assert_eq!(3, vels.len());
+        assert_eq!(0, vels[0].x);
+        assert!(vels[0].y > 0);
+        assert_eq!(-vels[1].x, vels[2].x);
+    }
+
+    #[test]
+    fn test_lead() {
+        let pos = Vec2I::new(0, 0);
+        let target = Vec2I::new(0, 100 * ONE);
+        let target_vel = Vec2I::new(ONE, 0);
+        let aimed = shot_velocities(ShotPattern::Aimed, &pos, &target, &target_vel, 4 * ONE);
+        let lead = shot_velocities(ShotPattern::Lead, &pos, &target, &target_vel, 4 * ONE);
+        assert_eq!(0, aimed[0].x);
+        assert!(lead[0].x > 0);
+    }
+
+    #[test]
+    fn test_target_trackers() {
+        let mut trackers: [TargetTracker; 2] = Default::default();
+        update_target_trackers(&mut trackers, &[(0, Vec2I::new(0, 0)), (1, Vec2I::new(100 * ONE, 0))]);
+        update_target_trackers(&mut trackers, &[(0, Vec2I::new(ONE, 0)), (1, Vec2I::new(100 * ONE - ONE, 0))]);
+        assert_eq!(Vec2I::new(ONE, 0), *trackers[0].vel());
+        assert_eq!(Vec2I::new(-ONE, 0), *trackers[1].vel());
+
+        // Player 0 is lost, player 1 keeps its own velocity.
+        update_target_trackers(&mut trackers, &[(1, Vec2I::new(100 * ONE - 2 * ONE, 0))]);
+        assert_eq!(ZERO_VEC, *trackers[0].vel());
+        assert_eq!(Vec2I::new(-ONE, 0), *trackers[1].vel());
+
+        // Warp is not a movement.
+        update_target_trackers(&mut trackers, &[(0, Vec2I::new(0, 0)), (1, Vec2I::new(0, 0))]);
+        assert_eq!(ZERO_VEC, *trackers[1].vel());
+    }
+
+    #[test]
+    fn test_lead_delay() {
+        assert!(LEAD_DELAY < attack_shot_interval(MAX_LEVEL));
+    }
+
+    #[test]
+    fn test_ring_and_wide() {
+        let pos = Vec2I::new(0, 0);
+        let target = Vec2I::new(0, 100 * ONE);
+        let ring = shot_velocities(ShotPattern::Ring(4), &pos, &target, &ZERO_VEC, 4 * ONE);
+        assert_eq!(4, ring.len());
+        assert!(ring[0].y > 0);
+        assert!(ring[2].y < 0);
+        assert_eq!(-ring[1].x, ring[3].x);
+
+        // Wide doesn't aim at the target on the side.
+        let target = Vec2I::new(100 * ONE, 0);
+        let wide = shot_velocities(ShotPattern::Wide(3), &pos, &target, &ZERO_VEC, 4 * ONE);
+        assert_eq!(0, wide[1].x);
+        assert_eq!(-wide[0].x, wide[2].x);
+    }
+}
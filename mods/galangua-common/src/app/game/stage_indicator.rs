@@ -62,6 +62,10 @@ impl StageIndicator {
     }
 }
 
+pub fn flag_sprite_names() -> impl Iterator<Item = &'static str> {
+    FLAG_INFO_TABLE.iter().map(|info| info.sprite_name)
+}
+
 struct FlagInfo {
     sprite_name: &'static str,
     count: u16,
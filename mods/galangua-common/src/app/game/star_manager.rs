@@ -24,6 +24,7 @@ pub struct StarManager {
     frame_count: i32,
     scroll_vel: i32,
     stars: [Star; STAR_COUNT],
+    palette: Vec<u32>,
 }
 
 impl Default for StarManager {
@@ -34,7 +35,7 @@ impl Default for StarManager {
                 pos: Vec2I::new(rng.random_range(0..WIDTH) * ONE,
                                 rng.random_range(-16..HEIGHT) * ONE),
                 t: rng.random_range(0..64),
-                c: choose_random_color(&mut rng),
+                c: choose_random_color(&mut rng, &[]),
             }
         ; STAR_COUNT];
 
@@ -43,6 +44,7 @@ impl Default for StarManager {
             frame_count: 0,
             scroll_vel: 0,
             stars,
+            palette: Vec::new(),
         }
     }
 }
@@ -70,7 +72,7 @@ impl StarManager {
             star.pos.y = y;
             if warp {
                 star.pos.x = rng.random_range(0..WIDTH) * ONE;
-                star.c = choose_random_color(&mut rng);
+                star.c = choose_random_color(&mut rng, &self.palette);
                 star.t = rng.random_range(0..64);
             }
         }
@@ -91,6 +93,15 @@ impl StarManager {
         }
     }
 
+    // Colors of stars in 0xRRGGBB, empty for the default ones.
+    pub fn set_palette(&mut self, palette: &[u32]) {
+        self.palette = palette.to_vec();
+        let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
+        for star in self.stars.iter_mut() {
+            star.c = choose_random_color(&mut rng, palette);
+        }
+    }
+
     pub fn set_capturing(&mut self, value: bool) {
         self.state = if value { State::Capturing } else { State::Normal };
     }
@@ -114,7 +125,11 @@ struct Star {
 
 const COLOR_TABLE: [u32; 4] = [0, 71, 151, 222];
 
-fn choose_random_color(rng: &mut impl Rng) -> u32 {
+fn choose_random_color(rng: &mut impl Rng, palette: &[u32]) -> u32 {
+    if !palette.is_empty() {
+        return palette[rng.random_range(0..palette.len())];
+    }
+
     let c = rng.random_range(1..(1 << 6));  // 1 for avoid black.
     let r =  c       & 3;
     let g = (c >> 2) & 3;
@@ -1,10 +1,13 @@
-pub const TRACTOR_BEAM_SPRITE_NAMES: [&str; 29] = [
-    "beam00", "beam01", "beam02", "beam03", "beam04", "beam05",
-    "beam06", "beam07", "beam08", "beam09", "beam10", "beam11",
-    "beam12", "beam13", "beam14", "beam15", "beam16", "beam17",
-    "beam18", "beam19", "beam20", "beam21", "beam22", "beam23",
-    "beam24", "beam25", "beam26", "beam27", "beam28",
-];
+use crate::framework::sprite_sheet::AnimationTable;
+
+pub const TRACTOR_BEAM_ANIME_NAME: &str = "tractor_beam";
+
+// Frames beyond the offset table are not used, missing ones are not drawn.
+pub fn tractor_beam_sprite_names(animations: &AnimationTable) -> Vec<String> {
+    animations.get(TRACTOR_BEAM_ANIME_NAME)
+        .map(|clip| clip.frames.iter().take(TRACTOR_BEAM_Y_OFFSET_TABLE.len()).cloned().collect())
+        .unwrap_or_default()
+}
 
 pub const TRACTOR_BEAM_Y_OFFSET_TABLE: [i32; 29] = [
      0,  0,  5,  9, 12, 14,
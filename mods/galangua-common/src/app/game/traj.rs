@@ -1,4 +1,6 @@
 use crate::app::consts::*;
+use crate::app::game::difficulty::accelerated_speed;
+use crate::app::game::shot_pattern::ShotPattern;
 use crate::app::game::traj_command::TrajCommand;
 use crate::app::game::traj_command::TrajCommand::*;
 use crate::app::game::FormationIndex;
@@ -10,7 +12,8 @@ use crate::util::unsafe_util::extend_lifetime;
 
 pub trait Accessor {
     fn get_formation_pos(&self, formation_index: &FormationIndex) -> Vec2I;
-    fn get_stage_no(&self) -> u16;
+    fn get_level(&self) -> u16;  // Difficulty level, see `DifficultyProfile::level`.
+    fn is_shot_pattern_enabled(&self) -> bool;
 }
 
 enum WaitPred {
@@ -32,6 +35,7 @@ pub struct Traj {
     delay: u32,
     wait_pred: Option<WaitPred>,
     shot: Option<u32>,
+    shot_pattern: ShotPattern,
 
     #[cfg(debug_assertions)]
     #[allow(dead_code)]
@@ -58,6 +62,7 @@ impl Traj {
             delay: 0,
             wait_pred: None,
             shot: None,
+            shot_pattern: ShotPattern::Aimed,
 
             #[cfg(debug_assertions)]
             command_table_vec: None,
@@ -94,6 +99,10 @@ impl Traj {
         self.shot.take()
     }
 
+    pub fn shot_pattern(&self) -> ShotPattern {
+        self.shot_pattern
+    }
+
     pub fn update(&mut self, accessor: &dyn Accessor) -> bool {
         self.handle_command(accessor);
 
@@ -156,10 +165,7 @@ impl Traj {
                 return false;
             }
             Accelerate => {
-                const MAX_STAGE: i32 = 64;
-                const MAX_SPEED: i32 = 5 * ONE;
-                let stage_no = accessor.get_stage_no();
-                self.speed += (MAX_SPEED - self.speed) * std::cmp::min(stage_no as i32, MAX_STAGE) / MAX_STAGE;
+                self.speed = accelerated_speed(self.speed, accessor.get_level());
             }
             DestAngle(mut dest_angle, radius) => {
                 if self.flip_x {
@@ -198,7 +204,67 @@ impl Traj {
                     self.shot = Some(delay);
                 }
             }
+            Pattern(pattern, level) => {
+                if accessor.get_level() >= level {
+                    self.shot_pattern = pattern;
+                }
+            }
+            ExtraPattern(pattern, level) => {
+                if accessor.is_shot_pattern_enabled() && accessor.get_level() >= level {
+                    self.shot_pattern = pattern;
+                }
+            }
         }
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::game::traj_command_table::*;
+
+    struct TestAccessor {
+        level: u16,
+        shot_pattern_enabled: bool,
+    }
+
+    impl Accessor for TestAccessor {
+        fn get_formation_pos(&self, _formation_index: &FormationIndex) -> Vec2I { ZERO_VEC }
+        fn get_level(&self) -> u16 { self.level }
+        fn is_shot_pattern_enabled(&self) -> bool { self.shot_pattern_enabled }
+    }
+
+    fn first_pattern(table: &'static [TrajCommand], accessor: &TestAccessor) -> ShotPattern {
+        let mut traj = Traj::new(table, &ZERO_VEC, false, FormationIndex(0, 0));
+        traj.update(accessor);
+        traj.shot_pattern()
+    }
+
+    #[test]
+    fn test_boss_pattern_in_arcade() {
+        let arcade = TestAccessor { level: 0, shot_pattern_enabled: false };
+        assert_eq!(ShotPattern::Spread(3), first_pattern(&BOSS_PHASE1_TABLE, &arcade));
+        assert_eq!(ShotPattern::Ring(8), first_pattern(&BOSS_PHASE2_TABLE, &arcade));
+    }
+
+    #[test]
+    fn test_transformed_pattern_in_arcade() {
+        let arcade = TestAccessor { level: 0, shot_pattern_enabled: false };
+        assert_eq!(ShotPattern::Lead, first_pattern(&SCORPION_ATTACK_TABLE, &arcade));
+        assert_eq!(ShotPattern::Spread(3), first_pattern(&STINGRAY_ATTACK_TABLE, &arcade));
+        assert_eq!(ShotPattern::Burst(3), first_pattern(&FLAGSHIP_ATTACK_TABLE, &arcade));
+    }
+
+    #[test]
+    fn test_extra_pattern() {
+        let arcade = TestAccessor { level: 64, shot_pattern_enabled: false };
+        assert_eq!(ShotPattern::Aimed, first_pattern(&BEE_ATTACK_TABLE, &arcade));
+
+        let low = TestAccessor { level: 0, shot_pattern_enabled: true };
+        assert_eq!(ShotPattern::Aimed, first_pattern(&BEE_ATTACK_TABLE, &low));
+
+        let high = TestAccessor { level: 64, shot_pattern_enabled: true };
+        assert_eq!(ShotPattern::Lead, first_pattern(&BEE_ATTACK_TABLE, &high));
+    }
+}
@@ -1,3 +1,5 @@
+use crate::app::game::shot_pattern::ShotPattern;
+
 #[derive(Clone, PartialEq)]
 pub enum TrajCommand {
     Pos(i32, i32),
@@ -11,4 +13,6 @@ pub enum TrajCommand {
     AddPos(i32, i32),
     CopyFormationX,
     Shot(u32),
+    Pattern(ShotPattern, u16),  // shot pattern used from the level
+    ExtraPattern(ShotPattern, u16),  // same as `Pattern`, but not used in Arcade difficulty
 }
@@ -2,6 +2,7 @@ use counted_array::counted_array;
 
 use super::traj_command::TrajCommand;
 use super::traj_command::TrajCommand::*;
+use super::shot_pattern::ShotPattern;
 
 use crate::app::consts::*;
 use crate::util::math::{ANGLE, ONE};
@@ -55,6 +56,7 @@ counted_array!(pub const COMMAND_TABLE3: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const BEE_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Lead, 24),
     Speed(2 * ONE),
     Angle(0),
     VAngle(-4 * ONE),
@@ -80,6 +82,7 @@ counted_array!(pub const BEE_ATTACK_RUSH_CONT_TABLE: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const BUTTERFLY_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Spread(3), 16),
     Speed(2 * ONE),
     Angle(0 * ONE),
     VAngle(-4 * ONE),
@@ -105,6 +108,7 @@ counted_array!(pub const BUTTERFLY_ATTACK_TABLE: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const OWL_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Burst(3), 8),
     Speed(2 * ONE),
     Angle(0 * ONE),
     VAngle(-4 * ONE),
@@ -130,6 +134,7 @@ counted_array!(pub const OWL_ATTACK_TABLE: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const BEE_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Lead, 24),
     Speed(25 * ONE / 10),
     Angle((ANGLE / 2) * ONE),
     VAngle(0),
@@ -164,6 +169,7 @@ counted_array!(pub const BEE_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const BUTTERFLY_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Spread(3), 16),
     Speed(25 * ONE / 10),
     Angle((ANGLE / 2) * ONE),
     VAngle(0),
@@ -192,6 +198,7 @@ counted_array!(pub const BUTTERFLY_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
 ]);
 
 counted_array!(pub const OWL_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
+    ExtraPattern(ShotPattern::Burst(3), 8),
     Speed(25 * ONE / 10),
     Angle((ANGLE / 2) * ONE),
     VAngle(0),
@@ -212,3 +219,150 @@ counted_array!(pub const OWL_RUSH_ATTACK_TABLE: [TrajCommand; _] = [
     CopyFormationX,
     Angle((ANGLE / 2) * ONE),
 ]);
+
+counted_array!(pub const BEE_TRANSFORM_TABLE: [TrajCommand; _] = [
+    Speed(2 * ONE),
+    Angle(0),
+    VAngle(-4 * ONE),
+    Delay(41),
+
+    VAngle(0),
+    WaitYG(130 * ONE),
+
+    Speed(1 * ONE),
+    VAngle(8 * ONE),
+    Delay(32),
+]);
+
+// Transformed enemies keep their own pattern in every difficulty.
+
+counted_array!(pub const SCORPION_ATTACK_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Lead, 0),
+    Speed(3 * ONE),
+    Angle((ANGLE / 2) * ONE),
+    VAngle(3 * ONE),
+    Delay(12),
+
+    VAngle(-3 * ONE),
+    Delay(24),
+
+    VAngle(3 * ONE),
+    Delay(24),
+
+    VAngle(-3 * ONE),
+    Delay(12),
+
+    VAngle(0),
+    WaitYG(304 * ONE),
+    Speed(0),
+]);
+
+counted_array!(pub const STINGRAY_ATTACK_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Spread(3), 0),
+    Speed(2 * ONE),
+    Angle((ANGLE / 2) * ONE),
+    VAngle(-2 * ONE),
+    Delay(24),
+
+    Accelerate,
+    VAngle(4 * ONE),
+    Delay(24),
+
+    VAngle(0),
+    WaitYG(304 * ONE),
+    Speed(0),
+]);
+
+counted_array!(pub const FLAGSHIP_ATTACK_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Burst(3), 0),
+    Speed(2 * ONE),
+    Angle((ANGLE / 2) * ONE),
+    VAngle(0),
+    Delay(20),
+
+    Accelerate,
+    VAngle(-3 * ONE),
+    Delay(16),
+
+    VAngle(3 * ONE),
+    Delay(32),
+
+    VAngle(0),
+    WaitYG(304 * ONE),
+    Speed(0),
+]);
+
+// Boss comes down to the home position, then repeats the table of the current phase.
+
+counted_array!(pub const BOSS_APPEARANCE_TABLE: [TrajCommand; _] = [
+    Pos((WIDTH / 2) * ONE, -24 * ONE),
+    Speed(ONE),
+    Angle((ANGLE / 2) * ONE),
+    VAngle(0),
+    Delay(88),
+    Speed(0),
+]);
+
+counted_array!(pub const BOSS_PHASE1_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Spread(3), 0),
+    Speed(ONE),
+    Angle((ANGLE / 4) * ONE),
+    Delay(30),
+    Shot(0),
+    Delay(30),
+
+    Angle((-ANGLE / 4) * ONE),
+    Delay(30),
+    Shot(0),
+    Delay(60),
+    Shot(0),
+    Delay(30),
+
+    Angle((ANGLE / 4) * ONE),
+    Delay(30),
+    Shot(0),
+    Delay(30),
+]);
+
+counted_array!(pub const BOSS_PHASE2_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Ring(8), 0),
+    Speed(3 * ONE / 2),
+    Angle((ANGLE / 4) * ONE),
+    VAngle(2 * ONE),
+    Delay(32),
+    Shot(0),
+    Delay(32),
+    Pattern(ShotPattern::Burst(3), 0),
+    Shot(0),
+    Delay(32),
+    Pattern(ShotPattern::Ring(8), 0),
+    Shot(0),
+    Delay(32),
+    VAngle(0),
+]);
+
+counted_array!(pub const BOSS_PHASE3_TABLE: [TrajCommand; _] = [
+    Pattern(ShotPattern::Wide(5), 0),
+    Speed(2 * ONE),
+    Angle((ANGLE / 2 - ANGLE / 8) * ONE),
+    VAngle(0),
+    Delay(40),
+    Shot(0),
+
+    Angle((ANGLE / 2 + ANGLE / 8) * ONE),
+    Delay(40),
+    Pattern(ShotPattern::Ring(10), 0),
+    Shot(0),
+
+    Angle((-ANGLE / 8) * ONE),
+    Delay(40),
+    Pattern(ShotPattern::Wide(5), 0),
+    Shot(0),
+
+    Angle((ANGLE / 8) * ONE),
+    Delay(40),
+    Speed(0),
+    Pattern(ShotPattern::Spread(5), 0),
+    Shot(0),
+    Delay(40),
+]);
@@ -0,0 +1,112 @@
+use crate::app::game::formation_table::Y_COUNT;
+use crate::app::game::traj_command::TrajCommand;
+use crate::app::game::traj_command_table::*;
+use crate::app::game::EnemyType;
+use crate::framework::types::Vec2I;
+use crate::util::math::ONE;
+
+// Bees start transforming from this stage (0 origin).
+pub const TRANSFORM_START_STAGE: u16 = 3;
+
+// Transformed enemies use the row below the assault row.
+pub const TRANSFORM_FORMATION_Y: u8 = Y_COUNT as u8 + 1;
+
+pub const TRANSFORM_COUNT: usize = 3;
+
+// Offsets of the trio from the transformed bee, the leader comes first.
+pub const TRANSFORM_OFFSETS: [Vec2I; TRANSFORM_COUNT] = [
+    Vec2I::new(0, 0),
+    Vec2I::new(-14 * ONE, -12 * ONE),
+    Vec2I::new(14 * ONE, -12 * ONE),
+];
+
+const TRANSFORMED_TYPES: [EnemyType; 3] = [
+    EnemyType::Scorpion,
+    EnemyType::Stingray,
+    EnemyType::Flagship,
+];
+
+pub fn transformed_type(stage: u16) -> EnemyType {
+    let stage = stage.saturating_sub(TRANSFORM_START_STAGE);
+    TRANSFORMED_TYPES[(stage as usize / 4) % TRANSFORMED_TYPES.len()]
+}
+
+pub fn is_transformed(enemy_type: EnemyType) -> bool {
+    TRANSFORMED_TYPES.contains(&enemy_type)
+}
+
+pub fn transformed_attack_table(enemy_type: EnemyType) -> &'static [TrajCommand] {
+    match enemy_type {
+        EnemyType::Scorpion => &SCORPION_ATTACK_TABLE,
+        EnemyType::Stingray => &STINGRAY_ATTACK_TABLE,
+        EnemyType::Flagship => &FLAGSHIP_ATTACK_TABLE,
+        _ => { panic!("Illegal"); }
+    }
+}
+
+// Tracks the trio spawned from a bee, bonus is given when all of them are destroyed.
+#[derive(Default)]
+pub struct TransformGroup {
+    enemy_type: Option<EnemyType>,
+    destroyed_count: usize,
+    escaped: bool,
+}
+
+impl TransformGroup {
+    pub fn start(&mut self, enemy_type: EnemyType) {
+        *self = Self {
+            enemy_type: Some(enemy_type),
+            destroyed_count: 0,
+            escaped: false,
+        };
+    }
+
+    // Returns the type of the group when the last one is destroyed, see `ScoreRules::transformed_group_bonus`.
+    pub fn destroyed(&mut self) -> Option<EnemyType> {
+        let enemy_type = self.enemy_type?;
+        self.destroyed_count += 1;
+        if self.destroyed_count < TRANSFORM_COUNT || self.escaped {
+            return None;
+        }
+        self.enemy_type = None;
+        Some(enemy_type)
+    }
+
+    pub fn escaped(&mut self) {
+        self.escaped = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::game::score_rules::ScoreRules;
+
+    #[test]
+    fn test_group_bonus() {
+        let rules = ScoreRules::default();
+        let mut group = TransformGroup::default();
+        group.start(EnemyType::Stingray);
+        assert_eq!(None, group.destroyed());
+        assert_eq!(None, group.destroyed());
+        assert_eq!(Some(2000), group.destroyed().map(|t| rules.transformed_group_bonus(t)));
+    }
+
+    #[test]
+    fn test_no_bonus_after_escape() {
+        let mut group = TransformGroup::default();
+        group.start(EnemyType::Scorpion);
+        group.destroyed();
+        group.escaped();
+        assert_eq!(None, group.destroyed());
+        assert_eq!(None, group.destroyed());
+    }
+
+    #[test]
+    fn test_transformed_type() {
+        assert!(transformed_type(TRANSFORM_START_STAGE) == EnemyType::Scorpion);
+        assert!(transformed_type(TRANSFORM_START_STAGE + 4) == EnemyType::Stingray);
+        assert!(transformed_type(TRANSFORM_START_STAGE + 8) == EnemyType::Flagship);
+        assert!(transformed_type(TRANSFORM_START_STAGE + 12) == EnemyType::Scorpion);
+    }
+}
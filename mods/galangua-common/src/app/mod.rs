@@ -1,3 +1,7 @@
+pub mod accessibility;
+pub mod assist;
+pub mod asset_check;
+pub mod assets;
 pub mod consts;
 pub mod game;
 pub mod score_holder;
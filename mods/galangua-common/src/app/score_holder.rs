@@ -1,42 +1,199 @@
-use crate::framework::RendererTrait;
+use serde_json::json;
+
+use crate::app::consts::*;
+use crate::framework::storage::Blob;
+use crate::framework::text_layout::{draw_text, TextStyle};
+use crate::framework::types::Vec2I;
+use crate::framework::{RendererTrait, SystemTrait};
+
+pub const MAX_PLAYER_COUNT: usize = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayMode {
+    Single,
+    Alternate,  // Two players take turns.
+    Coop,       // Two players at once.
+}
+
+impl PlayMode {
+    pub fn next(self) -> Self {
+        match self {
+            PlayMode::Single => PlayMode::Alternate,
+            PlayMode::Alternate => PlayMode::Coop,
+            PlayMode::Coop => PlayMode::Single,
+        }
+    }
+
+    pub fn player_count(self) -> usize {
+        match self {
+            PlayMode::Single => 1,
+            PlayMode::Alternate | PlayMode::Coop => MAX_PLAYER_COUNT,
+        }
+    }
+}
+
+// How a run counts for the high score, decided at the start of a game.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum RunKind {
+    Ranked,
+    Unranked,  // Game mode off the arcade rules, the high score is not updated.
+    Assisted,  // Same as `Unranked`, and flagged on the screen.
+}
 
 #[derive(Clone)]
 pub struct ScoreHolder {
-    pub score: u32,
+    pub scores: [u32; MAX_PLAYER_COUNT],
     pub high_score: u32,
+    run_kinds: [RunKind; MAX_PLAYER_COUNT],
+    player: usize,
+    player_count: usize,
 }
 
 impl ScoreHolder {
     pub fn new(high_score: u32) -> Self {
         Self {
-            score: 0,
+            scores: [0; MAX_PLAYER_COUNT],
             high_score,
+            run_kinds: [RunKind::Ranked; MAX_PLAYER_COUNT],
+            player: 0,
+            player_count: 1,
         }
     }
 
-    pub fn reset_score(&mut self) {
-        self.score = 0;
+    // See `AssistOptions::run_kind`.
+    pub fn reset_score(&mut self, player_count: usize, run_kind: RunKind) {
+        self.scores = [0; MAX_PLAYER_COUNT];
+        self.run_kinds = [run_kind; MAX_PLAYER_COUNT];
+        self.player = 0;
+        self.player_count = player_count.clamp(1, MAX_PLAYER_COUNT);
+    }
+
+    pub fn score(&self) -> u32 {
+        self.scores[self.player]
+    }
+
+    pub fn player(&self) -> usize {
+        self.player
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.player_count
+    }
+
+    pub fn switch_player(&mut self, player: usize) {
+        assert!(player < self.player_count);
+        self.player = player;
     }
 
     pub fn add_score(&mut self, add: u32) {
-        self.score = self.score.saturating_add(add);
-        if self.score > self.high_score {
-            self.high_score = self.score;
+        self.add_player_score(self.player, add);
+    }
+
+    pub fn add_player_score(&mut self, player: usize, add: u32) {
+        let score = &mut self.scores[player];
+        *score = score.saturating_add(add);
+        if self.run_kinds[player] == RunKind::Ranked && *score > self.high_score {
+            self.high_score = *score;
         }
     }
 
-    pub fn draw(&self, renderer: &mut impl RendererTrait, show_1up: bool) {
+    // Label of the current player blinks with `show_up`.
+    pub fn draw(&self, renderer: &mut impl RendererTrait, show_up: bool) {
+        const UP_X: [i32; MAX_PLAYER_COUNT] = [2, 23];
+        const SCORE_X: [i32; MAX_PLAYER_COUNT] = [0, 21];
+
         renderer.set_texture_color_mod("font", 255, 0, 0);
-        if show_1up {
-            renderer.draw_str("font", 2 * 8, 0 * 8, "1UP");
+        for (player, x) in UP_X.iter().enumerate().take(self.player_count) {
+            if show_up || player != self.player {
+                renderer.draw_str("font", x * 8, 0 * 8, &format!("{}UP", player + 1));
+            }
         }
         renderer.draw_str("font", 9 * 8, 0 * 8, "HIGH SCORE");
         renderer.set_texture_color_mod("font", 255, 255, 255);
 
         const MAX_DISP_SCORE: u32 = 9999999;
-        let score = std::cmp::min(self.score, MAX_DISP_SCORE);
-        renderer.draw_str("font", 0 * 8, 1 * 8, &format!("{:6}0", score / 10));
+        for (score, x) in self.scores.iter().zip(SCORE_X.iter()).take(self.player_count) {
+            let score = std::cmp::min(*score, MAX_DISP_SCORE);
+            renderer.draw_str("font", x * 8, 1 * 8, &format!("{:6}0", score / 10));
+        }
         let high_score = std::cmp::min(self.high_score, MAX_DISP_SCORE);
         renderer.draw_str("font", 10 * 8, 1 * 8, &format!("{:6}0", high_score / 10));
+
+        if self.run_kinds[self.player] == RunKind::Assisted {
+            renderer.set_texture_color_mod("font", 255, 255, 0);
+            renderer.draw_str("font", 22 * 8, 2 * 8, "ASSIST");
+        }
+    }
+}
+
+pub fn load_high_score(system: &impl SystemTrait) -> u32 {
+    let high_score = match system.get_blob(NS_SCORES) {
+        Some(blob) => blob.data_of_version(SCORES_VERSION)
+            .and_then(|data| data[KEY_HIGH_SCORE].as_u64())
+            .map(|score| score as u32),
+        None => system.get_u32(KEY_HIGH_SCORE),
+    };
+    high_score.unwrap_or(DEFAULT_HIGH_SCORE)
+}
+
+pub fn save_high_score(system: &mut impl SystemTrait, high_score: u32) {
+    system.set_blob(NS_SCORES, &Blob::new(SCORES_VERSION, json!({ KEY_HIGH_SCORE: high_score })));
+}
+
+pub fn draw_play_mode(renderer: &mut impl RendererTrait, play_mode: PlayMode) {
+    let msg = match play_mode {
+        PlayMode::Single => "UP KEY: 1 PLAYER",
+        PlayMode::Alternate => "UP KEY: {ffff00}2 PLAYERS{}",
+        PlayMode::Coop => "UP KEY: {ffff00}2 PLAYERS CO-OP{}",
+    };
+    draw_text(renderer, "font", [&Vec2I::new(0, 27 * 8), &Vec2I::new(WIDTH, 8)],
+              &TextStyle::centered((128, 128, 128)), msg);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_players() {
+        let mut score_holder = ScoreHolder::new(1000);
+        score_holder.reset_score(2, RunKind::Ranked);
+        score_holder.add_score(500);
+        score_holder.switch_player(1);
+        score_holder.add_score(1200);
+        assert_eq!([500, 1200], score_holder.scores);
+        assert_eq!(1200, score_holder.score());
+        assert_eq!(1200, score_holder.high_score);
+    }
+
+    #[test]
+    fn test_run_kind() {
+        let mut score_holder = ScoreHolder::new(1000);
+        score_holder.reset_score(2, RunKind::Assisted);
+        score_holder.add_score(1500);
+        score_holder.switch_player(1);
+        score_holder.add_score(1200);
+        assert_eq!(1000, score_holder.high_score);
+
+        score_holder.reset_score(1, RunKind::Unranked);
+        score_holder.add_score(1500);
+        assert_eq!(1000, score_holder.high_score);
+
+        score_holder.reset_score(1, RunKind::Ranked);
+        score_holder.add_score(1500);
+        assert_eq!(1500, score_holder.high_score);
+    }
+
+    #[test]
+    fn test_play_mode() {
+        let mut play_mode = PlayMode::Single;
+        let counts: Vec<usize> = (0..3)
+            .map(|_| {
+                play_mode = play_mode.next();
+                play_mode.player_count()
+            })
+            .collect();
+        assert_eq!(vec![2, 2, 1], counts);
+        assert_eq!(PlayMode::Single, play_mode);
     }
 }
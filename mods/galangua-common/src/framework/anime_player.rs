@@ -0,0 +1,118 @@
+use std::sync::Arc;
+
+use super::sprite_sheet::{AnimationClip, LoopMode};
+
+// Steps through an `AnimationClip`, one update per frame.
+#[derive(Clone)]
+pub struct AnimePlayer {
+    clip: Arc<AnimationClip>,
+    frame: usize,
+    count: u32,
+    backward: bool,
+    finished: bool,
+}
+
+impl AnimePlayer {
+    pub fn new(clip: Arc<AnimationClip>) -> Self {
+        Self {
+            clip,
+            frame: 0,
+            count: 0,
+            backward: false,
+            finished: false,
+        }
+    }
+
+    // Returns false when the clip is finished.
+    pub fn update(&mut self) -> bool {
+        if self.finished {
+            return false;
+        }
+
+        self.count += 1;
+        if self.count >= self.clip.durations[self.frame] {
+            self.count = 0;
+            self.advance();
+        }
+        !self.finished
+    }
+
+    pub fn sprite_name(&self) -> &str {
+        &self.clip.frames[self.frame]
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    fn advance(&mut self) {
+        let n = self.clip.frames.len();
+        match self.clip.loop_mode {
+            LoopMode::Once => {
+                if self.frame + 1 < n {
+                    self.frame += 1;
+                } else {
+                    self.finished = true;
+                }
+            }
+            LoopMode::Loop => {
+                self.frame = (self.frame + 1) % n;
+            }
+            LoopMode::PingPong => {
+                if n <= 1 {
+                    return;
+                }
+                if self.backward {
+                    self.frame -= 1;
+                    self.backward = self.frame > 0;
+                } else {
+                    self.frame += 1;
+                    self.backward = self.frame + 1 >= n;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clip(n: usize, duration: u32, loop_mode: LoopMode) -> Arc<AnimationClip> {
+        Arc::new(AnimationClip {
+            frames: (0..n).map(|i| format!("spr{}", i)).collect(),
+            durations: vec![duration; n],
+            loop_mode,
+        })
+    }
+
+    #[test]
+    fn test_once() {
+        let mut player = AnimePlayer::new(clip(2, 2, LoopMode::Once));
+        assert_eq!("spr0", player.sprite_name());
+        assert!(player.update());
+        assert!(player.update());
+        assert_eq!("spr1", player.sprite_name());
+        assert!(player.update());
+        assert!(!player.update());
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_loop() {
+        let mut player = AnimePlayer::new(clip(2, 1, LoopMode::Loop));
+        let frames: Vec<usize> = (0..4).map(|_| { player.update(); player.frame() }).collect();
+        assert_eq!(vec![1, 0, 1, 0], frames);
+    }
+
+    #[test]
+    fn test_pingpong() {
+        let mut player = AnimePlayer::new(clip(3, 1, LoopMode::PingPong));
+        let frames: Vec<usize> = (0..6).map(|_| { player.update(); player.frame() }).collect();
+        assert_eq!(vec![1, 2, 1, 0, 1, 2], frames);
+    }
+}
@@ -4,8 +4,10 @@ pub trait AppTrait<R: RendererTrait> {
     fn init(&mut self, renderer: &mut R);
     fn update(&mut self) -> bool;
     fn draw(&mut self, renderer: &mut R);
+    fn game_speed(&self) -> u32;  // In percent.
 
     fn on_key(&mut self, keycode: VKey, down: bool);
-    fn on_joystick_axis(&mut self, axis_index: u8, dir: i8);
-    fn on_joystick_button(&mut self, button_index: u8, down: bool);
+    // `joystick_index` is 0 for the first connected joystick, 1 for the second, and so on.
+    fn on_joystick_axis(&mut self, joystick_index: u8, axis_index: u8, dir: i8);
+    fn on_joystick_button(&mut self, joystick_index: u8, button_index: u8, down: bool);
 }
@@ -0,0 +1,77 @@
+use serde_json::Value;
+
+use super::RendererTrait;
+
+// List of asset files which an app loads, relative to `base_path`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AssetManifest {
+    pub base_path: String,
+    pub textures: Vec<String>,
+    pub sprite_sheets: Vec<String>,
+    pub fonts: Vec<String>,
+    pub sounds: Vec<String>,  // Without extension.
+}
+
+impl AssetManifest {
+    pub fn load(text: &str) -> Option<Self> {
+        let deserialized: Value = serde_json::from_str(text).ok()?;
+        Some(Self {
+            base_path: deserialized["base_path"].as_str()?.to_string(),
+            textures: convert_strings(&deserialized["textures"])?,
+            sprite_sheets: convert_strings(&deserialized["sprite_sheets"])?,
+            fonts: convert_strings(&deserialized["fonts"])?,
+            sounds: convert_strings(&deserialized["sounds"]).unwrap_or_default(),
+        })
+    }
+
+    pub fn load_into<R: RendererTrait>(&self, renderer: &mut R) {
+        let textures: Vec<&str> = self.textures.iter().chain(self.fonts.iter())
+            .map(|s| s.as_str())
+            .collect();
+        renderer.load_textures(&self.base_path, &textures);
+        for sprite_sheet in self.sprite_sheets.iter() {
+            renderer.load_sprite_sheet(&self.path(sprite_sheet));
+        }
+    }
+
+    pub fn path(&self, filename: &str) -> String {
+        format!("{}/{}", self.base_path, filename)
+    }
+
+    pub fn sound_paths(&self) -> Vec<String> {
+        self.sounds.iter().map(|s| self.path(s)).collect()
+    }
+
+    // Image and json files, which can be reloaded while running.
+    pub fn reloadable_files(&self) -> Vec<String> {
+        self.textures.iter().chain(self.fonts.iter()).chain(self.sprite_sheets.iter())
+            .cloned()
+            .collect()
+    }
+}
+
+pub(super) fn convert_strings(value: &Value) -> Option<Vec<String>> {
+    value.as_array()?.iter()
+        .map(|v| v.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load() {
+        let manifest = AssetManifest::load(r#"{
+            "base_path": "assets",
+            "textures": ["chr.png"],
+            "sprite_sheets": ["chr.json"],
+            "fonts": ["font.png"]
+        }"#).unwrap();
+        assert_eq!("assets/chr.json", manifest.path(&manifest.sprite_sheets[0]));
+        assert_eq!(vec!["chr.png", "font.png", "chr.json"], manifest.reloadable_files());
+        assert!(manifest.sounds.is_empty());
+
+        assert_eq!(None, AssetManifest::load(r#"{"base_path": "assets"}"#));
+    }
+}
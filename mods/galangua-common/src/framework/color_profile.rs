@@ -0,0 +1,109 @@
+use super::software_renderer::Image;
+
+// Simulated color vision deficiencies in RGB space.
+const PROTANOPIA: [[f32; 3]; 3] = [[0.567, 0.433, 0.0], [0.558, 0.442, 0.0], [0.0, 0.242, 0.758]];
+const DEUTERANOPIA: [[f32; 3]; 3] = [[0.625, 0.375, 0.0], [0.7, 0.3, 0.0], [0.0, 0.3, 0.7]];
+const TRITANOPIA: [[f32; 3]; 3] = [[0.95, 0.05, 0.0], [0.0, 0.433, 0.567], [0.0, 0.475, 0.525]];
+// Moves the lost difference into visible channels.
+const ERROR_SHIFT: [[f32; 3]; 3] = [[0.0, 0.0, 0.0], [0.7, 1.0, 0.0], [0.7, 0.0, 1.0]];
+
+const HIGH_CONTRAST_SATURATION: f32 = 1.5;
+const HIGH_CONTRAST: f32 = 1.4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorProfile {
+    Normal,
+    Deuteranopia,
+    Protanopia,
+    Tritanopia,
+    HighContrast,
+}
+
+impl ColorProfile {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "normal" => Some(ColorProfile::Normal),
+            "deuteranopia" => Some(ColorProfile::Deuteranopia),
+            "protanopia" => Some(ColorProfile::Protanopia),
+            "tritanopia" => Some(ColorProfile::Tritanopia),
+            "high-contrast" => Some(ColorProfile::HighContrast),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ColorProfile::Normal => ColorProfile::Deuteranopia,
+            ColorProfile::Deuteranopia => ColorProfile::Protanopia,
+            ColorProfile::Protanopia => ColorProfile::Tritanopia,
+            ColorProfile::Tritanopia => ColorProfile::HighContrast,
+            ColorProfile::HighContrast => ColorProfile::Normal,
+        }
+    }
+
+    pub fn map_color(self, r: u8, g: u8, b: u8) -> (u8, u8, u8) {
+        let c = [r as f32, g as f32, b as f32];
+        let mapped = match self {
+            ColorProfile::Normal => return (r, g, b),
+            ColorProfile::Deuteranopia => daltonize(c, &DEUTERANOPIA),
+            ColorProfile::Protanopia => daltonize(c, &PROTANOPIA),
+            ColorProfile::Tritanopia => daltonize(c, &TRITANOPIA),
+            ColorProfile::HighContrast => {
+                let l = 0.299 * c[0] + 0.587 * c[1] + 0.114 * c[2];
+                let s = c.map(|v| l + (v - l) * HIGH_CONTRAST_SATURATION);
+                s.map(|v| 128.0 + (v - 128.0) * HIGH_CONTRAST)
+            }
+        };
+        let [r, g, b] = mapped.map(|v| v.round().clamp(0.0, 255.0) as u8);
+        (r, g, b)
+    }
+
+    pub fn apply(self, image: &mut Image) {
+        if self == ColorProfile::Normal {
+            return;
+        }
+        for p in image.pixels.chunks_exact_mut(4) {
+            let (r, g, b) = self.map_color(p[0], p[1], p[2]);
+            p[0] = r;
+            p[1] = g;
+            p[2] = b;
+        }
+    }
+}
+
+fn daltonize(c: [f32; 3], simulation: &[[f32; 3]; 3]) -> [f32; 3] {
+    let sim = mul(simulation, c);
+    let error = [c[0] - sim[0], c[1] - sim[1], c[2] - sim[2]];
+    let shift = mul(&ERROR_SHIFT, error);
+    [c[0] + shift[0], c[1] + shift[1], c[2] + shift[2]]
+}
+
+fn mul(m: &[[f32; 3]; 3], v: [f32; 3]) -> [f32; 3] {
+    m.map(|row| row[0] * v[0] + row[1] * v[1] + row[2] * v[2])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_gray() {
+        let mut profile = ColorProfile::Normal;
+        loop {
+            assert_eq!((255, 255, 255), profile.map_color(255, 255, 255), "{:?}", profile);
+            assert_eq!((0, 0, 0), profile.map_color(0, 0, 0), "{:?}", profile);
+            profile = profile.next();
+            if profile == ColorProfile::Normal {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_red_green_separated() {
+        // Red and green look alike with deuteranopia, daltonized ones differ in blue.
+        let red = ColorProfile::Deuteranopia.map_color(255, 0, 0);
+        let green = ColorProfile::Deuteranopia.map_color(0, 255, 0);
+        assert!(red.2 > green.2 + 64, "{:?} {:?}", red, green);
+    }
+}
@@ -0,0 +1,140 @@
+use super::software_renderer::Image;
+
+// Output pixels per source pixel, needed to draw scanlines and shadow mask.
+pub const CRT_SCALE: u32 = 3;
+
+const SCANLINE: u32 = 96;     // Darkness of the gap between lines, /256
+const BLOOM: u32 = 80;        // Amount of blurred image added, /256
+const SHADOW_MASK: u32 = 64;  // Attenuation of non matching sub pixels, /256
+const CURVATURE: f32 = 0.06;
+
+// Applies CRT like look to a framebuffer on the CPU.
+pub struct CrtFilter {
+    src_width: u32,
+    src_height: u32,
+    // Source pixel index and brightness (/256) for each output pixel, None for outside of the tube.
+    sample_map: Vec<Option<(usize, u32)>>,
+    bloom: Vec<u8>,
+    output: Image,
+}
+
+impl CrtFilter {
+    pub fn new(src_width: u32, src_height: u32) -> Self {
+        let width = src_width * CRT_SCALE;
+        let height = src_height * CRT_SCALE;
+        let mut sample_map = Vec::with_capacity((width * height) as usize);
+        for y in 0..height {
+            for x in 0..width {
+                sample_map.push(calc_sample(x, y, width, height, src_width, src_height));
+            }
+        }
+
+        Self {
+            src_width,
+            src_height,
+            sample_map,
+            bloom: vec![0; (src_width * src_height * 4) as usize],
+            output: Image::new(width, height),
+        }
+    }
+
+    pub fn apply(&mut self, src: &Image) -> &Image {
+        assert!(src.width == self.src_width && src.height == self.src_height);
+        self.calc_bloom(src);
+
+        let width = self.output.width as usize;
+        for (i, sample) in self.sample_map.iter().enumerate() {
+            let d = &mut self.output.pixels[i * 4..i * 4 + 4];
+            if let Some((si, brightness)) = *sample {
+                let sub = (i % width) % CRT_SCALE as usize;
+                for (c, dc) in d.iter_mut().take(3).enumerate() {
+                    let mask = if c == sub { 256 } else { 256 - SHADOW_MASK };
+                    let v = src.pixels[si + c] as u32 * brightness / 256 * mask / 256
+                        + self.bloom[si + c] as u32 * BLOOM / 256;
+                    *dc = v.min(255) as u8;
+                }
+            } else {
+                d[0] = 0;
+                d[1] = 0;
+                d[2] = 0;
+            }
+            d[3] = 255;
+        }
+        &self.output
+    }
+
+    // 3x3 box blur of the source.
+    fn calc_bloom(&mut self, src: &Image) {
+        let w = self.src_width as i32;
+        let h = self.src_height as i32;
+        for y in 0..h {
+            for x in 0..w {
+                let mut sum = [0u32; 3];
+                for dy in -1..=1 {
+                    for dx in -1..=1 {
+                        let sx = (x + dx).max(0).min(w - 1);
+                        let sy = (y + dy).max(0).min(h - 1);
+                        let si = ((sy * w + sx) * 4) as usize;
+                        for (c, s) in sum.iter_mut().enumerate() {
+                            *s += src.pixels[si + c] as u32;
+                        }
+                    }
+                }
+                let di = ((y * w + x) * 4) as usize;
+                for (c, s) in sum.iter().enumerate() {
+                    self.bloom[di + c] = (s / 9) as u8;
+                }
+            }
+        }
+    }
+}
+
+fn calc_sample(x: u32, y: u32, width: u32, height: u32,
+               src_width: u32, src_height: u32) -> Option<(usize, u32)> {
+    // Barrel distortion in [-1, 1] coordinate.
+    let u = (x as f32 + 0.5) / width as f32 * 2.0 - 1.0;
+    let v = (y as f32 + 0.5) / height as f32 * 2.0 - 1.0;
+    let u = u * (1.0 + CURVATURE * v * v);
+    let v = v * (1.0 + CURVATURE * u * u);
+    if !(-1.0..1.0).contains(&u) || !(-1.0..1.0).contains(&v) {
+        return None;
+    }
+
+    let fx = (u + 1.0) * 0.5 * src_width as f32;
+    let fy = (v + 1.0) * 0.5 * src_height as f32;
+    let sx = (fx as u32).min(src_width - 1);
+    let sy = (fy as u32).min(src_height - 1);
+    // Darken the last sub row of each source line.
+    let sub_row = ((fy - sy as f32) * CRT_SCALE as f32) as u32;
+    let brightness = if sub_row + 1 >= CRT_SCALE { 256 - SCANLINE } else { 256 };
+    Some((((sy * src_width + sx) * 4) as usize, brightness))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_output_size() {
+        let mut filter = CrtFilter::new(4, 3);
+        let src = Image::new(4, 3);
+        let output = filter.apply(&src);
+        assert_eq!(4 * CRT_SCALE, output.width);
+        assert_eq!(3 * CRT_SCALE, output.height);
+        assert!(output.pixels.iter().skip(3).step_by(4).all(|&a| a == 255));
+    }
+
+    #[test]
+    fn test_center_keeps_color() {
+        let mut filter = CrtFilter::new(64, 64);
+        let mut src = Image::new(64, 64);
+        for d in src.pixels.chunks_exact_mut(4) {
+            d.copy_from_slice(&[200, 200, 200, 255]);
+        }
+        let output = filter.apply(&src);
+        let center = ((output.height / 2 * output.width + output.width / 2) * 4) as usize;
+        assert!(output.pixels[center] > 100);
+        // Corner is outside of the curved tube.
+        assert_eq!(0, output.pixels[0]);
+    }
+}
@@ -1,7 +1,15 @@
+pub mod anime_player;
+pub mod asset_manifest;
+pub mod color_profile;
 mod app_trait;
+pub mod crt_filter;
 mod renderer_trait;
+pub mod software_renderer;
 pub mod sprite_sheet;
+pub mod storage;
 mod system_trait;
+pub mod text_layout;
+pub mod theme;
 pub mod types;
 mod vkey;
 
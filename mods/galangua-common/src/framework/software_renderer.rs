@@ -0,0 +1,272 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::color_profile::ColorProfile;
+use super::sprite_sheet::SpriteSheet;
+use super::types::Vec2I;
+use super::RendererTrait;
+
+// RGBA, 8bit per channel.
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![0; (width * height * 4) as usize],
+        }
+    }
+
+    // Returns the 1 pixel border around opaque pixels in `src` area, so it is 2 pixels larger.
+    pub fn outline(&self, src: [i32; 4], color: (u8, u8, u8)) -> Image {
+        let [sx, sy, sw, sh] = src;
+        let opaque = |x: i32, y: i32| {
+            x >= 0 && y >= 0 && x < sw && y < sh &&
+                self.pixels[(((sy + y) as u32 * self.width + (sx + x) as u32) * 4 + 3) as usize] != 0
+        };
+        let mut image = Image::new(sw as u32 + 2, sh as u32 + 2);
+        for y in -1..=sh {
+            for x in -1..=sw {
+                if !opaque(x, y) && (opaque(x - 1, y) || opaque(x + 1, y) || opaque(x, y - 1) || opaque(x, y + 1)) {
+                    let i = (((y + 1) as u32 * image.width + (x + 1) as u32) * 4) as usize;
+                    image.pixels[i..i + 4].copy_from_slice(&[color.0, color.1, color.2, 255]);
+                }
+            }
+        }
+        image
+    }
+}
+
+type ImageLoader = Box<dyn Fn(&str) -> Result<Image, String>>;
+
+struct SoftTexture {
+    image: Image,
+    color_mod: (u8, u8, u8),
+}
+
+// Renders into an RGBA framebuffer on the CPU.
+pub struct SoftwareRenderer {
+    framebuffer: Image,
+    textures: HashMap<String, SoftTexture>,
+    sprite_sheet: SpriteSheet,
+    draw_color: (u8, u8, u8),
+    loader: ImageLoader,
+    color_profile: ColorProfile,
+}
+
+impl SoftwareRenderer {
+    pub fn new(width: u32, height: u32, loader: ImageLoader) -> Self {
+        Self {
+            framebuffer: Image::new(width, height),
+            textures: HashMap::new(),
+            sprite_sheet: SpriteSheet::default(),
+            draw_color: (0, 0, 0),
+            loader,
+            color_profile: ColorProfile::Normal,
+        }
+    }
+
+    pub fn framebuffer(&self) -> &Image {
+        &self.framebuffer
+    }
+
+    // Applied to textures loaded after this call.
+    pub fn set_color_profile(&mut self, color_profile: ColorProfile) {
+        self.color_profile = color_profile;
+    }
+
+    pub fn load_texture(&mut self, base_path: &str, filename: &str) -> Result<(), String> {
+        let mut image = (self.loader)(&format!("{}/{}", base_path, filename))?;
+        self.color_profile.apply(&mut image);
+        let key = Path::new(filename).file_stem().unwrap().to_str().unwrap();
+        self.textures.insert(key.to_string(), SoftTexture { image, color_mod: (255, 255, 255) });
+        Ok(())
+    }
+
+    pub fn clear_textures(&mut self) {
+        self.textures.clear();
+    }
+
+    // Makes outlines drawn around the sprites.
+    pub fn set_outline_sprites(&mut self, sprite_names: &[String], color: (u8, u8, u8)) {
+        self.textures.retain(|key, _| !key.starts_with(OUTLINE_PREFIX));
+        for sprite_name in sprite_names {
+            if let Some((sheet, tex_name)) = self.sprite_sheet.get(sprite_name) {
+                if let Some(texture) = self.textures.get(tex_name) {
+                    let src = [sheet.frame.x, sheet.frame.y, sheet.frame.w as i32, sheet.frame.h as i32];
+                    let image = texture.image.outline(src, color);
+                    self.textures.insert(outline_key(sprite_name), SoftTexture { image, color_mod: (255, 255, 255) });
+                }
+            }
+        }
+    }
+
+    pub fn outline_image(&self, sprite_name: &str) -> Option<&Image> {
+        self.textures.get(&outline_key(sprite_name)).map(|texture| &texture.image)
+    }
+
+    fn blit_outline(&mut self, sprite_name: &str, pos: &Vec2I, rot: Option<(f32, Vec2I)>) {
+        let key = outline_key(sprite_name);
+        if let Some(texture) = self.textures.get(&key) {
+            let src = [0, 0, texture.image.width as i32, texture.image.height as i32];
+            let one = Vec2I::new(1, 1);
+            self.blit(&key, src, &(pos - &one), rot.map(|(angle, center)| (angle, &center + &one)));
+        }
+    }
+
+    fn blit(&mut self, tex_name: &str, src: [i32; 4], dst: &Vec2I, rot: Option<(f32, Vec2I)>) {
+        let texture = match self.textures.get(tex_name) {
+            Some(texture) => texture,
+            None => return,
+        };
+        let [sx, sy, sw, sh] = src;
+        let fb = &mut self.framebuffer;
+        let (fw, fh) = (fb.width as i32, fb.height as i32);
+
+        // Destination bounding box, and inverse transform from destination to source.
+        let (x0, y0, x1, y1, sin, cos, center) = if let Some((angle, center)) = rot {
+            let (sin, cos) = angle.sin_cos();
+            let r = (((sw * sw + sh * sh) as f32).sqrt() as i32) + 1;
+            let c = dst + &center;
+            (c.x - r, c.y - r, c.x + r, c.y + r, sin, cos, center)
+        } else {
+            (dst.x, dst.y, dst.x + sw, dst.y + sh, 0.0, 1.0, Vec2I::new(0, 0))
+        };
+
+        for y in y0.max(0)..y1.min(fh) {
+            for x in x0.max(0)..x1.min(fw) {
+                let (u, v) = if rot.is_some() {
+                    let dx = (x - dst.x - center.x) as f32 + 0.5;
+                    let dy = (y - dst.y - center.y) as f32 + 0.5;
+                    let u = (dx * cos + dy * sin + center.x as f32).floor() as i32;
+                    let v = (-dx * sin + dy * cos + center.y as f32).floor() as i32;
+                    (u, v)
+                } else {
+                    (x - dst.x, y - dst.y)
+                };
+                if u < 0 || v < 0 || u >= sw || v >= sh {
+                    continue;
+                }
+                let si = (((sy + v) as u32 * texture.image.width + (sx + u) as u32) * 4) as usize;
+                let s = &texture.image.pixels[si..si + 4];
+                let a = s[3] as u32;
+                if a == 0 {
+                    continue;
+                }
+                let (mr, mg, mb) = texture.color_mod;
+                let di = ((y * fw + x) * 4) as usize;
+                let d = &mut fb.pixels[di..di + 4];
+                d[0] = blend(d[0], s[0] as u32 * mr as u32 / 255, a);
+                d[1] = blend(d[1], s[1] as u32 * mg as u32 / 255, a);
+                d[2] = blend(d[2], s[2] as u32 * mb as u32 / 255, a);
+                d[3] = 255;
+            }
+        }
+    }
+}
+
+const OUTLINE_PREFIX: &str = "@outline:";
+
+fn outline_key(sprite_name: &str) -> String {
+    format!("{}{}", OUTLINE_PREFIX, sprite_name)
+}
+
+fn blend(dst: u8, src: u32, alpha: u32) -> u8 {
+    ((src * alpha + dst as u32 * (255 - alpha)) / 255) as u8
+}
+
+impl RendererTrait for SoftwareRenderer {
+    fn load_textures(&mut self, base_path: &str, filenames: &[&str]) {
+        for filename in filenames {
+            self.load_texture(base_path, filename)
+                .expect("load_textures failed");
+        }
+    }
+
+    fn load_sprite_sheet(&mut self, filename: &str) {
+        let text = std::fs::read_to_string(filename)
+            .expect("load sprite sheet failed");
+        self.sprite_sheet.load_sprite_sheet(&text);
+    }
+
+    fn clear(&mut self) {
+        let (r, g, b) = self.draw_color;
+        for d in self.framebuffer.pixels.chunks_exact_mut(4) {
+            d.copy_from_slice(&[r, g, b, 255]);
+        }
+    }
+
+    // Color profile is already applied to the pixels.
+    fn set_texture_color_mod(&mut self, tex_name: &str, r: u8, g: u8, b: u8) {
+        if let Some(texture) = self.textures.get_mut(tex_name) {
+            texture.color_mod = (r, g, b);
+        }
+    }
+
+    fn set_sprite_texture_color_mod(&mut self, sprite_name: &str, r: u8, g: u8, b: u8) {
+        if let Some((_sheet, tex_name)) = self.sprite_sheet.get(sprite_name) {
+            let tex_name = tex_name.to_string();
+            self.set_texture_color_mod(&tex_name, r, g, b);
+        }
+    }
+
+    fn draw_str(&mut self, tex_name: &str, x: i32, y: i32, text: &str) {
+        let mut x = x;
+        for c in text.chars() {
+            let u: i32 = ((c as i32) - (' ' as i32)) % 16 * 8;
+            let v: i32 = ((c as i32) - (' ' as i32)) / 16 * 8;
+            self.blit(tex_name, [u, v, 8, 8], &Vec2I::new(x, y), None);
+            x += 8;
+        }
+    }
+
+    fn draw_sprite(&mut self, sprite_name: &str, pos: &Vec2I) {
+        let (sheet, tex_name) = self.sprite_sheet.get(sprite_name)
+            .expect("No sprite");
+        let pos = sheet.trim_pos(pos);
+        let src = [sheet.frame.x, sheet.frame.y, sheet.frame.w as i32, sheet.frame.h as i32];
+        let tex_name = tex_name.to_string();
+        self.blit_outline(sprite_name, &pos, None);
+        self.blit(&tex_name, src, &pos, None);
+    }
+
+    fn draw_sprite_rot(&mut self, sprite_name: &str, pos: &Vec2I, angle: u8,
+                       center: Option<&Vec2I>) {
+        let (sheet, tex_name) = self.sprite_sheet.get(sprite_name)
+            .expect("No sprite");
+        let pos = sheet.trim_pos(pos);
+        let src = [sheet.frame.x, sheet.frame.y, sheet.frame.w as i32, sheet.frame.h as i32];
+        let center = center.map_or_else(
+            || Vec2I::new(sheet.frame.w as i32 / 2, sheet.frame.h as i32 / 2),
+            |v| *v);
+        let tex_name = tex_name.to_string();
+        let angle = (angle as f32) * (2.0 * std::f32::consts::PI / 256.0);
+        self.blit_outline(sprite_name, &pos, Some((angle, center)));
+        self.blit(&tex_name, src, &pos, Some((angle, center)));
+    }
+
+    fn set_draw_color(&mut self, r: u8, g: u8, b: u8) {
+        self.draw_color = self.color_profile.map_color(r, g, b);
+    }
+
+    fn fill_rect(&mut self, dst: Option<[&Vec2I; 2]>) {
+        let (fw, fh) = (self.framebuffer.width as i32, self.framebuffer.height as i32);
+        let (x0, y0, x1, y1) = if let Some(rect) = dst {
+            (rect[0].x, rect[0].y, rect[0].x + rect[1].x, rect[0].y + rect[1].y)
+        } else {
+            (0, 0, fw, fh)
+        };
+        let (r, g, b) = self.draw_color;
+        for y in y0.max(0)..y1.min(fh) {
+            for x in x0.max(0)..x1.min(fw) {
+                let di = ((y * fw + x) * 4) as usize;
+                self.framebuffer.pixels[di..di + 4].copy_from_slice(&[r, g, b, 255]);
+            }
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use serde_json::Value;
 use std::collections::HashMap;
 use std::rc::Rc;
+use std::sync::Arc;
 
 use super::types::Vec2I;
 
@@ -14,6 +15,10 @@ impl SpriteSheet {
     pub fn load_sprite_sheet(&mut self, text: &str) -> bool {
         if let Some(sprite_sheet) = SpriteSheet1::load(text) {
             let sprite_sheet = Rc::new(sprite_sheet);
+            // Loading the same texture again replaces its sheets, for hot reloading.
+            let texture_name = &sprite_sheet.texture_name;
+            self.sprite_sheets.retain(|ss| &ss.texture_name != texture_name);
+            self.sheet_map.retain(|_key, ss| &ss.texture_name != texture_name);
             self.sprite_sheets.push(sprite_sheet.clone());
             for (key, _sheet) in sprite_sheet.as_ref().sheets.iter() {
                 self.sheet_map.insert(key.clone(), sprite_sheet.clone());
@@ -30,6 +35,88 @@ impl SpriteSheet {
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LoopMode {
+    Once,
+    Loop,
+    PingPong,
+}
+
+// Named sequence of sprites, defined in `meta.animations` of the sprite sheet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AnimationClip {
+    pub frames: Vec<String>,
+    pub durations: Vec<u32>,
+    pub loop_mode: LoopMode,
+}
+
+impl AnimationClip {
+    pub fn total_duration(&self) -> u32 {
+        self.durations.iter().sum()
+    }
+}
+
+// Clips by name, owned by the app. The game looks up clips here, not in the sprite sheets
+// which the renderer has loaded.
+#[derive(Clone, Default)]
+pub struct AnimationTable {
+    clips: HashMap<String, Arc<AnimationClip>>,
+}
+
+impl AnimationTable {
+    pub fn insert(&mut self, name: &str, clip: AnimationClip) {
+        self.clips.insert(name.to_string(), Arc::new(clip));
+    }
+
+    // Clips in the sprite sheet json replace the ones with the same name.
+    pub fn load(&mut self, text: &str) -> bool {
+        if let Some(animations) = load_animations(text) {
+            self.clips.extend(animations.into_iter().map(|(key, clip)| (key, Arc::new(clip))));
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<AnimationClip>> {
+        self.clips.get(name).cloned()
+    }
+}
+
+// Reads animation clips in the sprite sheet json:
+//   "animations": { "name": {"frames": [...], "duration": n or "durations": [...], "loop": "once"} }
+pub fn load_animations(text: &str) -> Option<HashMap<String, AnimationClip>> {
+    let deserialized: Value = serde_json::from_str(text).ok()?;
+    let mut animations = HashMap::new();
+    for (key, value) in deserialized["meta"]["animations"].as_object()? {
+        animations.insert(key.clone(), convert_animation(value)?);
+    }
+    Some(animations)
+}
+
+fn convert_animation(value: &Value) -> Option<AnimationClip> {
+    let frames: Vec<String> = value["frames"].as_array()?.iter()
+        .map(|v| v.as_str().map(get_mainname))
+        .collect::<Option<_>>()?;
+    let durations: Vec<u32> = if let Some(duration) = value["duration"].as_u64() {
+        vec![duration as u32; frames.len()]
+    } else {
+        value["durations"].as_array()?.iter()
+            .map(|v| v.as_u64().map(|d| d as u32))
+            .collect::<Option<_>>()?
+    };
+    if frames.is_empty() || durations.len() != frames.len() {
+        return None;
+    }
+    let loop_mode = match value["loop"].as_str().unwrap_or("once") {
+        "once" => LoopMode::Once,
+        "loop" => LoopMode::Loop,
+        "pingpong" => LoopMode::PingPong,
+        _ => return None,
+    };
+    Some(AnimationClip { frames, durations, loop_mode })
+}
+
 #[derive(Clone)]
 struct SpriteSheet1 {
     texture_name: String,
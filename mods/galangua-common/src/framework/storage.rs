@@ -0,0 +1,47 @@
+use serde_json::{json, Value};
+
+// Structured data saved under a namespace, `version` tells its format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Blob {
+    pub version: u32,
+    pub data: Value,
+}
+
+impl Blob {
+    pub fn new(version: u32, data: Value) -> Self {
+        Self { version, data }
+    }
+
+    pub fn from_value(value: &Value) -> Option<Self> {
+        Some(Self {
+            version: value["version"].as_u64()? as u32,
+            data: value.get("data")?.clone(),
+        })
+    }
+
+    pub fn to_value(&self) -> Value {
+        json!({"version": self.version, "data": self.data})
+    }
+
+    // Returns data only if it is saved in the given version.
+    pub fn data_of_version(self, version: u32) -> Option<Value> {
+        if self.version == version { Some(self.data) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let blob = Blob::new(2, json!({"scores": [100, 50]}));
+        let text = blob.to_value().to_string();
+        let loaded = Blob::from_value(&serde_json::from_str(&text).unwrap()).unwrap();
+        assert_eq!(blob, loaded);
+        assert_eq!(None, loaded.clone().data_of_version(1));
+        assert_eq!(Some(json!({"scores": [100, 50]})), loaded.data_of_version(2));
+
+        assert_eq!(None, Blob::from_value(&json!(123)));
+    }
+}
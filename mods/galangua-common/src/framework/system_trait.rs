@@ -1,7 +1,12 @@
+use super::storage::Blob;
+
 pub trait SystemTrait {
     fn get_u32(&self, key: &str) -> Option<u32>;
     fn set_u32(&mut self, key: &str, value: u32);
 
+    fn get_blob(&self, namespace: &str) -> Option<Blob>;
+    fn set_blob(&mut self, namespace: &str, blob: &Blob);
+
     fn is_touch_device(&self) -> bool;
 
     fn play_se(&mut self, channel: u32, filename: &str);
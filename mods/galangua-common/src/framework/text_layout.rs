@@ -0,0 +1,260 @@
+use super::types::Vec2I;
+use super::RendererTrait;
+
+// Font texture is 8x8 glyphs, 16 columns, from ' ' to '_'.
+const GLYPH_SIZE: i32 = 8;
+const FIRST_GLYPH: char = ' ';
+const LAST_GLYPH: char = '_';
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone)]
+pub struct TextStyle {
+    pub align: Align,
+    pub color: (u8, u8, u8),
+    pub wrap: bool,
+    pub proportional: bool,
+    pub line_height: i32,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            align: Align::Left,
+            color: (255, 255, 255),
+            wrap: false,
+            proportional: false,
+            line_height: GLYPH_SIZE,
+        }
+    }
+}
+
+impl TextStyle {
+    pub fn centered(color: (u8, u8, u8)) -> Self {
+        Self { align: Align::Center, color, ..Default::default() }
+    }
+
+    pub fn right(color: (u8, u8, u8)) -> Self {
+        Self { align: Align::Right, color, ..Default::default() }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Glyph {
+    pub c: char,
+    pub color: (u8, u8, u8),
+    pub x: i32,
+}
+
+#[derive(Default, PartialEq, Eq, Debug)]
+pub struct Line {
+    pub glyphs: Vec<Glyph>,
+    pub width: i32,
+}
+
+// Draws `text` in the rect (`[pos, size]`), height is ignored.
+// Color can be changed inline with `{RRGGBB}`, `{}` restores the style color and `{{` is an escaped brace, drawn as `[` (the font has no brace glyph).
+pub fn draw_text(renderer: &mut impl RendererTrait, tex_name: &str, rect: [&Vec2I; 2],
+                 style: &TextStyle, text: &str) {
+    let mut color = None;
+    for (i, line) in layout_text(text, rect[1].x, style).iter().enumerate() {
+        let x = rect[0].x + align_offset(style.align, rect[1].x, line.width);
+        let y = rect[0].y + i as i32 * style.line_height;
+
+        // Glyphs in a row with the same color are drawn at once.
+        let mut run = String::new();
+        let mut run_x = 0;
+        for glyph in line.glyphs.iter() {
+            if !run.is_empty() &&
+                (color != Some(glyph.color) || glyph.x != run_x + run.len() as i32 * GLYPH_SIZE)
+            {
+                renderer.draw_str(tex_name, x + run_x, y, &run);
+                run.clear();
+            }
+            if color != Some(glyph.color) {
+                color = Some(glyph.color);
+                renderer.set_texture_color_mod(tex_name, glyph.color.0, glyph.color.1, glyph.color.2);
+            }
+            if run.is_empty() {
+                run_x = glyph.x;
+            }
+            run.push(glyph.c);
+        }
+        if !run.is_empty() {
+            renderer.draw_str(tex_name, x + run_x, y, &run);
+        }
+    }
+}
+
+// Centered text is kept on the character grid.
+fn align_offset(align: Align, width: i32, line_width: i32) -> i32 {
+    match align {
+        Align::Left => 0,
+        Align::Center => (width - line_width) / 2 / GLYPH_SIZE * GLYPH_SIZE,
+        Align::Right => width - line_width,
+    }
+}
+
+pub fn layout_text(text: &str, width: i32, style: &TextStyle) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut line = Line::default();
+    let mut word: Vec<(char, (u8, u8, u8))> = Vec::new();
+    let mut color = style.color;
+
+    let mut chars = text.chars().peekable();
+    loop {
+        let c = chars.next();
+        match c {
+            Some('{') if chars.peek() == Some(&'{') => {
+                chars.next();
+                word.push((map_glyph('{'), color));
+                continue;
+            }
+            Some('{') => {
+                let markup: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                color = parse_color(&markup).unwrap_or(style.color);
+                continue;
+            }
+            Some(c) if c != ' ' && c != '\n' => {
+                word.push((map_glyph(c), color));
+                continue;
+            }
+            _ => {}
+        }
+
+        // End of word: put it on the current line, or on the next line when it overflows.
+        let word_width: i32 = word.iter().map(|&(c, _)| glyph_advance(c, style)).sum();
+        if style.wrap && !line.glyphs.is_empty() && line.width + word_width > width {
+            trim_trailing_space(&mut line, style);
+            lines.push(std::mem::take(&mut line));
+        }
+        for &(c, color) in word.iter() {
+            line.glyphs.push(Glyph { c, color, x: line.width });
+            line.width += glyph_advance(c, style);
+        }
+        word.clear();
+
+        match c {
+            Some(' ') => {
+                line.glyphs.push(Glyph { c: ' ', color, x: line.width });
+                line.width += glyph_advance(' ', style);
+            }
+            Some('\n') => {
+                lines.push(std::mem::take(&mut line));
+            }
+            _ => {
+                lines.push(line);
+                break;
+            }
+        }
+    }
+    lines
+}
+
+// Substitutes characters which the font doesn't have.
+pub fn map_glyph(c: char) -> char {
+    match c {
+        c if (FIRST_GLYPH..=LAST_GLYPH).contains(&c) => c,
+        c if c.is_ascii_lowercase() => c.to_ascii_uppercase(),
+        '`' | '\u{2018}' | '\u{2019}' => '\'',
+        '\u{201c}' | '\u{201d}' => '"',
+        '~' | '\u{2013}' | '\u{2014}' => '-',
+        '|' => '!',
+        '{' => '[',
+        '}' => ']',
+        '\u{d7}' => 'X',
+        _ => '?',
+    }
+}
+
+fn glyph_advance(c: char, style: &TextStyle) -> i32 {
+    if !style.proportional {
+        return GLYPH_SIZE;
+    }
+    match c {
+        '.' | ',' | ':' | ';' | '!' | '\'' => 4,
+        ' ' | 'I' | '1' | '(' | ')' => 6,
+        _ => GLYPH_SIZE,
+    }
+}
+
+fn trim_trailing_space(line: &mut Line, style: &TextStyle) {
+    while line.glyphs.last().map(|g| g.c) == Some(' ') {
+        line.glyphs.pop();
+        line.width -= glyph_advance(' ', style);
+    }
+}
+
+fn parse_color(markup: &str) -> Option<(u8, u8, u8)> {
+    if markup.len() != 6 {
+        return None;
+    }
+    let value = u32::from_str_radix(markup, 16).ok()?;
+    Some(((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_str(line: &Line) -> String {
+        line.glyphs.iter().map(|g| g.c).collect()
+    }
+
+    #[test]
+    fn test_single_line() {
+        let lines = layout_text("GAME OVER", 224, &TextStyle::default());
+        assert_eq!(1, lines.len());
+        assert_eq!("GAME OVER", line_str(&lines[0]));
+        assert_eq!(9 * 8, lines[0].width);
+    }
+
+    #[test]
+    fn test_wrap() {
+        let style = TextStyle { wrap: true, ..Default::default() };
+        let lines = layout_text("PRESS SPACE KEY", 12 * 8, &style);
+        assert_eq!(vec!["PRESS SPACE", "KEY"],
+                   lines.iter().map(line_str).collect::<Vec<_>>());
+        assert_eq!(11 * 8, lines[0].width);
+    }
+
+    #[test]
+    fn test_newline() {
+        let lines = layout_text("A\nBC", 224, &TextStyle::default());
+        assert_eq!(2, lines.len());
+        assert_eq!("BC", line_str(&lines[1]));
+    }
+
+    #[test]
+    fn test_color_markup() {
+        let lines = layout_text("A{ff0000}B{}C", 224, &TextStyle::centered((1, 2, 3)));
+        let colors: Vec<_> = lines[0].glyphs.iter().map(|g| g.color).collect();
+        assert_eq!(vec![(1, 2, 3), (255, 0, 0), (1, 2, 3)], colors);
+        assert_eq!(3 * 8, lines[0].width);
+    }
+
+    #[test]
+    fn test_escaped_brace() {
+        let lines = layout_text("{{A}", 224, &TextStyle::default());
+        assert_eq!("[A]", line_str(&lines[0]));
+    }
+
+    #[test]
+    fn test_align_offset() {
+        assert_eq!(88, align_offset(Align::Center, 224, 5 * 8));
+        assert_eq!(72, align_offset(Align::Center, 224, 9 * 8));
+        assert_eq!(184, align_offset(Align::Right, 224, 5 * 8));
+    }
+
+    #[test]
+    fn test_map_glyph() {
+        assert_eq!('A', map_glyph('a'));
+        assert_eq!('_', map_glyph('_'));
+        assert_eq!('?', map_glyph('\u{3042}'));
+    }
+}
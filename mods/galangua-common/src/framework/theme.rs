@@ -0,0 +1,91 @@
+use serde_json::Value;
+
+use super::asset_manifest::{convert_strings, AssetManifest};
+
+pub const THEME_FILE_NAME: &str = "theme.json";
+
+// Assets overriding the built-in ones. Files which are not listed fall back to the built-in theme.
+//
+// A sprite sheet whose image name differs from the built-in one overrides only
+// the sprites it contains, the same image name replaces the whole sheet.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    pub assets: AssetManifest,
+    pub star_colors: Vec<u32>,  // 0xRRGGBB
+}
+
+impl Theme {
+    pub fn load(dir: &str, text: &str) -> Option<Self> {
+        let deserialized: Value = serde_json::from_str(text).ok()?;
+        let list = |key: &str| convert_strings(&deserialized[key]).unwrap_or_default();
+        let star_colors = list("star_colors").iter()
+            .map(|s| u32::from_str_radix(s, 16).ok())
+            .collect::<Option<Vec<u32>>>()?;
+        Some(Self {
+            assets: AssetManifest {
+                base_path: dir.to_string(),
+                textures: list("textures"),
+                sprite_sheets: list("sprite_sheets"),
+                fonts: list("fonts"),
+                sounds: list("sounds"),
+            },
+            star_colors,
+        })
+    }
+
+    // Drops files which do not exist, returns their paths.
+    pub fn remove_missing(&mut self, exists: impl Fn(&str) -> bool) -> Vec<String> {
+        let assets = &mut self.assets;
+        let base_path = assets.base_path.clone();
+        let mut missing = Vec::new();
+        for (files, ext) in [(&mut assets.textures, ""), (&mut assets.sprite_sheets, ""),
+                             (&mut assets.fonts, ""), (&mut assets.sounds, ".ogg")] {
+            files.retain(|filename| {
+                let path = format!("{}/{}{}", base_path, filename, ext);
+                let found = exists(&path);
+                if !found {
+                    missing.push(path);
+                }
+                found
+            });
+        }
+        missing
+    }
+
+    // Pairs of a built-in sound path and the one which overrides it.
+    pub fn sound_overrides(&self, base: &AssetManifest) -> Vec<(String, String)> {
+        self.assets.sounds.iter()
+            .filter(|sound| base.sounds.contains(sound))
+            .map(|sound| (base.path(sound), self.assets.path(sound)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme() {
+        let mut theme = Theme::load("themes/foo", r#"{
+            "sprite_sheets": ["foo.json"],
+            "sounds": ["audio/se_pyuun", "audio/missing"],
+            "star_colors": ["ff0000", "00ff00"]
+        }"#).unwrap();
+        assert_eq!(vec![0xff0000, 0x00ff00], theme.star_colors);
+        assert!(theme.assets.fonts.is_empty());
+
+        let missing = theme.remove_missing(|path| !path.contains("missing"));
+        assert_eq!(vec!["themes/foo/audio/missing.ogg"], missing);
+
+        let base = AssetManifest {
+            base_path: "assets".to_string(),
+            sounds: vec!["audio/se_pyuun".to_string()],
+            ..AssetManifest::default()
+        };
+        assert_eq!(vec![("assets/audio/se_pyuun".to_string(), "themes/foo/audio/se_pyuun".to_string())],
+                   theme.sound_overrides(&base));
+
+        assert_eq!(None, Theme::load("themes/foo", r#"{"star_colors": ["red"]}"#));
+    }
+}
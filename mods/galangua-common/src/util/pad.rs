@@ -13,6 +13,8 @@ bitflags! {
     }
 }
 
+const AUTO_FIRE_INTERVAL: u32 = 8;
+
 #[derive(Clone, Default)]
 pub struct Pad {
     pad: PadBit,
@@ -20,13 +22,40 @@ pub struct Pad {
     last_pad: PadBit,
     key: PadBit,
     joy: PadBit,
+    auto_fire: bool,
+    auto_fire_count: u32,
+    player: usize,  // Selects the key layout.
 }
 
 impl Pad {
+    pub fn new(player: usize) -> Self {
+        Self {
+            player,
+            ..Self::default()
+        }
+    }
+
     pub fn update(&mut self) {
         self.pad = self.key | self.joy;
         self.trg = self.pad & !self.last_pad;
         self.last_pad = self.pad;
+
+        if self.auto_fire && self.pad.contains(PadBit::A) {
+            if self.trg.contains(PadBit::A) {
+                self.auto_fire_count = 0;
+            } else {
+                self.auto_fire_count += 1;
+                if self.auto_fire_count >= AUTO_FIRE_INTERVAL {
+                    self.auto_fire_count = 0;
+                    self.trg |= PadBit::A;
+                }
+            }
+        }
+    }
+
+    // Triggers A repeatedly while it is held.
+    pub fn set_auto_fire(&mut self, enable: bool) {
+        self.auto_fire = enable;
     }
 
     pub fn is_pressed(&self, btn: PadBit) -> bool {
@@ -38,7 +67,7 @@ impl Pad {
     }
 
     pub fn on_key(&mut self, keycode: VKey, down: bool) {
-        let bit = get_key_bit(keycode);
+        let bit = get_key_bit(keycode, self.player);
         if down {
             self.key |= bit;
         } else {
@@ -78,13 +107,13 @@ impl Pad {
     }
 }
 
-fn get_key_bit(key: VKey) -> PadBit {
-    match key {
-        VKey::Left => PadBit::L,
-        VKey::Right => PadBit::R,
-        VKey::Up => PadBit::U,
-        VKey::Down => PadBit::D,
-        VKey::Space => PadBit::A,
+fn get_key_bit(key: VKey, player: usize) -> PadBit {
+    match (key, player) {
+        (VKey::Left, 0) | (VKey::A, 1) => PadBit::L,
+        (VKey::Right, 0) | (VKey::D, 1) => PadBit::R,
+        (VKey::Up, 0) | (VKey::W, 1) => PadBit::U,
+        (VKey::Down, 0) | (VKey::S, 1) => PadBit::D,
+        (VKey::Space, 0) | (VKey::F, 1) => PadBit::A,
         _ => PadBit::empty(),
     }
 }
@@ -106,4 +135,28 @@ mod tests {
         assert_eq!(true, pad.is_pressed(PadBit::A));
         assert_eq!(false, pad.is_trigger(PadBit::A));
     }
+
+    #[test]
+    fn test_auto_fire() {
+        let mut pad = Pad::default();
+        pad.set_auto_fire(true);
+        pad.on_key(VKey::Space, true);
+        let triggers: Vec<u32> = (0..AUTO_FIRE_INTERVAL * 2 + 1)
+            .filter(|_| { pad.update(); pad.is_trigger(PadBit::A) })
+            .collect();
+        assert_eq!(vec![0, AUTO_FIRE_INTERVAL, AUTO_FIRE_INTERVAL * 2], triggers);
+    }
+
+    #[test]
+    fn test_key_layout() {
+        let mut pad1 = Pad::new(0);
+        let mut pad2 = Pad::new(1);
+        for pad in [&mut pad1, &mut pad2].iter_mut() {
+            pad.on_key(VKey::Space, true);
+            pad.on_key(VKey::A, true);
+            pad.update();
+        }
+        assert!(pad1.is_pressed(PadBit::A) && !pad1.is_pressed(PadBit::L));
+        assert!(!pad2.is_pressed(PadBit::A) && pad2.is_pressed(PadBit::L));
+    }
 }
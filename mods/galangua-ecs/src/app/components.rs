@@ -1,7 +1,11 @@
 use legion::*;
 
+use galangua_common::app::game::boss::{BossDefeat, BossLife};
+use galangua_common::app::game::item::{ItemType, PowerUps};
+use galangua_common::app::game::tractor_beam_table::TRACTOR_BEAM_Y_OFFSET_TABLE;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::{EnemyType, FormationIndex};
+use galangua_common::framework::anime_player::AnimePlayer;
 use galangua_common::framework::types::Vec2I;
 
 //
@@ -28,10 +32,13 @@ pub enum PlayerState {
     MoveHomePos,
 }
 pub struct Player {
+    pub index: usize,
+    pub home_x: i32,
     pub state: PlayerState,
     pub count: u32,
     pub shot_enable: bool,
     pub dual: Option<Entity>,
+    pub power_ups: PowerUps,
 }
 
 //
@@ -72,6 +79,7 @@ pub struct EnemyBase {
 pub enum ZakoAttackType {
     BeeAttack,
     Traj,
+    Transform,
 }
 #[derive(PartialEq)]
 pub enum ZakoState {
@@ -123,7 +131,21 @@ pub struct Owl {
 }
 
 //
-pub const TRACTOR_BEAM_SPRITE_COUNT: usize = 29;
+#[derive(Clone, Copy, PartialEq)]
+pub enum BossState {
+    Appearance,
+    Attack,
+    Defeat,
+}
+pub struct Boss {
+    pub base: EnemyBase,
+    pub state: BossState,
+    pub life: BossLife,
+    pub defeat: BossDefeat,
+}
+
+//
+pub const TRACTOR_BEAM_SPRITE_COUNT: usize = TRACTOR_BEAM_Y_OFFSET_TABLE.len();
 #[derive(Copy, Clone, PartialEq)]
 pub enum TractorBeamState {
     Opening,
@@ -139,6 +161,7 @@ pub struct TractorBeam {
     pub color_count: u32,
     pub size_count: i32,
     pub beam_sprites: [Option<Entity>; TRACTOR_BEAM_SPRITE_COUNT],
+    pub sprite_names: Vec<String>,
     pub capturing_player: Option<Entity>,
 }
 
@@ -157,13 +180,14 @@ pub struct Troop {
 //
 pub struct EneShot(pub Vec2I);
 
+//
+pub struct Item(pub ItemType);
+
 //
 pub struct SequentialSpriteAnime {
-    pub sprites: &'static [&'static str],
-    pub frame_wait: u32,
+    pub player: AnimePlayer,
     pub delay: u32,
     pub offset: Vec2I,
-    pub count: u32,
 }
 
 //
@@ -172,5 +196,11 @@ pub struct SpriteDrawable {
     pub offset: Vec2I,
 }
 
+// Sprite named in the animation table.
+pub struct ClipSpriteDrawable {
+    pub sprite_name: String,
+    pub offset: Vec2I,
+}
+
 //
 pub struct SpriteColor(pub u8, pub u8, pub u8);
@@ -1,13 +1,19 @@
 use atomic_refcell::AtomicRef;
 use legion::*;
 
+use galangua_common::app::assets::asset_manifest;
+use galangua_common::app::assist::{draw_assist_hint, AssistMenu, AssistOptions};
 use galangua_common::app::consts::*;
 use galangua_common::app::game::appearance_manager::AppearanceManager;
 use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::effect_table::builtin_animations;
 use galangua_common::app::game::formation::Formation;
 use galangua_common::app::game::stage_indicator::StageIndicator;
 use galangua_common::app::game::star_manager::StarManager;
-use galangua_common::app::score_holder::ScoreHolder;
+use galangua_common::app::score_holder::{draw_play_mode, load_high_score, save_high_score, PlayMode, ScoreHolder, MAX_PLAYER_COUNT};
+use galangua_common::framework::sprite_sheet::AnimationTable;
+use galangua_common::framework::text_layout::{draw_text, TextStyle};
+use galangua_common::framework::theme::Theme;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::{AppTrait, RendererTrait, SystemTrait, VKey};
 use galangua_common::util::fps_calc::{FpsCalc, TimerTrait};
@@ -27,10 +33,13 @@ pub struct GalanguaEcsApp<T: TimerTrait, S: SystemTrait> {
     system: S,
     pressed_key: Option<VKey>,
     state: AppState,
-    pad: Pad,
+    pads: [Pad; MAX_PLAYER_COUNT],
     star_manager: StarManager,
     score_holder: ScoreHolder,
     fps_calc: FpsCalc<T>,
+    theme: Option<Theme>,
+    animations: AnimationTable,
+    assist: AssistOptions,
 
     #[cfg(debug_assertions)]
     paused: bool,
@@ -38,25 +47,42 @@ pub struct GalanguaEcsApp<T: TimerTrait, S: SystemTrait> {
 
 impl<T: TimerTrait, S: SystemTrait> GalanguaEcsApp<T, S> {
     pub fn new(timer: T, system: S) -> Self {
-        let high_score = system.get_u32(KEY_HIGH_SCORE)
-                .unwrap_or(DEFAULT_HIGH_SCORE);
+        let high_score = load_high_score(&system);
+        let assist = AssistOptions::load(&system);
 
         Self {
             system,
             pressed_key: None,
             state: AppState::Title(Title::new()),
-            pad: Pad::default(),
+            pads: [Pad::new(0), Pad::new(1)],
             star_manager: StarManager::default(),
             score_holder: ScoreHolder::new(high_score),
             fps_calc: FpsCalc::new(timer),
+            theme: None,
+            animations: builtin_animations(),
+            assist,
 
             #[cfg(debug_assertions)]
             paused: false,
         }
     }
 
-    fn start_game(&mut self) {
-        self.state = AppState::Game(Box::new(Game::new(&self.star_manager, self.score_holder.high_score)));
+    // Call before `init`.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.star_manager.set_palette(&theme.star_colors);
+        self.theme = Some(theme);
+    }
+
+    // Clips of the sprite sheets, the built-in ones are used if not given.
+    pub fn set_animations(&mut self, animations: AnimationTable) {
+        self.animations = animations;
+    }
+
+    fn start_game(&mut self, play_mode: PlayMode) {
+        self.state = AppState::Game(Box::new(Game::new(&self.star_manager, self.score_holder.high_score, self.assist, play_mode, self.animations.clone())));
+        for pad in self.pads.iter_mut() {
+            pad.set_auto_fire(self.assist.auto_fire);
+        }
     }
 
     fn back_to_title(&mut self) {
@@ -72,6 +98,9 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaEcsApp<T, S> {
             }
             self.star_manager.set_stop(false);
             self.state = AppState::Title(Title::new());
+            for pad in self.pads.iter_mut() {
+                pad.set_auto_fire(false);
+            }
 
             #[cfg(debug_assertions)]
             { self.paused = false; }
@@ -83,39 +112,49 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaEcsApp<T, S> {
     }
 
     fn on_high_score_updated(&mut self) {
-        self.system.set_u32(KEY_HIGH_SCORE, self.score_holder.high_score);
+        save_high_score(&mut self.system, self.score_holder.high_score);
     }
 }
 
 impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaEcsApp<T, S> {
     fn on_key(&mut self, vkey: VKey, down: bool) {
-        self.pad.on_key(vkey, down);
+        for pad in self.pads.iter_mut() {
+            pad.on_key(vkey, down);
+        }
         if down {
             self.pressed_key = Some(vkey);
         }
     }
 
-    fn on_joystick_axis(&mut self, axis_index: u8, dir: i8) {
-        self.pad.on_joystick_axis(axis_index, dir);
+    fn on_joystick_axis(&mut self, joystick_index: u8, axis_index: u8, dir: i8) {
+        if let Some(pad) = self.pads.get_mut(joystick_index as usize) {
+            pad.on_joystick_axis(axis_index, dir);
+        }
     }
 
-    fn on_joystick_button(&mut self, button_index: u8, down: bool) {
-        self.pad.on_joystick_button(button_index, down);
+    fn on_joystick_button(&mut self, joystick_index: u8, button_index: u8, down: bool) {
+        if let Some(pad) = self.pads.get_mut(joystick_index as usize) {
+            pad.on_joystick_button(button_index, down);
+        }
     }
 
     fn init(&mut self, renderer: &mut R) {
-        renderer.load_textures("assets", &["chr.png", "font.png"]);
-        renderer.load_sprite_sheet("assets/chr.json");
+        asset_manifest().load_into(renderer);
+        if let Some(theme) = &self.theme {
+            theme.assets.load_into(renderer);
+        }
     }
 
     fn update(&mut self) -> bool {
-        self.pad.update();
+        for pad in self.pads.iter_mut() {
+            pad.update();
+        }
 
         if self.pressed_key == Some(VKey::Escape) {
-            match &self.state {
-                AppState::Title(_title) => {
+            match &mut self.state {
+                AppState::Title(title) => {
                     self.pressed_key = None;
-                    return false;
+                    return title.assist_menu.take().is_some();
                 }
                 _ => self.back_to_title(),
             }
@@ -126,7 +165,7 @@ impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaEc
             if self.pressed_key == Some(VKey::Return) {
                 self.paused = !self.paused;
             }
-            if self.paused && self.pressed_key != Some(VKey::S) {
+            if self.paused && self.pressed_key != Some(VKey::N) {
                 self.pressed_key = None;
                 return true;
             }
@@ -134,16 +173,22 @@ impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaEc
 
         match &mut self.state {
             AppState::Title(title) => {
-                if let Some(value) = title.update(&self.pad, &mut self.star_manager) {
+                let prev = self.assist;
+                let result = title.update(&self.pads[0], &mut self.star_manager, &mut self.assist);
+                if self.assist != prev {
+                    self.assist.save(&mut self.system);
+                }
+                if let Some(value) = result {
                     if value {
-                        self.start_game();
+                        let play_mode = title.play_mode;
+                        self.start_game(play_mode);
                     } else {
                         return false;
                     }
                 }
             }
             AppState::Game(game) => {
-                if !game.update(&self.pad, &mut self.system) {
+                if !game.update(&self.pads, &mut self.system) {
                     self.back_to_title();
                 }
             }
@@ -154,7 +199,7 @@ impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaEc
 
     fn draw(&mut self, renderer: &mut R) {
         match &self.state {
-            AppState::Title(title) => title.draw(&self.star_manager, &self.score_holder, self.system.is_touch_device(), renderer),
+            AppState::Title(title) => title.draw(&self.star_manager, &self.score_holder, &self.assist, self.system.is_touch_device(), renderer),
             AppState::Game(game) => game.draw(renderer),
         }
 
@@ -166,51 +211,77 @@ impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaEc
             renderer.draw_str("font", 23 * 8, 0 * 8, &format!("FPS{:2}", self.fps_calc.fps()));
         }
     }
+
+    fn game_speed(&self) -> u32 {
+        match &self.state {
+            AppState::Title(_title) => 100,
+            AppState::Game(_game) => self.assist.speed,
+        }
+    }
 }
 
 struct Title {
     frame_count: u32,
+    assist_menu: Option<AssistMenu>,
+    play_mode: PlayMode,
 }
 
 impl Title {
     fn new() -> Self {
         Self {
             frame_count: 0,
+            assist_menu: None,
+            play_mode: PlayMode::Single,
         }
     }
 
-    fn update(&mut self, pad: &Pad, star_manager: &mut StarManager) -> Option<bool> {
+    fn update(&mut self, pad: &Pad, star_manager: &mut StarManager, assist: &mut AssistOptions) -> Option<bool> {
         self.frame_count = self.frame_count.wrapping_add(1);
 
         star_manager.update();
 
-        if pad.is_trigger(PadBit::A) {
+        if let Some(assist_menu) = &mut self.assist_menu {
+            if !assist_menu.update(pad, assist) {
+                self.assist_menu = None;
+            }
+        } else if pad.is_trigger(PadBit::A) {
             return Some(true);
+        } else if pad.is_trigger(PadBit::D) {
+            self.assist_menu = Some(AssistMenu::default());
+        } else if pad.is_trigger(PadBit::U) {
+            self.play_mode = self.play_mode.next();
         }
         None
     }
 
-    fn draw(&self, star_manager: &StarManager, score_holder: &ScoreHolder, is_touch_device: bool, renderer: &mut impl RendererTrait) {
+    fn draw(&self, star_manager: &StarManager, score_holder: &ScoreHolder, assist: &AssistOptions, is_touch_device: bool, renderer: &mut impl RendererTrait) {
         renderer.set_draw_color(0, 0, 0);
         renderer.clear();
 
         star_manager.draw(renderer);
 
-        renderer.set_texture_color_mod("font", 255, 255, 255);
-        renderer.draw_str("font", 10 * 8, 8 * 8, "GALANGUA");
+        draw_text(renderer, "font", [&Vec2I::new(0, 8 * 8), &Vec2I::new(WIDTH, 8)],
+                  &TextStyle::centered((255, 255, 255)), "GALANGUA");
 
-        if self.frame_count & 32 == 0 {
+        if let Some(assist_menu) = &self.assist_menu {
+            assist_menu.draw(renderer, assist);
+        } else if self.frame_count & 32 == 0 {
             let msg = if is_touch_device {
-                "PRESS \"SHOT\" TO START"
+                "PRESS {ffff00}\"SHOT\"{} TO START"
             } else {
-                "PRESS SPACE KEY TO START"
+                "PRESS {ffff00}SPACE{} KEY TO START"
             };
-            renderer.draw_str("font", (28 - msg.len() as i32) / 2 * 8, 25 * 8, msg);
+            draw_text(renderer, "font", [&Vec2I::new(0, 25 * 8), &Vec2I::new(WIDTH, 8)],
+                      &TextStyle::centered((255, 255, 255)), msg);
+        }
+        if self.assist_menu.is_none() && !is_touch_device {
+            draw_play_mode(renderer, self.play_mode);
+            draw_assist_hint(renderer, assist);
         }
         score_holder.draw(renderer, true);
 
-        renderer.set_texture_color_mod("font", 128, 128, 128);
-        renderer.draw_str("font", WIDTH - (VERSION.len() as i32) * 8, HEIGHT - 1 * 8, VERSION);
+        draw_text(renderer, "font", [&Vec2I::new(0, HEIGHT - 1 * 8), &Vec2I::new(WIDTH, 8)],
+                  &TextStyle::right((128, 128, 128)), VERSION);
     }
 }
 
@@ -221,7 +292,10 @@ struct Game {
 }
 
 impl Game {
-    fn new(star_manager: &StarManager, high_score: u32) -> Self {
+    fn new(
+        star_manager: &StarManager, high_score: u32, assist: AssistOptions, play_mode: PlayMode,
+        animations: AnimationTable,
+    ) -> Self {
         let schedule = Schedule::builder()
             .add_system(update_game_controller_system())
             .add_system(move_star_system())
@@ -236,6 +310,7 @@ impl Game {
             .add_system(animate_zako_system())
             .add_system(move_owl_system())
             .add_system(animate_owl_system())
+            .add_system(move_boss_system())
             .add_system(move_troops_system())
             .add_system(move_tractor_beam_system())
             .add_system(spawn_eneshot_system())
@@ -243,6 +318,8 @@ impl Game {
             .add_system(coll_check_myshot_enemy_system())
             .add_system(coll_check_player_enemy_system())
             .add_system(coll_check_player_eneshot_system())
+            .add_system(move_item_system())
+            .add_system(coll_check_player_item_system())
             .add_system(recapture_fighter_system())
             .add_system(move_sequential_anime_system())
             .build();
@@ -254,16 +331,19 @@ impl Game {
         resources.insert(AppearanceManager::default());
         resources.insert(AttackManager::default());
         resources.insert(EneShotSpawner::default());
-        resources.insert(GameInfo::new(high_score));
+        resources.insert(GameInfo::new(high_score, assist, play_mode, animations));
         resources.insert(SoundQueue::new());
 
+        let home_xs: &[i32] = if play_mode == PlayMode::Coop { &COOP_PLAYER_X } else { &[CENTER_X] };
         let mut world = World::default();
-        world.push((
-            new_player(),
-            Posture(Vec2I::new(CENTER_X, PLAYER_Y), 0),
-            player_coll_rect(),
-            player_sprite(),
-        ));
+        for (index, &home_x) in home_xs.iter().enumerate() {
+            world.push((
+                new_player(index, home_x),
+                Posture(Vec2I::new(home_x, PLAYER_Y), 0),
+                player_coll_rect(),
+                player_sprite(),
+            ));
+        }
 
         Self {
             world,
@@ -272,8 +352,8 @@ impl Game {
         }
     }
 
-    fn update(&mut self, pad: &Pad, system: &mut impl SystemTrait) -> bool {
-        self.resources.insert(pad.clone());
+    fn update(&mut self, pads: &[Pad; MAX_PLAYER_COUNT], system: &mut impl SystemTrait) -> bool {
+        self.resources.insert(pads.clone());
 
         self.schedule.execute(&mut self.world, &mut self.resources);
 
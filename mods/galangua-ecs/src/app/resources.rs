@@ -4,19 +4,28 @@ use legion::*;
 use rand::{RngExt, SeedableRng};
 use rand_xoshiro::Xoshiro128Plus;
 
+use galangua_common::app::assist::AssistOptions;
 use galangua_common::app::consts::*;
 use galangua_common::app::game::appearance_manager::AppearanceManager;
+use galangua_common::app::game::appearance_table::ORDER;
 use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::boss::is_boss_stage;
+use galangua_common::app::game::difficulty::{ene_shot_speed, DifficultyProfile};
 use galangua_common::app::game::formation::Formation;
+use galangua_common::app::game::player_turn::{PlayerProgress, PlayerTurns};
+use galangua_common::app::game::score_rules::ScoreRules;
+use galangua_common::app::game::shot_pattern::{shot_velocities, update_target_trackers, ShotPattern, TargetTracker};
 use galangua_common::app::game::stage_indicator::StageIndicator;
 use galangua_common::app::game::star_manager::StarManager;
+use galangua_common::app::game::transform::TransformGroup;
 use galangua_common::app::game::{CaptureState, FormationIndex};
-use galangua_common::app::score_holder::ScoreHolder;
+use galangua_common::app::score_holder::{PlayMode, ScoreHolder, MAX_PLAYER_COUNT};
+use galangua_common::framework::sprite_sheet::AnimationTable;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::SystemTrait;
-use galangua_common::util::math::{atan2_lut, calc_velocity, clamp, ANGLE, ONE};
 
 use super::components::*;
+use super::system::system_boss::spawn_boss;
 use super::system::system_player::{enable_player_shot, enum_player_target_pos, restart_player};
 
 const WAIT1: u32 = 60;
@@ -32,6 +41,7 @@ pub enum GameState {
     Captured,
     Recapturing,
     StageClear,
+    SwitchPlayer,
     GameOver,
     Finished,
 }
@@ -46,32 +56,56 @@ pub enum StageState {
 
 pub struct GameInfo {
     pub stage: u16,
-    pub left_ship: u32,
+    pub left_ships: Vec<u32>,  // For each player on screen.
     pub game_state: GameState,
     pub count: u32,
     pub stage_state: StageState,
+    rush_threshold: u32,
     pub capture_state: CaptureState,
     pub capture_enemy_fi: FormationIndex,
     pub alive_enemy_count: u32,
+    pub transform_group: TransformGroup,
     pub score_holder: ScoreHolder,
+    pub score_rules: ScoreRules,
+    pub animations: AnimationTable,
     pub frame_count: u32,
+    pub assist: AssistOptions,
+    pub difficulty: DifficultyProfile,
+    pub turns: PlayerTurns,
+    survivors: Option<Vec<FormationIndex>>,
 }
 
 impl GameInfo {
-    pub fn new(high_score: u32) -> Self {
+    pub fn new(high_score: u32, assist: AssistOptions, play_mode: PlayMode, animations: AnimationTable) -> Self {
         let stage = 0;
+        let mut score_holder = ScoreHolder::new(high_score);
+        score_holder.reset_score(play_mode.player_count(), assist.run_kind());
+        let difficulty = DifficultyProfile::new(assist.difficulty);
+        let (turn_count, fighter_count) = match play_mode {
+            PlayMode::Single => (1, 1),
+            PlayMode::Alternate => (play_mode.player_count(), 1),
+            PlayMode::Coop => (1, play_mode.player_count()),
+        };
 
         GameInfo {
             stage,
-            left_ship: DEFAULT_LEFT_SHIP,
+            left_ships: vec![DEFAULT_LEFT_SHIP; fighter_count],
             game_state: GameState::StartStage,
             count: 0,
             stage_state: StageState::Appearance,
+            rush_threshold: difficulty.rush_threshold(),
             capture_state: CaptureState::NoCapture,
             capture_enemy_fi: FormationIndex(0, 0),
             alive_enemy_count: 0,
-            score_holder: ScoreHolder::new(high_score),
+            transform_group: TransformGroup::default(),
+            score_holder,
+            score_rules: ScoreRules::for_mode(&assist),
+            animations,
             frame_count: 0,
+            turns: PlayerTurns::new(turn_count, &PlayerProgress::new(difficulty.clone())),
+            survivors: None,
+            difficulty,
+            assist,
         }
     }
 
@@ -93,20 +127,42 @@ impl GameInfo {
                     sound_queue.push_play_se(CH_BOMB, SE_COUNT_STAGE);
                 }
                 self.count += 1;
-                if self.count >= 90 {
+                if self.count >= 90 && is_boss_stage(self.stage, self.assist.boss_interval) {
+                    *appearance_manager = AppearanceManager::default();
+                    self.survivors = None;
+                    self.start_next_stage(self.stage, formation, attack_manager, eneshot_spawner);
+                    self.rush_threshold = 0;
+                    self.stage_state = StageState::Normal;
+                    spawn_boss(self.level(), commands);
+                    self.alive_enemy_count += 1;
+                    self.game_state = GameState::Playing;
+                } else if self.count >= 90 {
                     let captured_fighter = if self.capture_state == CaptureState::Captured {
                         Some(FormationIndex(self.capture_enemy_fi.0, self.capture_enemy_fi.1 - 1))
                     } else {
                         None
                     };
-                    self.start_next_stage(self.stage, captured_fighter, formation, appearance_manager, attack_manager, eneshot_spawner);
+                    if let Some(survivors) = self.survivors.take() {
+                        appearance_manager.resume(self.stage, &survivors, captured_fighter, &self.difficulty);
+                    } else {
+                        appearance_manager.restart(self.stage, captured_fighter, &self.difficulty);
+                    }
+                    self.start_next_stage(self.stage, formation, attack_manager, eneshot_spawner);
                     self.game_state = GameState::Playing;
                 }
             }
             GameState::Playing => {
-                if self.stage_state == StageState::Cleared && self.all_destroyed(world) {
+                // Another player might be crashed during the capture sequence.
+                if <&Player>::query().iter(world).any(|player| self.is_waiting_restart(player)) {
+                    appearance_manager.pause(true);
+                    attack_manager.pause(true);
+                    star_manager.set_stop(true);
+                    self.game_state = GameState::PlayerDead;
+                    self.count = 0;
+                } else if self.stage_state == StageState::Cleared && self.all_destroyed(world) {
                     self.game_state = GameState::StageClear;
                     self.count = 0;
+                    self.difficulty.stage_cleared();
                 }
             }
             GameState::PlayerDead => {
@@ -149,6 +205,13 @@ impl GameInfo {
                     self.count = 0;
                 }
             }
+            GameState::SwitchPlayer => {
+                // Shows game over of the player before switching.
+                self.count += 1;
+                if self.left_ships[0] > 0 || self.count >= 35 * 60 / 10 {
+                    self.switch_player(appearance_manager, attack_manager, star_manager, world, commands);
+                }
+            }
             GameState::GameOver => {
                 self.count += 1;
                 if self.count >= 35 * 60 / 10 {
@@ -163,6 +226,19 @@ impl GameInfo {
         return !<&EneShot>::query().iter(world).any(|_| true);
     }
 
+    pub fn is_coop(&self) -> bool {
+        self.left_ships.len() > 1
+    }
+
+    // Players take turns with their own pad.
+    pub fn pad_index(&self, player: &Player) -> usize {
+        if self.is_coop() { player.index } else { self.turns.current() }
+    }
+
+    fn is_waiting_restart(&self, player: &Player) -> bool {
+        player.is_lost() && self.left_ships[player.index] > 0
+    }
+
     pub fn can_capture_attack(&self) -> bool {
         self.capture_state == CaptureState::NoCapture
     }
@@ -230,7 +306,9 @@ impl GameInfo {
 
     pub fn crash_player(&mut self, died: bool, appearance_manager: &mut AppearanceManager, attack_manager: &mut AttackManager) {
         if died {
-            if self.game_state != GameState::Recapturing {
+            // Crashes in the capture sequence are handled after it.
+            let in_capture = matches!(self.game_state, GameState::Capturing | GameState::Captured | GameState::Recapturing);
+            if !in_capture {
                 appearance_manager.pause(true);
                 attack_manager.pause(true);
                 self.game_state = GameState::PlayerDead;
@@ -244,22 +322,101 @@ impl GameInfo {
     }
 
     pub fn next_player(&mut self, appearance_manager: &mut AppearanceManager, attack_manager: &mut AttackManager, world: &mut SubWorld, commands: &mut CommandBuffer) {
-        self.left_ship -= 1;
-        if self.left_ship == 0 {
+        let lost: Vec<usize> = <&Player>::query().iter(world)
+            .filter(|player| self.is_waiting_restart(player))
+            .map(|player| player.index)
+            .collect();
+        for &index in lost.iter() {
+            self.left_ships[index] -= 1;
+            self.difficulty.ship_lost();
+        }
+        if self.turns.can_switch() {
+            appearance_manager.pause(true);
+            attack_manager.pause(true);
+            self.game_state = GameState::SwitchPlayer;
+            self.count = 0;
+        } else if self.left_ships.iter().all(|&left_ship| left_ship == 0) {
             appearance_manager.pause(true);
             attack_manager.pause(true);
             self.game_state = GameState::GameOver;
             self.count = 0;
         } else {
             for (player, pos, entity) in <(&mut Player, &mut Posture, Entity)>::query().iter_mut(world) {
-                restart_player(player, *entity, pos, commands);
-                enable_player_shot(player, false);
+                if lost.contains(&player.index) && self.left_ships[player.index] > 0 {
+                    restart_player(player, *entity, pos, commands);
+                    enable_player_shot(player, false);
+                }
             }
             self.game_state = GameState::WaitReady2;
             self.count = 0;
         }
     }
 
+    fn switch_player(
+        &mut self, appearance_manager: &mut AppearanceManager, attack_manager: &mut AttackManager,
+        star_manager: &mut StarManager, world: &mut SubWorld, commands: &mut CommandBuffer,
+    ) {
+        let progress = self.turns.switch(self.progress(appearance_manager, world));
+        self.restore_progress(progress);
+        self.score_holder.switch_player(self.turns.current());
+        star_manager.set_stop(false);
+
+        // Clear the stage.
+        for (_enemy, entity) in <(&Enemy, Entity)>::query().iter(world) {
+            commands.remove(*entity);
+        }
+        for (_shot, entity) in <(&EneShot, Entity)>::query().iter(world) {
+            commands.remove(*entity);
+        }
+        for (_shot, entity) in <(&MyShot, Entity)>::query().iter(world) {
+            commands.remove(*entity);
+        }
+        for (_item, entity) in <(&Item, Entity)>::query().iter(world) {
+            commands.remove(*entity);
+        }
+        *appearance_manager = AppearanceManager::default();
+        attack_manager.set_enable(false);
+        self.alive_enemy_count = 0;
+        self.transform_group = TransformGroup::default();
+
+        for (player, pos, entity) in <(&mut Player, &mut Posture, Entity)>::query().iter_mut(world) {
+            restart_player(player, *entity, pos, commands);
+            enable_player_shot(player, true);
+        }
+        self.game_state = GameState::StartStage;
+        self.count = 0;
+    }
+
+    fn progress(&self, appearance_manager: &AppearanceManager, world: &SubWorld) -> PlayerProgress {
+        // Destroyed owl can be left as a ghost without collision.
+        let alive: Vec<FormationIndex> = <(&Enemy, &CollRect)>::query().iter(world)
+            .map(|(enemy, _)| enemy.formation_index)
+            .collect();
+        let mut survivors = appearance_manager.pending_indices();
+        survivors.extend(ORDER.iter().filter(|fi| alive.contains(fi)));
+
+        PlayerProgress {
+            stage: self.stage,
+            left_ship: self.left_ships[0],
+            capture_enemy_fi: if self.capture_state == CaptureState::Captured { Some(self.capture_enemy_fi) } else { None },
+            difficulty: self.difficulty.clone(),
+            survivors: Some(survivors),
+        }
+    }
+
+    fn restore_progress(&mut self, progress: PlayerProgress) {
+        self.stage = progress.stage;
+        self.left_ships[0] = progress.left_ship;
+        self.capture_state = if progress.capture_enemy_fi.is_some() { CaptureState::Captured } else { CaptureState::NoCapture };
+        self.capture_enemy_fi = progress.capture_enemy_fi.unwrap_or(FormationIndex(0, 0));
+        self.difficulty = progress.difficulty;
+        self.survivors = progress.survivors;
+    }
+
+    pub fn level(&self) -> u16 {
+        self.difficulty.level(self.stage)
+    }
+
     pub fn decrement_alive_enemy(&mut self) {
         self.alive_enemy_count -= 1;
     }
@@ -268,34 +425,30 @@ impl GameInfo {
         self.game_state == GameState::Playing && self.stage_state == StageState::Rush
     }
 
-    pub fn add_score(&mut self, add: u32, sound_queue: &mut SoundQueue) {
-        let before = self.score_holder.score;
-        self.score_holder.add_score(add);
-        let ext = if before < EXTEND_FIRST_SCORE {
-            EXTEND_FIRST_SCORE
-        } else {
-            (before + EXTEND_AFTER_SCORE - 1) / EXTEND_AFTER_SCORE * EXTEND_AFTER_SCORE
-        };
-        if before + add >= ext {
-            self.extend_ship(sound_queue);
+    pub fn add_score(&mut self, player_index: usize, add: u32, sound_queue: &mut SoundQueue) {
+        let player = if self.is_coop() { player_index } else { self.score_holder.player() };
+        let before = self.score_holder.scores[player];
+        self.score_holder.add_player_score(player, add);
+        if self.score_rules.is_extended(before, add) {
+            self.extend_ship(player_index, sound_queue);
         }
     }
 
-    fn extend_ship(&mut self, sound_queue: &mut SoundQueue) {
-        self.left_ship += 1;
+    fn extend_ship(&mut self, player_index: usize, sound_queue: &mut SoundQueue) {
+        self.left_ships[player_index] += 1;
         sound_queue.push_play_se(CH_JINGLE, SE_EXTEND_SHIP);
     }
 
     fn start_next_stage(
-        &mut self, stage: u16, captured_fighter: Option<FormationIndex>, formation: &mut Formation,
-        appearance_manager: &mut AppearanceManager, attack_manager: &mut AttackManager,
+        &mut self, stage: u16, formation: &mut Formation, attack_manager: &mut AttackManager,
         eneshot_spawner: &mut EneShotSpawner,
     ) {
-        formation.restart();
-        appearance_manager.restart(stage, captured_fighter);
-        attack_manager.restart(stage);
+        formation.restart(stage);
+        attack_manager.restart(stage, &self.difficulty);
         eneshot_spawner.restart();
+        self.rush_threshold = self.difficulty.rush_threshold();
         self.stage_state = StageState::Appearance;
+        self.transform_group = TransformGroup::default();
     }
 
     fn check_stage_state(&mut self, appearance_manager: &AppearanceManager) {
@@ -307,9 +460,9 @@ impl GameInfo {
         }
 
         let new_state = match self.alive_enemy_count {
-            n if n == 0               => StageState::Cleared,
-            n if n <= RUSH_THRESHOLD  => StageState::Rush,
-            _                         => self.stage_state,
+            n if n == 0                    => StageState::Cleared,
+            n if n <= self.rush_threshold => StageState::Rush,
+            _                              => self.stage_state,
         };
         if new_state != self.stage_state {
             self.stage_state = new_state;
@@ -321,16 +474,18 @@ impl GameInfo {
 
 #[derive(Default)]
 pub struct EneShotSpawner {
-    queue: Vec<Vec2I>,
+    queue: Vec<(Vec2I, ShotPattern)>,
     shot_paused_count: u32,
+    target_trackers: [TargetTracker; MAX_PLAYER_COUNT],
 }
 
 impl EneShotSpawner {
-    pub fn push(&mut self, pos: &Vec2I) {
-        self.queue.push(*pos);
+    pub fn push(&mut self, pos: &Vec2I, pattern: ShotPattern) {
+        self.queue.push((*pos, pattern));
     }
 
     pub fn update(&mut self, game_info: &GameInfo, world: &SubWorld, commands: &mut CommandBuffer) {
+        update_target_trackers(&mut self.target_trackers, &enum_player_target_pos(world));
         if self.shot_paused_count > 0 {
             self.shot_paused_count -= 1;
         } else {
@@ -346,6 +501,7 @@ impl EneShotSpawner {
     pub fn restart(&mut self) {
         self.shot_paused_count = 0;
         self.queue.clear();
+        self.target_trackers = Default::default();
     }
 
     fn process_queue(&mut self, game_info: &GameInfo, world: &SubWorld, commands: &mut CommandBuffer) {
@@ -353,17 +509,17 @@ impl EneShotSpawner {
         let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
         let target_pos = enum_player_target_pos(world);
         let count = target_pos.len();
-        for (pos, _i) in self.queue.iter().zip(shot_count..MAX_ENE_SHOT_COUNT) {
-            let target: &Vec2I = target_pos.get(rng.random_range(0..count)).unwrap();
-
-            let d = target - pos;
-            let angle = atan2_lut(d.y, -d.x);  // 0=down
-            let limit = ANGLE * ONE * 30 / 360;
-            let angle = clamp(angle, -limit, limit);
-            let vel = calc_velocity(angle + ANGLE * ONE / 2, calc_ene_shot_speed(game_info.stage));
+        let speed = ene_shot_speed(game_info.level());
+        let shots = self.queue.iter().flat_map(|(pos, pattern)| {
+            let (player, target) = target_pos.get(rng.random_range(0..count)).unwrap();
+            shot_velocities(*pattern, pos, target, self.target_trackers[*player].vel(), speed)
+                .into_iter()
+                .map(move |vel| (*pos, vel))
+        });
+        for ((pos, vel), _i) in shots.zip(shot_count..MAX_ENE_SHOT_COUNT) {
             commands.push((
                 EneShot(vel),
-                Posture(*pos, 0),
+                Posture(pos, 0),
                 CollRect { offset: Vec2I::new(-1, -4), size: Vec2I::new(1, 8) },
                 SpriteDrawable { sprite_name: "ene_shot", offset: Vec2I::new(-2, -4) },
             ));
@@ -371,12 +527,6 @@ impl EneShotSpawner {
     }
 }
 
-fn calc_ene_shot_speed(stage: u16) -> i32 {
-    const MAX_STAGE: i32 = 64;
-    let per = std::cmp::min(stage as i32, MAX_STAGE) * ONE / MAX_STAGE;
-    (ENE_SHOT_SPEED2 - ENE_SHOT_SPEED1) * per / ONE + ENE_SHOT_SPEED1
-}
-
 //
 
 pub struct SoundQueue {
@@ -1,4 +1,5 @@
 pub mod system;
+pub mod system_boss;
 mod system_effect;
 mod system_enemy;
 mod system_owl;
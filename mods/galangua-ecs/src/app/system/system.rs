@@ -6,29 +6,40 @@ use galangua_common::app::consts::*;
 use galangua_common::app::game::appearance_manager::Accessor as AppearanceManagerAccessor;
 use galangua_common::app::game::appearance_manager::AppearanceManager;
 use galangua_common::app::game::attack_manager::Accessor as AttackManagerAccessor;
-use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::attack_manager::{AttackManager, AttackType};
+use galangua_common::app::game::boss::draw_boss;
 use galangua_common::app::game::formation::Formation;
+use galangua_common::app::game::item::{draw_item, fall_item, ItemType};
 use galangua_common::app::game::stage_indicator::StageIndicator;
 use galangua_common::app::game::star_manager::StarManager;
 use galangua_common::app::game::{CaptureState, EnemyType, FormationIndex};
+use galangua_common::app::score_holder::MAX_PLAYER_COUNT;
 use galangua_common::app::util::collision::CollBox;
+use galangua_common::framework::text_layout::{draw_text, TextStyle};
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
 use galangua_common::util::math::{quantize_angle, round_vec};
-use galangua_common::util::pad::{Pad, PadBit};
+use galangua_common::util::pad::Pad;
 
 use crate::app::components::*;
 use crate::app::resources::*;
 
+use super::system_boss::*;
 use super::system_effect::*;
 use super::system_enemy::*;
 use super::system_owl::*;
 use super::system_player::*;
 
+const MYSHOT_COUNT: usize = 2;
+
 #[system]
 #[write_component(Player)]
 #[write_component(Posture)]
 #[read_component(EneShot)]
+#[read_component(MyShot)]
+#[read_component(Item)]
+#[read_component(Enemy)]
+#[read_component(CollRect)]
 pub fn update_game_controller(
     world: &mut SubWorld,
     #[resource] game_info: &mut GameInfo,
@@ -50,19 +61,25 @@ pub fn update_game_controller(
 #[write_component(Posture)]
 pub fn move_player(
     player: &mut Player, entity: &Entity,
-    #[resource] pad: &Pad,
+    #[resource] pads: &[Pad; MAX_PLAYER_COUNT],
     #[resource] game_info: &mut GameInfo,
     #[resource] attack_manager: &mut AttackManager,
     world: &mut SubWorld, commands: &mut CommandBuffer,
 ) {
+    let pad = &pads[game_info.pad_index(player)];
     do_move_player(player, pad, *entity, game_info, attack_manager, world, commands);
 }
 
 #[system(for_each)]
 #[read_component(MyShot)]
-pub fn fire_myshot(player: &Player, posture: &Posture, entity: &Entity, world: &mut SubWorld, #[resource] pad: &Pad, #[resource] sound_queue: &mut SoundQueue, commands: &mut CommandBuffer) {
-    let shot_count = <&MyShot>::query().iter(world).count();
-    if pad.is_trigger(PadBit::A) && shot_count < 2 && do_fire_myshot(player, posture, *entity, commands) {
+#[read_component(Posture)]
+pub fn fire_myshot(player: &Player, entity: &Entity, world: &mut SubWorld, #[resource] pads: &[Pad; MAX_PLAYER_COUNT], #[resource] game_info: &GameInfo, #[resource] sound_queue: &mut SoundQueue, commands: &mut CommandBuffer) {
+    let pad = &pads[game_info.pad_index(player)];
+    let posture = <&Posture>::query().get(world, *entity).unwrap();
+    let shot_count = <&MyShot>::query().iter(world)
+        .filter(|shot| shot.player_entity == *entity)
+        .count();
+    if player.power_ups.is_fire(pad) && shot_count < player.power_ups.myshot_count(MYSHOT_COUNT) && do_fire_myshot(player, posture, *entity, commands) {
         sound_queue.push_play_se(CH_SHOT, SE_MYSHOT);
     }
 }
@@ -94,6 +111,10 @@ pub fn run_appearance_manager(world: &mut SubWorld, #[resource] appearance_manag
                 EnemyType::Butterfly => "dman1",
                 EnemyType::Owl => "cpp11",
                 EnemyType::CapturedFighter => "rustacean_captured",
+                EnemyType::Scorpion => "scorpion1",
+                EnemyType::Stingray => "stingray1",
+                EnemyType::Flagship => "flagship1",
+                EnemyType::Boss => { panic!("Illegal"); }
             };
 
             let enemy = Enemy { enemy_type: e.enemy_type, formation_index: e.fi, is_formation: false };
@@ -139,12 +160,13 @@ pub fn run_attack_manager(world: &mut SubWorld, #[resource] attack_manager: &mut
         let accessor = SysAttackManagerAccessor(world, game_info);
         attack_manager.update(&accessor)
     };
-    if let Some((fi, capture_attack)) = result {
+    if let Some((fi, attack_type)) = result {
         let get_player_pos = || {
-            if let Some((_player, posture)) = <(&Player, &Posture)>::query().iter(world).next() {
-                return Some(posture.0);
-            }
-            None
+            let players: Vec<(&Player, &Posture)> = <(&Player, &Posture)>::query().iter(world).collect();
+            players.iter()
+                .find(|(player, _)| !player.is_lost())
+                .or_else(|| players.first())
+                .map(|(_player, posture)| posture.0)
         };
 
         let entity_opt = <(&Enemy, Option<&Owl>, Entity)>::query().iter(world)
@@ -157,17 +179,19 @@ pub fn run_attack_manager(world: &mut SubWorld, #[resource] attack_manager: &mut
             });
         if let Some((entity, is_owl)) = entity_opt {
             if is_owl {
+                let capture_attack = attack_type == AttackType::Capture;
                 let player_pos = get_player_pos().unwrap();
+                let escorts = attack_manager.choose_escorts(&fi, &SysAttackManagerAccessor(world, game_info));
                 let (mut subworld1, mut subworld2) = world.split::<(&mut Owl, &mut Speed)>();
                 let (owl, speed) = <(&mut Owl, &mut Speed)>::query().get_mut(&mut subworld1, entity).unwrap();
-                owl_start_attack(owl, capture_attack, speed, &player_pos, entity, sound_queue, &mut subworld2, commands);
+                owl_start_attack(owl, capture_attack, &escorts, speed, &player_pos, entity, sound_queue, &mut subworld2, commands);
                 if capture_attack {
                     game_info.capture_state = CaptureState::CaptureAttacking;
                     game_info.capture_enemy_fi = fi;
                 }
             } else {
                 let (zako, enemy, posture) = <(&mut Zako, &mut Enemy, &mut Posture)>::query().get_mut(world, entity).unwrap();
-                zako_start_attack(zako, enemy, posture, sound_queue);
+                zako_start_attack(zako, enemy, posture, attack_type, sound_queue);
             }
             attack_manager.put_attacker(&fi);
         }
@@ -246,6 +270,23 @@ pub fn move_owl(
     do_move_owl(owl, *entity, speed, formation, eneshot_spawner, sound_queue, game_info, world, commands);
 }
 
+#[system(for_each)]
+pub fn move_boss(
+    boss: &mut Boss, posture: &mut Posture, speed: &mut Speed, entity: &Entity,
+    #[resource] formation: &Formation,
+    #[resource] eneshot_spawner: &mut EneShotSpawner,
+    #[resource] sound_queue: &mut SoundQueue,
+    #[resource] game_info: &mut GameInfo,
+    commands: &mut CommandBuffer,
+) {
+    if boss.state == BossState::Defeat {
+        update_boss_defeat(boss, posture, *entity, game_info, sound_queue, commands);
+    } else {
+        let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
+        update_boss_attack(boss, posture, speed, &mut accessor);
+    }
+}
+
 #[system(for_each)]
 pub fn animate_owl(
     owl: &Owl, sprite: &mut SpriteDrawable,
@@ -294,6 +335,7 @@ pub fn move_eneshot(shot: &mut EneShot, posture: &mut Posture, entity: &Entity,
 #[read_component(Zako)]
 #[write_component(Enemy)]
 #[write_component(Owl)]
+#[write_component(Boss)]
 #[write_component(TractorBeam)]
 #[write_component(Troops)]
 #[write_component(SpriteDrawable)]
@@ -344,6 +386,7 @@ pub fn coll_check_myshot_enemy(
 #[write_component(Player)]
 #[write_component(Enemy)]
 #[write_component(Owl)]
+#[write_component(Boss)]
 #[write_component(TractorBeam)]
 #[write_component(Troops)]
 #[write_component(SpriteDrawable)]
@@ -381,11 +424,18 @@ pub fn coll_check_player_enemy(
         set_enemy_damage(
             enemy_type, enemy_entity, 100, player_entity, star_manager, attack_manager, eneshot_spawner,
             sound_queue, game_info, world, commands);
-
-        create_player_explosion_effect(&pl_pos, commands);
+        if game_info.assist.invincible {
+            continue;  // Rammed enemy is destroyed, but not the player.
+        }
 
         let (mut subworld1, mut subworld2) = world.split::<&mut Player>();
         let player = <&mut Player>::query().get_mut(&mut subworld1, player_entity).unwrap();
+        if player.power_ups.consume_shield() {
+            sound_queue.push_play_se(CH_BOMB, SE_SHIELD);
+            continue;
+        }
+
+        create_player_explosion_effect(&pl_pos, &game_info.animations, commands);
         set_damage_to_player(player, dual, player_entity, game_info, star_manager, appearance_manager, attack_manager, sound_queue, &mut subworld2, commands);
     }
 }
@@ -417,6 +467,10 @@ pub fn coll_check_player_eneshot(
     #[resource] game_info: &mut GameInfo,
     commands: &mut CommandBuffer,
 ) {
+    if game_info.assist.invincible {
+        return;
+    }
+
     let mut colls: Vec<(Entity, Vec2I, bool)> = Vec::new();
     for (player, player_pos, player_coll_rect, player_entity) in <(&Player, &Posture, &CollRect, Entity)>::query().iter(world) {
         let player_poses = [
@@ -437,17 +491,71 @@ pub fn coll_check_player_eneshot(
     }
 
     for (player_entity, pl_pos, dual) in colls {
-        create_player_explosion_effect(&pl_pos, commands);
-
         let (mut subworld1, mut subworld2) = world.split::<&mut Player>();
         let player = <&mut Player>::query().get_mut(&mut subworld1, player_entity).unwrap();
+        if player.power_ups.consume_shield() {
+            sound_queue.push_play_se(CH_BOMB, SE_SHIELD);
+            continue;
+        }
+
+        create_player_explosion_effect(&pl_pos, &game_info.animations, commands);
         set_damage_to_player(player, dual, player_entity, game_info, star_manager, appearance_manager, attack_manager, sound_queue, &mut subworld2, commands);
     }
 }
 
 #[system(for_each)]
-pub fn move_sequential_anime(anime: &mut SequentialSpriteAnime, drawable: Option<&mut SpriteDrawable>, entity: &Entity, commands: &mut CommandBuffer) {
-    update_seqanime(anime, drawable, *entity, commands);
+pub fn move_item(_item: &Item, posture: &mut Posture, entity: &Entity, commands: &mut CommandBuffer) {
+    if !fall_item(&mut posture.0) {
+        commands.remove(*entity);
+    }
+}
+
+#[system]
+#[read_component(Item)]
+#[read_component(EneShot)]
+#[read_component(Posture)]
+#[read_component(CollRect)]
+#[write_component(Player)]
+pub fn coll_check_player_item(
+    world: &mut SubWorld,
+    #[resource] sound_queue: &mut SoundQueue,
+    commands: &mut CommandBuffer,
+) {
+    let mut colls: Vec<(Entity, ItemType)> = Vec::new();
+    for (item, item_pos, item_coll_rect, item_entity) in <(&Item, &Posture, &CollRect, Entity)>::query().iter(world) {
+        let item_collbox = pos_to_coll_box(&item_pos.0, item_coll_rect);
+        let picker = <(&Player, &Posture, &CollRect, Entity)>::query().iter(world)
+            .find(|(player, player_pos, player_coll_rect, _)| {
+                let player_poses = [
+                    Some(player_pos.0),
+                    player.dual.map(|dual| <&Posture>::query().get(world, dual).unwrap().0),
+                ];
+                player_poses.iter().flatten()
+                    .any(|pl_pos| pos_to_coll_box(pl_pos, player_coll_rect).check_collision(&item_collbox))
+            })
+            .map(|(_, _, _, player_entity)| *player_entity);
+        if let Some(player_entity) = picker {
+            colls.push((player_entity, item.0));
+            commands.remove(*item_entity);
+        }
+    }
+
+    for (player_entity, item_type) in colls {
+        let player = <&mut Player>::query().get_mut(world, player_entity).unwrap();
+        player.power_ups.apply(item_type);
+        if item_type == ItemType::Bomb {
+            for (_eneshot, eneshot_entity) in <(&EneShot, Entity)>::query().iter(world) {
+                commands.remove(*eneshot_entity);
+            }
+            sound_queue.push_play_se(CH_BOMB, SE_ITEM_BOMB);
+        }
+        sound_queue.push_play_se(CH_JINGLE, SE_GET_ITEM);
+    }
+}
+
+#[system(for_each)]
+pub fn move_sequential_anime(anime: &mut SequentialSpriteAnime, entity: &Entity, commands: &mut CommandBuffer) {
+    update_seqanime(anime, *entity, commands);
 }
 
 #[system]
@@ -472,58 +580,99 @@ pub fn draw_system(world: &World, resources: &Resources, renderer: &mut impl Ren
 
     let white = SpriteColor(255, 255, 255);
     for (posture, drawable, sprite_color_opt) in <(&Posture, &SpriteDrawable, Option<&SpriteColor>)>::query().iter(world) {
-        let pos = &round_vec(&posture.0) + &drawable.offset;
-        let angle = quantize_angle(posture.1, ANGLE_DIV);
+        draw_sprite(renderer, drawable.sprite_name, posture, &drawable.offset, sprite_color_opt.unwrap_or(&white));
+    }
+    for (posture, drawable, sprite_color_opt) in <(&Posture, &ClipSpriteDrawable, Option<&SpriteColor>)>::query().iter(world) {
+        draw_sprite(renderer, &drawable.sprite_name, posture, &drawable.offset, sprite_color_opt.unwrap_or(&white));
+    }
+    for (posture, anime) in <(&Posture, &SequentialSpriteAnime)>::query().iter(world) {
+        if anime.delay == 0 {
+            draw_sprite(renderer, anime.player.sprite_name(), posture, &anime.offset, &white);
+        }
+    }
 
-        let sprite_color = sprite_color_opt.unwrap_or(&white);
-        renderer.set_sprite_texture_color_mod(drawable.sprite_name, sprite_color.0, sprite_color.1, sprite_color.2);
+    for (item, posture) in <(&Item, &Posture)>::query().iter(world) {
+        draw_item(renderer, item.0, &posture.0);
+    }
 
-        if angle == 0 {
-            renderer.draw_sprite(drawable.sprite_name, &pos);
-        } else {
-            renderer.draw_sprite_rot(drawable.sprite_name, &pos, angle, None);
-        }
+    let frame_count = resources.get::<GameInfo>().unwrap().frame_count;
+    for (boss, posture) in <(&Boss, &Posture)>::query().iter(world) {
+        let broken_parts = if boss.state == BossState::Defeat { boss.defeat.broken_parts() } else { 0 };
+        draw_boss(renderer, &posture.0, ((frame_count >> 5) & 1) as usize, &boss.life, broken_parts);
+        boss.life.draw_bar(renderer);
+        boss.defeat.draw_bonus(renderer, &posture.0);
     }
 
     let stage_indicator = resources.get::<StageIndicator>().unwrap();
     stage_indicator.draw(renderer);
 
+    for player in <&Player>::query().iter(world) {
+        let (x, right_align) = if player.index == 0 { (0, false) } else { (WIDTH, true) };
+        player.power_ups.draw(renderer, x, right_align);
+    }
+
     let game_info = resources.get::<GameInfo>().unwrap();
-    if game_info.left_ship > 0 {
-        let disp_count = std::cmp::min(game_info.left_ship - 1, 8);
-        renderer.set_sprite_texture_color_mod("rustacean", 255, 255, 255);
+    renderer.set_sprite_texture_color_mod("rustacean", 255, 255, 255);
+    // Second player's ships are lined from the right.
+    for (index, &left_ship) in game_info.left_ships.iter().enumerate().filter(|(_, &left_ship)| left_ship > 0) {
+        let disp_count = std::cmp::min(left_ship - 1, 8);
         for i in 0..disp_count {
-            renderer.draw_sprite("rustacean", &Vec2I::new(i as i32 * 16, HEIGHT - 16));
+            let x = if index == 0 { i as i32 * 16 } else { WIDTH - 16 - i as i32 * 16 };
+            renderer.draw_sprite("rustacean", &Vec2I::new(x, HEIGHT - 16));
         }
     }
 
     game_info.score_holder.draw(renderer, (game_info.frame_count & 31) < 16);
 
+    match game_info.game_state {
+        GameState::StartStage | GameState::WaitReady2 | GameState::SwitchPlayer | GameState::GameOver
+            if game_info.turns.is_two_players() =>
+        {
+            draw_text(renderer, "font", [&Vec2I::new(0, 16 * 8), &Vec2I::new(WIDTH, 8)],
+                      &TextStyle::centered((0, 255, 255)), &format!("PLAYER {}", game_info.turns.current() + 1));
+        }
+        _ => {}
+    }
+
     match game_info.game_state {
         GameState::StartStage => {
-            renderer.set_texture_color_mod("font", 0, 255, 255);
-            renderer.draw_str("font", 10 * 8, 18 * 8, &format!("STAGE {}", game_info.stage + 1));
+            draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                      &TextStyle::centered((0, 255, 255)), &format!("STAGE {}", game_info.stage + 1));
         }
         GameState::WaitReady | GameState::WaitReady2 => {
-            if game_info.left_ship > 1 || game_info.game_state == GameState::WaitReady2 {
-                renderer.set_texture_color_mod("font", 0, 255, 255);
-                renderer.draw_str("font", (28 - 6) / 2 * 8, 18 * 8, "READY");
+            let left_ship = game_info.left_ships.iter().copied().max().unwrap();
+            if (left_ship > 1 && !game_info.turns.can_switch()) || game_info.game_state == GameState::WaitReady2 {
+                draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((0, 255, 255)), "READY");
             }
         }
         GameState::Captured => {
             if game_info.count < 120 {
-                renderer.set_texture_color_mod("font", 255, 0, 0);
-                renderer.draw_str("font", (28 - 16) / 2 * 8, 19 * 8, "FIGHTER CAPTURED");
+                draw_text(renderer, "font", [&Vec2I::new(0, 19 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((255, 0, 0)), "FIGHTER CAPTURED");
             }
         }
-        GameState::GameOver => {
-            renderer.set_texture_color_mod("font", 0, 255, 255);
-            renderer.draw_str("font", (28 - 8) / 2 * 8, 18 * 8, "GAME OVER");
+        GameState::SwitchPlayer | GameState::GameOver if game_info.left_ships[0] == 0 => {
+            draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                      &TextStyle::centered((0, 255, 255)), "GAME OVER");
         }
         _ => {}
     }
 }
 
+fn draw_sprite(renderer: &mut impl RendererTrait, sprite_name: &str, posture: &Posture, offset: &Vec2I, sprite_color: &SpriteColor) {
+    let pos = &round_vec(&posture.0) + offset;
+    let angle = quantize_angle(posture.1, ANGLE_DIV);
+
+    renderer.set_sprite_texture_color_mod(sprite_name, sprite_color.0, sprite_color.1, sprite_color.2);
+
+    if angle == 0 {
+        renderer.draw_sprite(sprite_name, &pos);
+    } else {
+        renderer.draw_sprite_rot(sprite_name, &pos, angle, None);
+    }
+}
+
 //
 
 fn pos_to_coll_box(pos: &Vec2I, coll_rect: &CollRect) -> CollBox {
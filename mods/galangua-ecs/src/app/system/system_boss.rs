@@ -0,0 +1,86 @@
+use legion::systems::CommandBuffer;
+use legion::*;
+
+use galangua_common::app::consts::*;
+use galangua_common::app::game::boss::*;
+use galangua_common::app::game::effect_table::to_earned_point_type;
+use galangua_common::app::game::traj::Traj;
+use galangua_common::app::game::traj_command_table::BOSS_APPEARANCE_TABLE;
+use galangua_common::app::game::EnemyType;
+use galangua_common::framework::types::ZERO_VEC;
+
+use crate::app::components::*;
+use crate::app::resources::{GameInfo, SoundQueue};
+
+use super::system_effect::*;
+use super::system_enemy::EneBaseAccessorImpl;
+
+pub fn spawn_boss(level: u16, commands: &mut CommandBuffer) {
+    let traj = Traj::new(&BOSS_APPEARANCE_TABLE, &ZERO_VEC, false, BOSS_FORMATION_INDEX);
+    let boss = Boss {
+        base: EnemyBase::new(Some(traj)),
+        state: BossState::Appearance,
+        life: BossLife::new(level),
+        defeat: BossDefeat::default(),
+    };
+    let enemy = Enemy { enemy_type: EnemyType::Boss, formation_index: BOSS_FORMATION_INDEX, is_formation: false };
+    let coll_rect = CollRect { offset: BOSS_COLL_OFFSET, size: BOSS_COLL_SIZE };
+    commands.push((enemy, boss, Posture(BOSS_HOME_POS, 0), Speed(0, 0), coll_rect));
+}
+
+pub fn update_boss_attack(boss: &mut Boss, posture: &mut Posture, speed: &mut Speed, accessor: &mut EneBaseAccessorImpl) {
+    if !boss.base.update_trajectory(posture, speed, accessor) {
+        // Repeats the table of the current phase from the home position.
+        let mut traj = Traj::new(boss.life.phase_table(), &ZERO_VEC, false, BOSS_FORMATION_INDEX);
+        traj.set_pos(&BOSS_HOME_POS);
+        boss.base.traj = Some(traj);
+        boss.state = BossState::Attack;
+    }
+}
+
+pub fn update_boss_defeat(
+    boss: &mut Boss, posture: &Posture, entity: Entity, game_info: &mut GameInfo,
+    sound_queue: &mut SoundQueue, commands: &mut CommandBuffer,
+) {
+    match boss.defeat.update(&posture.0) {
+        Some(BossDefeatEvent::ExplodePart(pos)) => {
+            create_enemy_explosion_effect(&pos, 1, &game_info.animations, commands);
+            sound_queue.push_play_se(CH_BOMB, SE_BOMB_ZAKO);
+        }
+        Some(BossDefeatEvent::Bonus) => {
+            sound_queue.push_play_se(CH_JINGLE, SE_RECAPTURE);
+        }
+        None => {}
+    }
+    if boss.defeat.is_done() {
+        commands.remove(entity);
+        game_info.decrement_alive_enemy();
+    }
+}
+
+// Returns true when destroyed, the boss is kept alive during the defeat sequence.
+pub fn set_boss_damage(
+    boss: &mut Boss, posture: &Posture, power: u32, player_index: usize,
+    game_info: &mut GameInfo, sound_queue: &mut SoundQueue, commands: &mut CommandBuffer,
+) -> bool {
+    if boss.state == BossState::Defeat {
+        return false;
+    }
+    if !boss.life.set_damage(power) {
+        sound_queue.push_play_se(CH_BOMB, SE_DAMAGE);
+        return false;
+    }
+
+    let point = game_info.score_rules.enemy_point(EnemyType::Boss, false);
+    let bonus = game_info.score_rules.boss_bonus;
+    if let Some(point_type) = to_earned_point_type(point) {
+        create_earned_piont_effect(point_type, &posture.0, &game_info.animations, commands);
+    }
+    create_flash_enemy_effect(&posture.0, 0, EnemyType::Owl, &game_info.animations, commands);
+    sound_queue.push_play_se(CH_JINGLE, SE_BOMB_CAPTURED);
+    game_info.add_score(player_index, point + bonus, sound_queue);
+
+    boss.state = BossState::Defeat;
+    boss.defeat = BossDefeat::new(bonus);
+    true
+}
@@ -5,15 +5,19 @@ use rand::{RngExt, SeedableRng};
 use rand_xoshiro::Xoshiro128Plus;
 
 use galangua_common::app::consts::*;
-use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::attack_manager::{AttackManager, AttackType};
+use galangua_common::app::game::difficulty::{attack_shot_count, attack_shot_interval};
 use galangua_common::app::game::effect_table::{to_earned_point_type, FLASH_ENEMY_FRAME};
 use galangua_common::app::game::formation::Formation;
 use galangua_common::app::game::formation_table::{X_COUNT, Y_COUNT};
+use galangua_common::app::game::item::{roll_item_drop, ITEM_SIZE};
+use galangua_common::app::game::shot_pattern::ShotPattern;
 use galangua_common::app::game::star_manager::StarManager;
 use galangua_common::app::game::traj::Accessor as TrajAccessor;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::traj_command::TrajCommand;
 use galangua_common::app::game::traj_command_table::*;
+use galangua_common::app::game::transform::*;
 use galangua_common::app::game::{EnemyType, FormationIndex};
 use galangua_common::framework::types::{Vec2I, ZERO_VEC};
 use galangua_common::util::math::{atan2_lut, calc_velocity, clamp, diff_angle, normalize_angle, square, ANGLE, ONE, ONE_BIT};
@@ -21,18 +25,22 @@ use galangua_common::util::math::{atan2_lut, calc_velocity, clamp, diff_angle, n
 use crate::app::components::*;
 use crate::app::resources::{EneShotSpawner, GameInfo, SoundQueue};
 
+use super::system_boss::set_boss_damage;
 use super::system_effect::*;
 use super::system_owl::set_owl_damage;
 use super::system_player::enum_player_target_pos;
 
 const BEE_SPRITE_NAMES: [&str; 2] = ["gopher1", "gopher2"];
 const BUTTERFLY_SPRITE_NAMES: [&str; 2] = ["dman1", "dman2"];
+const SCORPION_SPRITE_NAMES: [&str; 2] = ["scorpion1", "scorpion2"];
+const STINGRAY_SPRITE_NAMES: [&str; 2] = ["stingray1", "stingray2"];
+const FLAGSHIP_SPRITE_NAMES: [&str; 2] = ["flagship1", "flagship2"];
 
 struct Vtable {
     rush_traj_table: &'static [TrajCommand],
 }
 
-const VTABLE: [Vtable; 4] = [
+const VTABLE: [Vtable; 7] = [
     // Bee
     Vtable {
         rush_traj_table: &BEE_RUSH_ATTACK_TABLE,
@@ -49,6 +57,19 @@ const VTABLE: [Vtable; 4] = [
     Vtable {
         rush_traj_table: &OWL_RUSH_ATTACK_TABLE,
     },
+    // Transformed enemies escape without rush, these are not used.
+    // Scorpion
+    Vtable {
+        rush_traj_table: &SCORPION_ATTACK_TABLE,
+    },
+    // Stingray
+    Vtable {
+        rush_traj_table: &STINGRAY_ATTACK_TABLE,
+    },
+    // Flagship
+    Vtable {
+        rush_traj_table: &FLAGSHIP_ATTACK_TABLE,
+    },
 ];
 
 pub fn forward(posture: &mut Posture, speed: &Speed) {
@@ -91,6 +112,15 @@ pub fn set_enemy_damage(
     world: &mut SubWorld,
     commands: &mut CommandBuffer,
 ) {
+    let player_index = <&Player>::query().get(world, player_entity).map_or(0, |player| player.index);
+    if enemy_type == EnemyType::Boss {
+        let (boss, posture) = <(&mut Boss, &Posture)>::query().get_mut(world, entity).unwrap();
+        if set_boss_damage(boss, posture, power, player_index, game_info, sound_queue, commands) {
+            commands.remove_component::<CollRect>(entity);
+        }
+        return;
+    }
+
     let point = match enemy_type {
         EnemyType::Owl => {
             let (mut subworld1, mut subworld2) = world.split::<&mut Owl>();
@@ -101,7 +131,7 @@ pub fn set_enemy_damage(
         }
         _ => {
             let is_formation = <&Zako>::query().get(world, entity).unwrap().state == ZakoState::Formation;
-            let point = calc_zako_point(enemy_type, is_formation);
+            let point = game_info.score_rules.enemy_point(enemy_type, is_formation);
             assert!(point > 0);
             commands.remove(entity);
             if enemy_type == EnemyType::CapturedFighter {
@@ -117,13 +147,30 @@ pub fn set_enemy_damage(
         let posture = <&Posture>::query().get(world, entity).unwrap();
 
         if let Some(point_type) = to_earned_point_type(point) {
-            create_earned_piont_effect(point_type, &posture.0, commands);
+            create_earned_piont_effect(point_type, &posture.0, &game_info.animations, commands);
+        }
+
+        if is_transformed(enemy_type) {
+            if let Some(group_type) = game_info.transform_group.destroyed() {
+                let bonus = game_info.score_rules.transformed_group_bonus(group_type);
+                if let Some(point_type) = to_earned_point_type(bonus) {
+                    create_earned_piont_effect(point_type, &posture.0, &game_info.animations, commands);
+                }
+                game_info.add_score(player_index, bonus, sound_queue);
+            }
         }
 
-        create_flash_enemy_effect(&posture.0, posture.1, enemy_type, commands);
-        create_enemy_explosion_effect(&posture.0, FLASH_ENEMY_FRAME, commands);
+        create_flash_enemy_effect(&posture.0, posture.1, enemy_type, &game_info.animations, commands);
+        create_enemy_explosion_effect(&posture.0, FLASH_ENEMY_FRAME, &game_info.animations, commands);
+
+        if enemy_type == EnemyType::Owl && game_info.assist.arrange {
+            if let Some(item_type) = roll_item_drop() {
+                let coll_rect = CollRect { offset: Vec2I::new(-ITEM_SIZE / 2, -ITEM_SIZE / 2), size: Vec2I::new(ITEM_SIZE, ITEM_SIZE) };
+                commands.push((Item(item_type), Posture(posture.0, 0), coll_rect));
+            }
+        }
 
-        game_info.add_score(point, sound_queue);
+        game_info.add_score(player_index, point, sound_queue);
         game_info.decrement_alive_enemy();
     }
 }
@@ -139,7 +186,7 @@ pub fn do_move_zako(
 ) {
     match zako.state {
         ZakoState::Appearance => {
-            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
             let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
             if !zako.base.update_trajectory(posture, speed, &mut accessor) {
                 zako.base.traj = None;
@@ -159,7 +206,7 @@ pub fn do_move_zako(
             posture.1 -= clamp(posture.1, -ang, ang);
         }
         ZakoState::Attack(t) => {
-            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
             let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
             zako.base.update_attack(&posture.0, true, &mut accessor);
             match t {
@@ -169,6 +216,9 @@ pub fn do_move_zako(
                 ZakoAttackType::Traj => {
                     update_attack_traj(zako, enemy, posture, speed, formation, eneshot_spawner, sound_queue, game_info, entity, commands);
                 }
+                ZakoAttackType::Transform => {
+                    update_transform(zako, enemy, posture, speed, formation, eneshot_spawner, game_info, entity, commands);
+                }
             }
         }
         ZakoState::MoveToFormation => {
@@ -193,13 +243,15 @@ pub fn do_move_zako(
     }
 }
 
-pub fn zako_start_attack(zako: &mut Zako, enemy: &mut Enemy, posture: &Posture, sound_queue: &mut SoundQueue) {
+pub fn zako_start_attack(zako: &mut Zako, enemy: &mut Enemy, posture: &Posture, attack_type: AttackType, sound_queue: &mut SoundQueue) {
     let flip_x = enemy.formation_index.0 >= (X_COUNT as u8) / 2;
     let (table, state): (&[TrajCommand], ZakoState) = match enemy.enemy_type {
+        EnemyType::Bee if attack_type == AttackType::Transform => (&BEE_TRANSFORM_TABLE, ZakoState::Attack(ZakoAttackType::Transform)),
         EnemyType::Bee => (&BEE_ATTACK_TABLE, ZakoState::Attack(ZakoAttackType::BeeAttack)),
         EnemyType::Butterfly => (&BUTTERFLY_ATTACK_TABLE, ZakoState::Attack(ZakoAttackType::Traj)),
         EnemyType::Owl => (&OWL_ATTACK_TABLE, ZakoState::Attack(ZakoAttackType::Traj)),
         EnemyType::CapturedFighter => (&OWL_ATTACK_TABLE, ZakoState::Attack(ZakoAttackType::Traj)),
+        _ => { panic!("Illegal"); }
     };
     let mut traj = Traj::new(table, &ZERO_VEC, flip_x, enemy.formation_index);
     traj.set_pos(&posture.0);
@@ -214,7 +266,7 @@ pub fn zako_start_attack(zako: &mut Zako, enemy: &mut Enemy, posture: &Posture,
 }
 
 fn update_bee_attack(zako: &mut Zako, enemy: &Enemy, posture: &mut Posture, speed: &mut Speed, formation: &Formation, eneshot_spawner: &mut EneShotSpawner, game_info: &GameInfo) {
-    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
     if !zako.base.update_trajectory(posture, speed, &mut accessor) {
         if game_info.is_rush() {
             let flip_x = enemy.formation_index.0 >= 5;
@@ -232,12 +284,16 @@ fn update_bee_attack(zako: &mut Zako, enemy: &Enemy, posture: &mut Posture, spee
 }
 
 fn update_attack_traj(zako: &mut Zako, enemy: &Enemy, posture: &mut Posture, speed: &mut Speed, formation: &Formation, eneshot_spawner: &mut EneShotSpawner, sound_queue: &mut SoundQueue, game_info: &mut GameInfo, entity: Entity, commands: &mut CommandBuffer) {
-    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
     if !zako.base.update_trajectory(posture, speed, &mut accessor) {
         zako.base.traj = None;
         if enemy.enemy_type == EnemyType::CapturedFighter {
             commands.remove(entity);
             game_info.decrement_alive_enemy();
+        } else if is_transformed(enemy.enemy_type) {
+            commands.remove(entity);
+            game_info.decrement_alive_enemy();
+            game_info.transform_group.escaped();
         } else if game_info.is_rush() {
             // Rush mode: Continue attacking
             let table = VTABLE[enemy.enemy_type as usize].rush_traj_table;
@@ -249,24 +305,41 @@ fn update_attack_traj(zako: &mut Zako, enemy: &Enemy, posture: &mut Posture, spe
     }
 }
 
-pub fn set_zako_to_troop(zako: &mut Zako, enemy: &mut Enemy) {
-    zako.state = ZakoState::Troop;
-    enemy.is_formation = false;
+fn update_transform(zako: &mut Zako, enemy: &Enemy, posture: &mut Posture, speed: &mut Speed, formation: &Formation, eneshot_spawner: &mut EneShotSpawner, game_info: &mut GameInfo, entity: Entity, commands: &mut CommandBuffer) {
+    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
+    if !zako.base.update_trajectory(posture, speed, &mut accessor) {
+        commands.remove(entity);
+        game_info.decrement_alive_enemy();
+
+        let flip_x = enemy.formation_index.0 >= 5;
+        spawn_transformed_enemies(&posture.0, flip_x, game_info, commands);
+    }
 }
 
-fn calc_zako_point(enemy_type: EnemyType, is_formation: bool) -> u32 {
-    match enemy_type {
-        EnemyType::Bee => {
-            if is_formation { 50 } else { 100 }
-        }
-        EnemyType::Butterfly => {
-            if is_formation { 80 } else { 160 }
-        }
-        EnemyType::CapturedFighter => {
-            if is_formation { 500 } else { 1000 }
-        }
-        _ => { panic!("Illegal"); }
+fn spawn_transformed_enemies(pos: &Vec2I, flip_x: bool, game_info: &mut GameInfo, commands: &mut CommandBuffer) {
+    let enemy_type = transformed_type(game_info.stage);
+    let table = transformed_attack_table(enemy_type);
+    for (i, offset) in TRANSFORM_OFFSETS.iter().enumerate() {
+        let fi = FormationIndex(i as u8, TRANSFORM_FORMATION_Y);
+        let mut traj = Traj::new(table, offset, flip_x, fi);
+        traj.set_pos(pos);
+
+        let enemy = Enemy { enemy_type, formation_index: fi, is_formation: false };
+        let zako = Zako { base: EnemyBase::new(Some(traj)), state: ZakoState::Attack(ZakoAttackType::Traj) };
+        let posture = Posture(*pos, 0);
+        let speed = Speed(0, 0);
+        let coll_rect = CollRect { offset: Vec2I::new(-6, -6), size: Vec2I::new(12, 12) };
+        let mut drawable = SpriteDrawable { sprite_name: "", offset: Vec2I::new(-8, -8) };
+        do_animate_zako(enemy_type, &mut drawable, game_info.frame_count);
+        commands.push((enemy, zako, posture, speed, coll_rect, drawable));
+        game_info.alive_enemy_count += 1;
     }
+    game_info.transform_group.start(enemy_type);
+}
+
+pub fn set_zako_to_troop(zako: &mut Zako, enemy: &mut Enemy) {
+    zako.state = ZakoState::Troop;
+    enemy.is_formation = false;
 }
 
 pub fn do_animate_zako(enemy_type: EnemyType, sprite: &mut SpriteDrawable, frame_count: u32) {
@@ -275,6 +348,9 @@ pub fn do_animate_zako(enemy_type: EnemyType, sprite: &mut SpriteDrawable, frame
         EnemyType::Bee => BEE_SPRITE_NAMES[pat],
         EnemyType::Butterfly => BUTTERFLY_SPRITE_NAMES[pat],
         EnemyType::CapturedFighter => "rustacean_captured",
+        EnemyType::Scorpion => SCORPION_SPRITE_NAMES[pat],
+        EnemyType::Stingray => STINGRAY_SPRITE_NAMES[pat],
+        EnemyType::Flagship => FLAGSHIP_SPRITE_NAMES[pat],
         _ => { panic!("Illegal"); }
     };
 }
@@ -296,9 +372,9 @@ fn out_of_screen(pos: &Vec2I) -> bool {
 //
 
 pub trait EneBaseAccessorTrait {
-    fn fire_shot(&mut self, pos: &Vec2I);
+    fn fire_shot(&mut self, pos: &Vec2I, pattern: ShotPattern);
     fn traj_accessor<'a>(&'a mut self) -> Box<dyn TrajAccessor + 'a>;
-    fn get_stage_no(&self) -> u16;
+    fn get_level(&self) -> u16;
 }
 
 impl EnemyBase {
@@ -327,7 +403,7 @@ impl EnemyBase {
                 if wait > 0 {
                     self.shot_wait = Some(wait - 1);
                 } else {
-                    accessor.fire_shot(&posture.0);
+                    accessor.fire_shot(&posture.0, traj.shot_pattern());
                     self.shot_wait = None;
                 }
             }
@@ -373,15 +449,14 @@ impl EnemyBase {
     pub fn update_attack(&mut self, pos: &Vec2I, shot_enable: bool, accessor: &mut impl EneBaseAccessorTrait) -> bool {
         self.attack_frame_count += 1;
 
-        let stage_no = accessor.get_stage_no();
-        let shot_count = std::cmp::min(2 + stage_no / 8, 5) as u32;
-        let shot_interval = 20 - shot_count * 2;
+        let shot_count = attack_shot_count(accessor.get_level());
+        let shot_interval = attack_shot_interval(accessor.get_level());
 
         if self.attack_frame_count <= shot_interval * shot_count &&
             self.attack_frame_count % shot_interval == 0
         {
             if shot_enable {
-                accessor.fire_shot(pos);
+                self.fire_shot(pos, accessor);
             }
             true
         } else {
@@ -389,6 +464,16 @@ impl EnemyBase {
         }
     }
 
+    fn fire_shot(&mut self, pos: &Vec2I, accessor: &mut impl EneBaseAccessorTrait) {
+        let pattern = self.traj.as_ref().map_or(ShotPattern::Aimed, |traj| traj.shot_pattern());
+        let delay = pattern.delay();
+        if delay == 0 {
+            accessor.fire_shot(pos, pattern);
+        } else if self.shot_wait.is_none() {
+            self.shot_wait = Some(delay);
+        }
+    }
+
     pub fn rush_attack(&mut self, table: &'static [TrajCommand], posture: &Posture, fi: &FormationIndex) {
         let flip_x = fi.0 >= 5;
         let mut traj = Traj::new(table, &ZERO_VEC, flip_x, *fi);
@@ -404,7 +489,7 @@ impl EnemyBase {
 
         let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
         let count = target_pos.len();
-        let target: &Vec2I = target_pos.get(rng.random_range(0..count)).unwrap();
+        let (_, target) = target_pos.get(rng.random_range(0..count)).unwrap();
 
         self.target_pos = *target;
         speed.1 = 0;
@@ -414,40 +499,48 @@ impl EnemyBase {
 pub struct EneBaseAccessorImpl<'l> {
     pub formation: &'l Formation,
     pub eneshot_spawner: &'l mut EneShotSpawner,
-    pub stage_no: u16,
+    pub level: u16,
+    pub shot_pattern_enabled: bool,
 }
 
 impl<'l> EneBaseAccessorImpl<'l> {
-    pub fn new(formation: &'l Formation, eneshot_spawner: &'l mut EneShotSpawner, stage_no: u16) -> Self {
+    pub fn new(formation: &'l Formation, eneshot_spawner: &'l mut EneShotSpawner, game_info: &GameInfo) -> Self {
         Self {
             formation,
             eneshot_spawner,
-            stage_no,
+            level: game_info.level(),
+            shot_pattern_enabled: game_info.difficulty.is_shot_pattern_enabled(),
         }
     }
 }
 
 impl<'a> EneBaseAccessorTrait for EneBaseAccessorImpl<'a> {
-    fn fire_shot(&mut self, pos: &Vec2I) {
-        self.eneshot_spawner.push(pos);
+    fn fire_shot(&mut self, pos: &Vec2I, pattern: ShotPattern) {
+        self.eneshot_spawner.push(pos, pattern);
     }
 
     fn traj_accessor<'b>(&'b mut self) -> Box<dyn TrajAccessor + 'b> {
-        Box::new(TrajAccessorImpl { formation: self.formation, stage_no: self.stage_no })
+        Box::new(TrajAccessorImpl {
+            formation: self.formation,
+            level: self.level,
+            shot_pattern_enabled: self.shot_pattern_enabled,
+        })
     }
 
-    fn get_stage_no(&self) -> u16 { self.stage_no }
+    fn get_level(&self) -> u16 { self.level }
 }
 
 //
 
 struct TrajAccessorImpl<'a> {
     formation: &'a Formation,
-    pub stage_no: u16,
+    pub level: u16,
+    pub shot_pattern_enabled: bool,
 }
 impl<'a> TrajAccessor for TrajAccessorImpl<'a> {
     fn get_formation_pos(&self, formation_index: &FormationIndex) -> Vec2I {
         self.formation.pos(formation_index)
     }
-    fn get_stage_no(&self) -> u16 { self.stage_no }
+    fn get_level(&self) -> u16 { self.level }
+    fn is_shot_pattern_enabled(&self) -> bool { self.shot_pattern_enabled }
 }
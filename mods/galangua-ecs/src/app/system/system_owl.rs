@@ -4,8 +4,10 @@ use legion::*;
 
 use galangua_common::app::consts::*;
 use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::attack_strategy::Escorts;
 use galangua_common::app::game::formation::Formation;
 use galangua_common::app::game::formation_table::{X_COUNT, Y_COUNT};
+use galangua_common::app::game::shot_pattern::ShotPattern;
 use galangua_common::app::game::star_manager::StarManager;
 use galangua_common::app::game::tractor_beam_table::*;
 use galangua_common::app::game::traj::Traj;
@@ -54,7 +56,7 @@ pub fn do_move_owl(
     match owl.state {
         OwlState::Appearance => {
             let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
-            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+            let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
             if !owl.base.update_trajectory(posture, speed, &mut accessor) {
                 owl.base.traj = None;
                 let enemy = <&Enemy>::query().get(world, entity).unwrap();
@@ -108,7 +110,7 @@ pub fn do_move_owl(
 }
 
 pub fn owl_start_attack(
-    owl: &mut Owl, capture_attack: bool, speed: &mut Speed, player_pos: &Vec2I,
+    owl: &mut Owl, capture_attack: bool, escorts: &Escorts, speed: &mut Speed, player_pos: &Vec2I,
     entity: Entity,
     sound_queue: &mut SoundQueue,
     world: &mut SubWorld, commands: &mut CommandBuffer,
@@ -117,7 +119,7 @@ pub fn owl_start_attack(
     let flip_x = fi.0 >= (X_COUNT as u8) / 2;
     if !capture_attack {
         let pos = <&Posture>::query().get(world, entity).unwrap().0;
-        choose_troops(entity, &fi, &pos, world, commands);
+        choose_troops(entity, &fi, &pos, escorts, world, commands);
 
         let posture = <&Posture>::query().get(world, entity).unwrap();
         let table: &[TrajCommand] = &OWL_ATTACK_TABLE;
@@ -160,7 +162,7 @@ fn update_attack_traj(
     world: &mut SubWorld,
 ) {
     let result = {
-        let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+        let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
         owl.base.update_attack(&<&mut Posture>::query().get_mut(world, entity).unwrap().0, owl.life > 0, &mut accessor)
      };
      if result {
@@ -168,13 +170,13 @@ fn update_attack_traj(
         if let Some(troops) = troops_opt {
             for troop in troops.members.iter().flatten() {
                 if let Ok(troop_pos) = <&Posture>::query().get(world, troop.entity) {
-                    eneshot_spawner.push(&troop_pos.0);
+                    eneshot_spawner.push(&troop_pos.0, ShotPattern::Aimed);
                 }
             }
         }
     }
 
-    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info.stage);
+    let mut accessor = EneBaseAccessorImpl::new(formation, eneshot_spawner, game_info);
     let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
     if !owl.base.update_trajectory(posture, speed, &mut accessor) {
         owl.base.traj = None;
@@ -243,7 +245,7 @@ fn run_capture_attack(
 
                 commands.add_component(
                     entity,
-                    create_tractor_beam(&(&*pos + &Vec2I::new(0, 8 * ONE))));
+                    create_tractor_beam(&(&*pos + &Vec2I::new(0, 8 * ONE)), tractor_beam_sprite_names(&game_info.animations)));
 
                 sound_queue.push_play_se(CH_JINGLE, SE_TRACTOR_BEAM1);
 
@@ -408,11 +410,11 @@ pub fn set_owl_damage(
             let guard_count = if let Ok(troops) = <&Troops>::query().get_mut(world, entity) {
                 troops.members.iter().flatten()
                     .filter(|troop| troop.is_guard)
-                    .count() as u32
+                    .count()
             } else {
                 0
             };
-            calc_point(is_formation, guard_count)
+            game_info.score_rules.owl_point(is_formation, guard_count)
         };
 
         {
@@ -434,6 +436,7 @@ pub fn set_owl_damage(
             }
         }
 
+        let mut rescued = false;
         let keep_alive_as_ghost = {
             let (mut subworld1, mut subworld2) = world.split::<&mut Troops>();
             if let Ok(troops) = <&mut Troops>::query().get_mut(&mut subworld1, entity) {
@@ -450,6 +453,7 @@ pub fn set_owl_damage(
                     start_recapturing(*troop_entity, player_entity, attack_manager, sound_queue, game_info, &subworld2, commands);
 
                     *slot = None;
+                    rescued = true;
                 }
                 troops.members.iter().any(|x| x.is_some())
             } else {
@@ -459,6 +463,12 @@ pub fn set_owl_damage(
 
         owl.capturing_state = OwlCapturingState::None;
 
+        let bonus = game_info.score_rules.rescue_bonus;
+        if rescued && bonus > 0 {
+            let player_index = <&Player>::query().get(world, player_entity).map_or(0, |player| player.index);
+            game_info.add_score(player_index, bonus, sound_queue);
+        }
+
         eneshot_spawner.pause_enemy_shot(OWL_DESTROY_SHOT_WAIT);
 
         sound_queue.push_play_se(CH_BOMB, SE_BOMB_ZAKO);
@@ -474,14 +484,6 @@ pub fn set_owl_damage(
     }
 }
 
-fn calc_point(is_formation: bool, guard_count: u32) -> u32 {
-    if is_formation {
-        150
-    } else {
-        (1 << guard_count) * 400
-    }
-}
-
 fn start_recapturing(
     owner_entity: Entity, player_entity: Entity,
     attack_manager: &mut AttackManager,
@@ -508,7 +510,7 @@ pub fn do_animate_owl(owl: &Owl, sprite: &mut SpriteDrawable, frame_count: u32)
 
 // Tractor Beam
 
-fn create_tractor_beam(pos: &Vec2I) -> TractorBeam {
+fn create_tractor_beam(pos: &Vec2I, sprite_names: Vec<String>) -> TractorBeam {
     TractorBeam {
         pos: *pos,
         state: TractorBeamState::Opening,
@@ -516,6 +518,7 @@ fn create_tractor_beam(pos: &Vec2I) -> TractorBeam {
         color_count: 0,
         size_count: 0,
         beam_sprites: [None; TRACTOR_BEAM_SPRITE_COUNT],
+        sprite_names,
         capturing_player: None,
     }
 }
@@ -544,14 +547,16 @@ pub fn do_move_tractor_beam(
             let an = tractor_beam.size_count / ONE;
             if an != pn {
                 let i = pn as usize;
-                let entity = commands.push((
-                    Posture(&tractor_beam.pos + &Vec2I::new(0, TRACTOR_BEAM_Y_OFFSET_TABLE[i] * ONE), 0),
-                    SpriteDrawable { sprite_name: TRACTOR_BEAM_SPRITE_NAMES[i], offset: Vec2I::new(-24, 0) },
-                    SpriteColor(0, 0, 0),
-                ));
-                tractor_beam.beam_sprites[i] = Some(entity);
-
-                if an as usize >= TRACTOR_BEAM_SPRITE_NAMES.len() {
+                if let Some(sprite_name) = tractor_beam.sprite_names.get(i) {
+                    let entity = commands.push((
+                        Posture(&tractor_beam.pos + &Vec2I::new(0, TRACTOR_BEAM_Y_OFFSET_TABLE[i] * ONE), 0),
+                        ClipSpriteDrawable { sprite_name: sprite_name.clone(), offset: Vec2I::new(-24, 0) },
+                        SpriteColor(0, 0, 0),
+                    ));
+                    tractor_beam.beam_sprites[i] = Some(entity);
+                }
+
+                if an as usize >= TRACTOR_BEAM_SPRITE_COUNT {
                     tractor_beam.size_count = an * ONE;
                     tractor_beam.state = Full;
                     tractor_beam.count = 0;
@@ -603,7 +608,7 @@ pub fn do_move_tractor_beam(
         Closed => {}
         Capturing => {
             let player_entity = tractor_beam.capturing_player.unwrap();
-            let (player, posture) = <(&mut Player, &mut Posture)>::query().iter_mut(world).find(|_| true).unwrap();
+            let (player, posture) = <(&mut Player, &mut Posture)>::query().get_mut(world, player_entity).unwrap();
             move_capturing_player(player, posture, &(&tractor_beam.pos + &Vec2I::new(0, 8 * ONE)));
             if player.is_captured() {
                 on_player_captured(
@@ -634,8 +639,8 @@ fn can_tractor_beam_capture_player(
     world: &SubWorld,
 ) -> Option<Entity> {
     const RANGE: i32 = 24 * ONE;
-    for (_player, player_pos, entity) in <(&Player, &Posture, Entity)>::query().iter(world) {
-        if game_info.can_capture() &&
+    for (player, player_pos, entity) in <(&Player, &Posture, Entity)>::query().iter(world) {
+        if game_info.can_capture() && player.state == PlayerState::Normal &&
             (player_pos.0.x - beam_pos.x).abs() <= RANGE
         {
             return Some(*entity)
@@ -723,14 +728,15 @@ fn choose_troops(
     leader_entity: Entity,
     leader_fi: &FormationIndex,
     leader_pos: &Vec2I,
+    escorts: &Escorts,
     world: &mut SubWorld,
     commands: &mut CommandBuffer,
 ) {
-    let indices = [
-        (FormationIndex(leader_fi.0 - 1, leader_fi.1 + 1), true),
-        (FormationIndex(leader_fi.0 + 1, leader_fi.1 + 1), true),
-        (FormationIndex(leader_fi.0, leader_fi.1 - 1), false),
-    ];
+    let captured_fighter = (FormationIndex(leader_fi.0, leader_fi.1 - 1), false);
+    let indices = escorts.iter().flatten()
+        .map(|fi| (*fi, true))
+        .chain(std::iter::once(captured_fighter))
+        .collect::<Vec<_>>();
     let mut troops = Troops { members: Default::default(), copy_angle_to_troops: true };
     let mut i = 0;
     for (enemy, zako, posture, zako_entity) in <(&mut Enemy, &mut Zako, &Posture, Entity)>::query().iter_mut(world) {
@@ -4,6 +4,7 @@ use legion::*;
 
 use galangua_common::app::consts::*;
 use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::item::PowerUps;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::util::math::{calc_velocity, clamp, ANGLE, ONE};
 use galangua_common::util::pad::{Pad, PadBit};
@@ -12,14 +13,16 @@ use crate::app::components::*;
 use crate::app::resources::{GameInfo, SoundQueue};
 
 const SPRITE_NAME: &str = "rustacean";
-const HOME_X: i32 = (WIDTH / 2 - 8) * ONE;
 
-pub fn new_player() -> Player {
+pub fn new_player(index: usize, home_x: i32) -> Player {
     Player {
+        index,
+        home_x,
         state: PlayerState::Normal,
         count: 0,
         shot_enable: true,
         dual: None,
+        power_ups: PowerUps::default(),
     }
 }
 
@@ -27,6 +30,11 @@ impl Player {
     pub fn is_captured(&self) -> bool {
         self.state == PlayerState::Captured
     }
+
+    // Lost the ship, by crash or capture.
+    pub fn is_lost(&self) -> bool {
+        matches!(self.state, PlayerState::Dead | PlayerState::Captured)
+    }
 }
 
 pub fn do_move_player(
@@ -37,6 +45,7 @@ pub fn do_move_player(
 ) {
     use PlayerState::*;
 
+    player.power_ups.update();
     match player.state {
         Normal => {
             let x = {
@@ -72,7 +81,8 @@ pub fn do_move_player(
             let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
             let pos = &mut posture.0;
             let speed = 2 * ONE;
-            pos.x += clamp(HOME_X - pos.x, -speed, speed);
+            let x = player.home_x - 8 * ONE;
+            pos.x += clamp(x - pos.x, -speed, speed);
         }
         EscapeCapturing => {
             const D: i32 = 1 * ONE;
@@ -127,6 +137,7 @@ pub fn move_capturing_player(player: &mut Player, posture: &mut Posture, target_
 
     if d.x == 0 && d.y == 0 {
         player.state = PlayerState::Captured;
+        player.power_ups.clear();
         *angle = 0;
     }
 }
@@ -157,6 +168,7 @@ pub fn crash_player(
     } else {
         player.state = PlayerState::Dead;
         player.count = 0;
+        player.power_ups.clear();
         commands.remove_component::<CollRect>(entity);
         commands.remove_component::<SpriteDrawable>(entity);
         true
@@ -178,7 +190,7 @@ pub fn escape_player_from_tractor_beam(player: &mut Player) {
 
 pub fn restart_player(player: &mut Player, entity: Entity, posture: &mut Posture, commands: &mut CommandBuffer) {
     player.state = PlayerState::Normal;
-    posture.0 = Vec2I::new(CENTER_X, PLAYER_Y);
+    posture.0 = Vec2I::new(player.home_x, PLAYER_Y);
 
     commands.add_component(entity, player_sprite());
     commands.add_component(entity, player_coll_rect());
@@ -196,13 +208,19 @@ pub fn player_coll_rect() -> CollRect {
     CollRect { offset: Vec2I::new(-4, -4), size: Vec2I::new(8, 8) }
 }
 
-pub fn enum_player_target_pos(world: &SubWorld) -> Vec<Vec2I> {
+// Player index and position, includes dual fighters.
+pub fn enum_player_target_pos(world: &SubWorld) -> Vec<(usize, Vec2I)> {
+    // Lost player is targeted only when no one is alive.
+    let living = <&Player>::query().iter(world).any(|player| !player.is_lost());
     let mut target_pos = Vec::new();
     for (player, posture) in <(&Player, &Posture)>::query().iter(world) {
-        target_pos.push(posture.0);
+        if living && player.is_lost() {
+            continue;
+        }
+        target_pos.push((player.index, posture.0));
         if let Some(dual) = player.dual {
             if let Ok(dual_posture) = <&Posture>::query().get(world, dual) {
-                target_pos.push(dual_posture.0);
+                target_pos.push((player.index, dual_posture.0));
             }
         }
     }
@@ -321,10 +339,10 @@ pub fn do_recapture_fighter(
             }
         }
         SlideHorz => {
-            let player_living = <&Player>::query().get(world, me.player_entity).unwrap().state != PlayerState::Dead;
+            let player = <&Player>::query().get(world, me.player_entity).unwrap();
+            let x = player.home_x + if player.state != PlayerState::Dead { 8 * ONE } else { 0 };
             let posture = <&mut Posture>::query().get_mut(world, entity).unwrap();
             let pos = &mut posture.0;
-            let x = CENTER_X + if player_living { 8 * ONE } else { 0 };
             pos.x += clamp(x - pos.x, -SPEED, SPEED);
             if pos.x == x {
                 me.state = SlideDown;
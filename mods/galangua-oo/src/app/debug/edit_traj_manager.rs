@@ -1,5 +1,6 @@
 use crate::app::game::manager::game_manager::GameManager;
 
+use galangua_common::app::game::attack_manager::AttackType;
 use galangua_common::app::game::traj_command::TrajCommand;
 use galangua_common::app::game::FormationIndex;
 use galangua_common::framework::types::Vec2I;
@@ -45,10 +46,13 @@ impl EditTrajManager {
             self.set_traj_attack(game_manager, self.no, self.flip_x);
         }
         if pressed_key == Some(VKey::Num2) {
-            self.start_attack(game_manager, false);
+            self.start_attack(game_manager, AttackType::Normal);
         }
         if pressed_key == Some(VKey::Num3) {
-            self.start_attack(game_manager, true);
+            self.start_attack(game_manager, AttackType::Capture);
+        }
+        if pressed_key == Some(VKey::Num4) {
+            self.start_attack(game_manager, AttackType::Transform);
         }
         if pressed_key == Some(VKey::Num9) && self.no > 0 {
             self.no -= 1;
@@ -56,7 +60,7 @@ impl EditTrajManager {
         if pressed_key == Some(VKey::Num0) {
             self.no += 1;
         }
-        if pressed_key == Some(VKey::F) {
+        if pressed_key == Some(VKey::M) {
             self.flip_x = !self.flip_x;
         }
         if pressed_key == Some(VKey::T) {
@@ -76,15 +80,15 @@ impl EditTrajManager {
         renderer.set_texture_color_mod("font", 128, 128, 128);
         renderer.draw_str("font", 0 * 8, 0 * 8, "EDIT MODE");
         renderer.draw_str("font", 0 * 8, 1 * 8, &format!("NO={}", self.no));
-        renderer.draw_str("font", 0 * 8, 2 * 8, &format!("F)LIP={}", self.flip_x.to_string().to_uppercase()));
+        renderer.draw_str("font", 0 * 8, 2 * 8, &format!("M)IRROR={}", self.flip_x.to_string().to_uppercase()));
         renderer.draw_str("font", 0 * 8, 3 * 8, &format!("T)OP={}", self.from_top.to_string().to_uppercase()));
     }
 
-    fn start_attack(&mut self, game_manager: &mut GameManager, capture_attack: bool) {
+    fn start_attack(&mut self, game_manager: &mut GameManager, attack_type: AttackType) {
         let accessor = unsafe { peep(game_manager) };
         let stage_manager = game_manager.stage_manager_mut();
         if let Some(enemy) = stage_manager.get_enemy_at_mut(&self.fi) {
-            enemy.start_attack(capture_attack, accessor);
+            enemy.start_attack(attack_type, accessor);
         }
     }
 
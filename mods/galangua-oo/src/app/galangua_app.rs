@@ -1,9 +1,17 @@
 use super::game::manager::game_manager::GameManager;
 use super::game::manager::game_manager::Params as GameManagerParams;
 
+use galangua_common::app::assets::asset_manifest;
+use galangua_common::app::assist::{draw_assist_hint, AssistMenu, AssistOptions};
 use galangua_common::app::consts::*;
+use galangua_common::app::game::effect_table::builtin_animations;
+use galangua_common::app::game::score_rules::ScoreRules;
 use galangua_common::app::game::star_manager::StarManager;
-use galangua_common::app::score_holder::ScoreHolder;
+use galangua_common::app::score_holder::{draw_play_mode, load_high_score, save_high_score, PlayMode, ScoreHolder, MAX_PLAYER_COUNT};
+use galangua_common::framework::sprite_sheet::AnimationTable;
+use galangua_common::framework::text_layout::{draw_text, TextStyle};
+use galangua_common::framework::theme::Theme;
+use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::{AppTrait, RendererTrait, SystemTrait, VKey};
 use galangua_common::util::fps_calc::{FpsCalc, TimerTrait};
 use galangua_common::util::pad::{Pad, PadBit};
@@ -23,7 +31,7 @@ enum AppState {
 pub struct GalanguaApp<T: TimerTrait, S: SystemTrait> {
     system: S,
     state: AppState,
-    pad: Pad,
+    pads: [Pad; MAX_PLAYER_COUNT],
     pressed_key: Option<VKey>,
     fps_calc: FpsCalc<T>,
     game_manager: Option<GameManager>,
@@ -31,6 +39,11 @@ pub struct GalanguaApp<T: TimerTrait, S: SystemTrait> {
     frame_count: u32,
     score_holder: ScoreHolder,
     prev_high_score: u32,
+    play_mode: PlayMode,
+    theme: Option<Theme>,
+    animations: AnimationTable,
+    assist: AssistOptions,
+    assist_menu: Option<AssistMenu>,
 
     #[cfg(debug_assertions)]
     paused: bool,
@@ -40,13 +53,13 @@ pub struct GalanguaApp<T: TimerTrait, S: SystemTrait> {
 
 impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
     pub fn new(timer: T, system: S) -> Self {
-        let high_score = system.get_u32(KEY_HIGH_SCORE)
-                .unwrap_or(DEFAULT_HIGH_SCORE);
+        let high_score = load_high_score(&system);
+        let assist = AssistOptions::load(&system);
 
         Self {
             system,
             state: AppState::Title,
-            pad: Pad::default(),
+            pads: [Pad::new(0), Pad::new(1)],
             pressed_key: None,
             fps_calc: FpsCalc::new(timer),
             game_manager: None,
@@ -54,6 +67,11 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
             frame_count: 0,
             score_holder: ScoreHolder::new(high_score),
             prev_high_score: 0,
+            play_mode: PlayMode::Single,
+            theme: None,
+            animations: builtin_animations(),
+            assist,
+            assist_menu: None,
 
             #[cfg(debug_assertions)]
             paused: false,
@@ -62,10 +80,24 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
         }
     }
 
+    // Call before `init`.
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.star_manager.set_palette(&theme.star_colors);
+        self.theme = Some(theme);
+    }
+
+    // Clips of the sprite sheets, the built-in ones are used if not given.
+    pub fn set_animations(&mut self, animations: AnimationTable) {
+        self.animations = animations;
+    }
+
     fn update_main(&mut self) -> bool {
         if self.pressed_key == Some(VKey::Escape) {
             if self.state != AppState::Title {
                 self.back_to_title();
+            } else if self.assist_menu.is_some() {
+                self.assist_menu = None;
+                return true;
             } else {
                 return false;
             }
@@ -76,7 +108,7 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
             if self.pressed_key == Some(VKey::Return) {
                 self.paused = !self.paused;
             }
-            if self.paused && self.pressed_key != Some(VKey::S) {
+            if self.paused && self.pressed_key != Some(VKey::N) {
                 return true;
             }
         }
@@ -86,8 +118,20 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
         match self.state {
             AppState::Title => {
                 self.frame_count = self.frame_count.wrapping_add(1);
-                if self.pad.is_trigger(PadBit::A) {
+                if let Some(assist_menu) = &mut self.assist_menu {
+                    let prev = self.assist;
+                    if !assist_menu.update(&self.pads[0], &mut self.assist) {
+                        self.assist_menu = None;
+                    }
+                    if self.assist != prev {
+                        self.assist.save(&mut self.system);
+                    }
+                } else if self.pads[0].is_trigger(PadBit::A) {
                     self.start_game();
+                } else if self.pads[0].is_trigger(PadBit::D) {
+                    self.assist_menu = Some(AssistMenu::default());
+                } else if self.pads[0].is_trigger(PadBit::U) {
+                    self.play_mode = self.play_mode.next();
                 }
 
                 #[cfg(debug_assertions)]
@@ -95,6 +139,7 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
                     self.state = AppState::EditTraj;
 
                     let mut game_manager = GameManager::new();
+                    game_manager.set_animations(self.animations.clone());
                     game_manager.start_edit_mode();
                     self.game_manager = Some(game_manager);
                     self.edit_traj_manager = Some(EditTrajManager::new());
@@ -104,7 +149,7 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
                 self.frame_count = self.frame_count.wrapping_add(1);
                 let mut params = GameManagerParams {
                     star_manager: &mut self.star_manager,
-                    pad: &self.pad,
+                    pads: &self.pads,
                     score_holder: &mut self.score_holder,
                 };
                 let game_manager = self.game_manager.as_mut().unwrap();
@@ -123,7 +168,7 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
 
                 let mut params = GameManagerParams {
                     star_manager: &mut self.star_manager,
-                    pad: &self.pad,
+                    pads: &self.pads,
                     score_holder: &mut self.score_holder,
                 };
                 game_manager.update(&mut params, &mut self.system);
@@ -139,21 +184,28 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
         self.star_manager.draw(renderer);
         match self.state {
             AppState::Title => {
-                renderer.set_texture_color_mod("font", 255, 255, 255);
-                renderer.draw_str("font", 10 * 8, 8 * 8, "GALANGUA");
+                draw_text(renderer, "font", [&Vec2I::new(0, 8 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((255, 255, 255)), "GALANGUA");
 
-                if self.frame_count & 32 == 0 {
+                if let Some(assist_menu) = &self.assist_menu {
+                    assist_menu.draw(renderer, &self.assist);
+                } else if self.frame_count & 32 == 0 {
                     let msg = if self.system.is_touch_device() {
-                        "PRESS \"SHOT\" TO START"
+                        "PRESS {ffff00}\"SHOT\"{} TO START"
                     } else {
-                        "PRESS SPACE KEY TO START"
+                        "PRESS {ffff00}SPACE{} KEY TO START"
                     };
-                    renderer.draw_str("font", (28 - msg.len() as i32) / 2 * 8, 25 * 8, msg);
+                    draw_text(renderer, "font", [&Vec2I::new(0, 25 * 8), &Vec2I::new(WIDTH, 8)],
+                              &TextStyle::centered((255, 255, 255)), msg);
+                }
+                if self.assist_menu.is_none() && !self.system.is_touch_device() {
+                    draw_play_mode(renderer, self.play_mode);
+                    draw_assist_hint(renderer, &self.assist);
                 }
                 self.score_holder.draw(renderer, true);
 
-                renderer.set_texture_color_mod("font", 128, 128, 128);
-                renderer.draw_str("font", WIDTH - (VERSION.len() as i32) * 8, HEIGHT - 1 * 8, VERSION);
+                draw_text(renderer, "font", [&Vec2I::new(0, HEIGHT - 1 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::right((128, 128, 128)), VERSION);
             }
             AppState::Game => {
                 self.game_manager.as_mut().unwrap().draw(renderer);
@@ -168,8 +220,10 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
             }
         }
 
-        renderer.set_texture_color_mod("font", 128, 128, 128);
-        renderer.draw_str("font", 21 * 8, 1 * 8, "OO VER.");
+        if self.score_holder.player_count() < MAX_PLAYER_COUNT {
+            renderer.set_texture_color_mod("font", 128, 128, 128);
+            renderer.draw_str("font", 21 * 8, 1 * 8, "OO VER.");
+        }
 
         #[cfg(debug_assertions)]
         {
@@ -179,9 +233,20 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
     }
 
     fn start_game(&mut self) {
-        self.game_manager = Some(GameManager::new());
+        let mut game_manager = GameManager::new();
+        game_manager.set_invincible(self.assist.invincible);
+        game_manager.set_difficulty(self.assist.difficulty);
+        game_manager.set_arrange(self.assist.arrange);
+        game_manager.set_score_rules(ScoreRules::for_mode(&self.assist));
+        game_manager.set_animations(self.animations.clone());
+        game_manager.set_boss_interval(self.assist.boss_interval);
+        game_manager.set_play_mode(self.play_mode);
+        self.game_manager = Some(game_manager);
         self.prev_high_score = self.score_holder.high_score;
-        self.score_holder.reset_score();
+        self.score_holder.reset_score(self.play_mode.player_count(), self.assist.run_kind());
+        for pad in self.pads.iter_mut() {
+            pad.set_auto_fire(self.assist.auto_fire);
+        }
 
         self.state = AppState::Game;
         self.frame_count = 0;
@@ -189,6 +254,9 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
 
     fn back_to_title(&mut self) {
         self.game_manager = None;
+        for pad in self.pads.iter_mut() {
+            pad.set_auto_fire(false);
+        }
         self.star_manager.set_stop(false);
 
         if self.score_holder.high_score > self.prev_high_score {
@@ -200,33 +268,43 @@ impl<T: TimerTrait, S: SystemTrait> GalanguaApp<T, S> {
     }
 
     fn on_high_score_updated(&mut self) {
-        self.system.set_u32(KEY_HIGH_SCORE, self.score_holder.high_score);
+        save_high_score(&mut self.system, self.score_holder.high_score);
     }
 }
 
 impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaApp<T, S> {
     fn on_key(&mut self, vkey: VKey, down: bool) {
-        self.pad.on_key(vkey, down);
+        for pad in self.pads.iter_mut() {
+            pad.on_key(vkey, down);
+        }
         if down {
             self.pressed_key = Some(vkey);
         }
     }
 
-    fn on_joystick_axis(&mut self, axis_index: u8, dir: i8) {
-        self.pad.on_joystick_axis(axis_index, dir);
+    fn on_joystick_axis(&mut self, joystick_index: u8, axis_index: u8, dir: i8) {
+        if let Some(pad) = self.pads.get_mut(joystick_index as usize) {
+            pad.on_joystick_axis(axis_index, dir);
+        }
     }
 
-    fn on_joystick_button(&mut self, button_index: u8, down: bool) {
-        self.pad.on_joystick_button(button_index, down);
+    fn on_joystick_button(&mut self, joystick_index: u8, button_index: u8, down: bool) {
+        if let Some(pad) = self.pads.get_mut(joystick_index as usize) {
+            pad.on_joystick_button(button_index, down);
+        }
     }
 
     fn init(&mut self, renderer: &mut R) {
-        renderer.load_textures("assets", &["chr.png", "font.png"]);
-        renderer.load_sprite_sheet("assets/chr.json");
+        asset_manifest().load_into(renderer);
+        if let Some(theme) = &self.theme {
+            theme.assets.load_into(renderer);
+        }
     }
 
     fn update(&mut self) -> bool {
-        self.pad.update();
+        for pad in self.pads.iter_mut() {
+            pad.update();
+        }
         let result = self.update_main();
         self.pressed_key = None;
         result
@@ -240,4 +318,8 @@ impl<R: RendererTrait, T: TimerTrait, S: SystemTrait> AppTrait<R> for GalanguaAp
 
         self.fps_calc.update();
     }
+
+    fn game_speed(&self) -> u32 {
+        if self.state == AppState::Game { self.assist.speed } else { 100 }
+    }
 }
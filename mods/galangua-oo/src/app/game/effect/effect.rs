@@ -1,6 +1,8 @@
 use galangua_common::app::consts::*;
 use galangua_common::app::game::effect_table::*;
 use galangua_common::app::game::{EarnedPointType, EnemyType};
+use galangua_common::framework::anime_player::AnimePlayer;
+use galangua_common::framework::sprite_sheet::AnimationTable;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
 use galangua_common::util::math::{quantize_angle, round_vec};
@@ -10,39 +12,39 @@ pub enum Effect {
     RotSprite(RotSprite),
 }
 
+// Effects are not created when the animation is missing.
 impl Effect {
-    pub fn create_earned_point(point_type: EarnedPointType, pos: &Vec2I) -> Self {
-        Effect::SequentialSpriteAnime(
+    pub fn create_earned_point(point_type: EarnedPointType, pos: &Vec2I, animations: &AnimationTable) -> Option<Self> {
+        let clip = animations.get(EARNED_POINT_ANIME_NAMES[point_type as usize])?;
+        Some(Effect::SequentialSpriteAnime(
             SequentialSpriteAnime::new(
                 &round_vec(pos) + &Vec2I::new(-8, -4),
-                &EARNED_POINT_SPRITE_TABLE[point_type as usize],
-                EARNED_POINT_FRAME, 0))
+                AnimePlayer::new(clip), 0)))
     }
 
-    pub fn create_flash_enemy(pos: &Vec2I, angle: i32, enemy_type: EnemyType) -> Self {
-        let sprite_name = FLASH_ENEMY_SPRITE_NAMES[enemy_type as usize];
-        Effect::RotSprite(
+    pub fn create_flash_enemy(pos: &Vec2I, angle: i32, enemy_type: EnemyType, animations: &AnimationTable) -> Option<Self> {
+        let clip = animations.get(FLASH_ENEMY_ANIME_NAMES[enemy_type as usize])?;
+        Some(Effect::RotSprite(
             RotSprite::new(
                 &round_vec(pos) + &Vec2I::new(-8, -8),
                 quantize_angle(angle, ANGLE_DIV),
-                sprite_name,
-                FLASH_ENEMY_FRAME))
+                AnimePlayer::new(clip))))
     }
 
-    pub fn create_enemy_explosion(pos: &Vec2I, delay: u32) -> Self {
-        Effect::SequentialSpriteAnime(
+    pub fn create_enemy_explosion(pos: &Vec2I, delay: u32, animations: &AnimationTable) -> Option<Self> {
+        let clip = animations.get(ENEMY_EXPLOSION_ANIME_NAME)?;
+        Some(Effect::SequentialSpriteAnime(
             SequentialSpriteAnime::new(
                 &round_vec(pos) + &Vec2I::new(-16, -16),
-                &ENEMY_EXPLOSION_SPRITE_TABLE,
-                ENEMY_EXPLOSION_FRAME, delay))
+                AnimePlayer::new(clip), delay)))
     }
 
-    pub fn create_player_explosion(pos: &Vec2I) -> Self {
-        Effect::SequentialSpriteAnime(
+    pub fn create_player_explosion(pos: &Vec2I, animations: &AnimationTable) -> Option<Self> {
+        let clip = animations.get(PLAYER_EXPLOSION_ANIME_NAME)?;
+        Some(Effect::SequentialSpriteAnime(
             SequentialSpriteAnime::new(
                 &round_vec(pos) + &Vec2I::new(-16, -16),
-                &PLAYER_EXPLOSION_SPRITE_TABLE,
-                PLAYER_EXPLOSION_FRAME, 0))
+                AnimePlayer::new(clip), 0)))
     }
 
     pub fn update(&mut self) -> bool {
@@ -64,22 +66,16 @@ impl Effect {
 
 pub struct SequentialSpriteAnime {
     pos: Vec2I,
-    sprites: &'static [&'static str],
+    player: AnimePlayer,
     delay: u32,
-    frame_wait: u32,
-    frame: u32,
-    count: u32,
 }
 
 impl SequentialSpriteAnime {
-    pub fn new(pos: Vec2I, sprites: &'static [&str], frame_wait: u32, delay: u32) -> Self {
+    pub fn new(pos: Vec2I, player: AnimePlayer, delay: u32) -> Self {
         Self {
             pos,
-            sprites,
-            frame_wait,
+            player,
             delay,
-            frame: 0,
-            count: 0,
         }
     }
 
@@ -89,13 +85,7 @@ impl SequentialSpriteAnime {
             return true;
         }
 
-        self.count += 1;
-        if self.count >= self.frame_wait {
-            self.count = 0;
-            self.frame += 1;
-        }
-
-        self.frame < self.sprites.len() as u32
+        self.player.update()
     }
 
     pub fn draw(&self, renderer: &mut impl RendererTrait) {
@@ -103,8 +93,7 @@ impl SequentialSpriteAnime {
             return;
         }
 
-        let sprite = self.sprites[self.frame as usize];
-        renderer.draw_sprite(sprite, &self.pos);
+        renderer.draw_sprite(self.player.sprite_name(), &self.pos);
     }
 }
 
@@ -113,28 +102,23 @@ impl SequentialSpriteAnime {
 pub struct RotSprite {
     pos: Vec2I,
     angle: u8,
-    sprite_name: &'static str,
-    duration: u32,
-    count: u32,
+    player: AnimePlayer,
 }
 
 impl RotSprite {
-    pub fn new(pos: Vec2I, angle: u8, sprite_name: &'static str, duration: u32) -> Self {
+    pub fn new(pos: Vec2I, angle: u8, player: AnimePlayer) -> Self {
         Self {
             pos,
             angle,
-            sprite_name,
-            duration,
-            count: 0,
+            player,
         }
     }
 
     pub fn update(&mut self) -> bool {
-        self.count += 1;
-        self.count < self.duration
+        self.player.update()
     }
 
     pub fn draw(&self, renderer: &mut impl RendererTrait) {
-        renderer.draw_sprite_rot(self.sprite_name, &self.pos, self.angle, None);
+        renderer.draw_sprite_rot(self.player.sprite_name(), &self.pos, self.angle, None);
     }
 }
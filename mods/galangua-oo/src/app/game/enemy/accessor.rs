@@ -3,18 +3,23 @@
 use super::Enemy;
 
 use crate::app::game::effect::Effect;
-use crate::app::game::manager::CaptureEventType;
+use crate::app::game::manager::{CaptureEventType, TransformEventType};
 
+use galangua_common::app::game::attack_strategy::Escorts;
+use galangua_common::app::game::score_rules::ScoreRules;
+use galangua_common::app::game::shot_pattern::ShotPattern;
 use galangua_common::app::game::{CaptureState, FormationIndex};
+use galangua_common::framework::sprite_sheet::AnimationTable;
 use galangua_common::framework::types::Vec2I;
 
 pub trait Accessor {
     fn add_score(&mut self, add: u32);
-    fn spawn_ene_shot(&mut self, pos: &Vec2I);
+    fn spawn_ene_shot(&mut self, pos: &Vec2I, pattern: ShotPattern);
     fn spawn_effect(&mut self, effect: Effect);
+    fn drop_item(&mut self, pos: &Vec2I);
     fn get_player_pos(&self) -> &Vec2I;
-    fn get_dual_player_pos(&self) -> Option<Vec2I>;
-    fn can_player_capture(&self) -> bool;
+    fn get_player_target_pos(&self) -> Vec<(usize, Vec2I)>;  // Player index and position, includes dual fighters.
+    fn capturable_players(&self) -> Vec<(usize, Vec2I)>;
     fn is_player_capture_completed(&self) -> bool;
     fn capture_state(&self) -> CaptureState;
     fn captured_fighter_index(&self) -> Option<FormationIndex>;
@@ -24,8 +29,13 @@ pub trait Accessor {
     fn get_formation_pos(&self, formation_index: &FormationIndex) -> Vec2I;
     fn pause_enemy_shot(&mut self, wait: u32);
     fn is_rush(&self) -> bool;
-    fn get_stage_no(&self) -> u16;
+    fn choose_escorts(&self, leader: &FormationIndex) -> Escorts;
+    fn get_level(&self) -> u16;
+    fn is_shot_pattern_enabled(&self) -> bool;
+    fn score_rules(&self) -> &ScoreRules;
+    fn animations(&self) -> &AnimationTable;
     fn play_se(&mut self, channel: u32, asset_path: &'static str);
 
     fn capture_event(&mut self, event: CaptureEventType);
+    fn transform_event(&mut self, event: TransformEventType);
 }
@@ -0,0 +1,148 @@
+use super::enemy::Enemy;
+use super::enemy_base::{EnemyBase, EnemyInfo, CoordinateTrait, FormationTrait};
+use super::{Accessor, DamageResult};
+
+use crate::app::game::effect::Effect;
+
+use galangua_common::app::consts::*;
+use galangua_common::app::game::attack_manager::AttackType;
+use galangua_common::app::game::boss::*;
+use galangua_common::app::game::traj::Traj;
+use galangua_common::app::game::traj_command_table::BOSS_APPEARANCE_TABLE;
+use galangua_common::app::game::{EnemyType, FormationIndex};
+use galangua_common::app::util::collision::{CollBox, Collidable};
+use galangua_common::framework::types::{Vec2I, ZERO_VEC};
+use galangua_common::framework::RendererTrait;
+use galangua_common::util::math::round_vec;
+
+#[cfg(debug_assertions)]
+use galangua_common::app::game::traj_command::TrajCommand;
+
+#[derive(Clone, Copy, PartialEq)]
+enum BossState {
+    Appearance,
+    Attack,
+    Defeat,
+}
+
+pub struct Boss {
+    info: EnemyInfo,
+    base: EnemyBase,
+    state: BossState,
+    life: BossLife,
+    defeat: BossDefeat,
+}
+
+impl Boss {
+    pub fn new(level: u16) -> Self {
+        let mut base = EnemyBase::new();
+        base.traj = Some(Traj::new(&BOSS_APPEARANCE_TABLE, &ZERO_VEC, false, BOSS_FORMATION_INDEX));
+        Self {
+            info: EnemyInfo::new(BOSS_HOME_POS, 0, 0, &BOSS_FORMATION_INDEX),
+            base,
+            state: BossState::Appearance,
+            life: BossLife::new(level),
+            defeat: BossDefeat::default(),
+        }
+    }
+
+    fn update_defeat(&mut self, accessor: &mut dyn Accessor) -> bool {
+        match self.defeat.update(&self.info.pos) {
+            Some(BossDefeatEvent::ExplodePart(pos)) => {
+                if let Some(effect) = Effect::create_enemy_explosion(&pos, 0, accessor.animations()) {
+                    accessor.spawn_effect(effect);
+                }
+                accessor.play_se(CH_BOMB, SE_BOMB_ZAKO);
+            }
+            Some(BossDefeatEvent::Bonus) => {
+                accessor.play_se(CH_JINGLE, SE_RECAPTURE);
+            }
+            None => {}
+        }
+        !self.defeat.is_done()
+    }
+}
+
+impl CoordinateTrait for Boss {
+    fn pos(&self) -> &Vec2I { self.info.pos() }
+    fn set_pos(&mut self, pos: &Vec2I) { self.info.set_pos(pos); }
+    fn angle(&self) -> i32 { self.info.angle() }
+}
+
+impl FormationTrait for Boss {
+    fn formation_index(&self) -> &FormationIndex { self.info.formation_index() }
+}
+
+impl Collidable for Boss {
+    fn get_collbox(&self) -> Option<CollBox> {
+        if self.state != BossState::Defeat {
+            Some(CollBox {
+                top_left: &round_vec(&self.info.pos) + &BOSS_COLL_OFFSET,
+                size: BOSS_COLL_SIZE,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Enemy for Boss {
+    fn update(&mut self, accessor: &mut dyn Accessor) -> bool {
+        if self.state == BossState::Defeat {
+            return self.update_defeat(accessor);
+        }
+
+        if !self.base.update_trajectory(&mut self.info, accessor) {
+            // Repeats the table of the current phase from the home position.
+            let mut traj = Traj::new(self.life.phase_table(), &ZERO_VEC, false, BOSS_FORMATION_INDEX);
+            traj.set_pos(&BOSS_HOME_POS);
+            self.base.traj = Some(traj);
+            self.state = BossState::Attack;
+        }
+        true
+    }
+
+    fn draw(&self, renderer: &mut dyn RendererTrait, pat: usize) {
+        let broken_parts = if self.state == BossState::Defeat { self.defeat.broken_parts() } else { 0 };
+        draw_boss(renderer, &self.info.pos, pat, &self.life, broken_parts);
+        self.life.draw_bar(renderer);
+        self.defeat.draw_bonus(renderer, &self.info.pos);
+    }
+
+    fn is_formation(&self) -> bool { false }
+
+    fn set_damage(&mut self, power: u32, accessor: &mut dyn Accessor) -> DamageResult {
+        if !self.life.set_damage(power) {
+            accessor.play_se(CH_BOMB, SE_DAMAGE);
+            return DamageResult { point: 0, keep_alive_as_ghost: false };
+        }
+
+        let rules = accessor.score_rules();
+        let (point, bonus) = (rules.enemy_point(EnemyType::Boss, false), rules.boss_bonus);
+        self.info.explode(accessor, EnemyType::Owl);
+        accessor.play_se(CH_JINGLE, SE_BOMB_CAPTURED);
+        accessor.add_score(bonus);
+        self.state = BossState::Defeat;
+        self.defeat = BossDefeat::new(bonus);
+        DamageResult { point, keep_alive_as_ghost: true }
+    }
+
+    fn update_troop(&mut self, _add: &Vec2I, _angle_opt: Option<i32>) {
+        panic!("Illegal");
+    }
+
+    fn start_attack(&mut self, _attack_type: AttackType, _accessor: &mut dyn Accessor) {
+        panic!("Illegal");
+    }
+
+    fn set_to_troop(&mut self) {
+        panic!("Illegal");
+    }
+
+    fn set_to_formation(&mut self) {
+        panic!("Illegal");
+    }
+
+    #[cfg(debug_assertions)]
+    fn set_table_attack(&mut self, _traj_command_vec: Vec<TrajCommand>, _flip_x: bool) {}
+}
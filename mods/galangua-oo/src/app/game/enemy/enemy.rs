@@ -1,9 +1,10 @@
 use super::enemy_base::{CoordinateTrait, FormationTrait};
 use super::owl::{Owl, OwlState};
-use super::zako::{Zako, ZakoState};
+use super::zako::{Zako, ZakoAttackType, ZakoState};
 use super::{Accessor, DamageResult};
 
 use galangua_common::app::consts::*;
+use galangua_common::app::game::attack_manager::AttackType;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::{EnemyType, FormationIndex};
 use galangua_common::app::util::collision::Collidable;
@@ -30,7 +31,7 @@ pub trait Enemy: Collidable + CoordinateTrait + FormationTrait {
 
     fn update_troop(&mut self, add: &Vec2I, angle_opt: Option<i32>);
 
-    fn start_attack(&mut self, capture_attack: bool, accessor: &mut dyn Accessor);
+    fn start_attack(&mut self, attack_type: AttackType, accessor: &mut dyn Accessor);
     fn set_to_troop(&mut self);
     fn set_to_formation(&mut self);
 
@@ -69,3 +70,12 @@ pub fn create_appearance_enemy(
         }
     }
 }
+
+pub fn create_transformed_enemy(
+    enemy_type: EnemyType, pos: &Vec2I, fi: &FormationIndex, traj: Traj,
+) -> Box<dyn Enemy> {
+    let mut zako = Zako::new(enemy_type, pos, 0, 0, fi);
+    zako.base.traj = Some(traj);
+    zako.set_state(ZakoState::Attack(ZakoAttackType::Traj));
+    Box::new(zako)
+}
@@ -11,7 +11,9 @@ use super::Accessor;
 use crate::app::game::effect::Effect;
 
 use galangua_common::app::consts::*;
+use galangua_common::app::game::difficulty::{attack_shot_count, attack_shot_interval};
 use galangua_common::app::game::effect_table::FLASH_ENEMY_FRAME;
+use galangua_common::app::game::shot_pattern::ShotPattern;
 use galangua_common::app::game::traj::Accessor as TrajAccessor;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::traj_command::TrajCommand;
@@ -29,7 +31,8 @@ impl<'a> TrajAccessor for TrajAccessorImpl<'a> {
     fn get_formation_pos(&self, formation_index: &FormationIndex) -> Vec2I {
         self.accessor.get_formation_pos(formation_index)
     }
-    fn get_stage_no(&self) -> u16 { self.accessor.get_stage_no() }
+    fn get_level(&self) -> u16 { self.accessor.get_level() }
+    fn is_shot_pattern_enabled(&self) -> bool { self.accessor.is_shot_pattern_enabled() }
 }
 
 #[delegatable_trait]
@@ -76,8 +79,12 @@ impl EnemyInfo {
     }
 
     pub(super) fn explode(&mut self, accessor: &mut dyn Accessor, enemy_type: EnemyType) {
-        accessor.spawn_effect(Effect::create_flash_enemy(&self.pos, self.angle, enemy_type));
-        accessor.spawn_effect(Effect::create_enemy_explosion(&self.pos, FLASH_ENEMY_FRAME));
+        if let Some(effect) = Effect::create_flash_enemy(&self.pos, self.angle, enemy_type, accessor.animations()) {
+            accessor.spawn_effect(effect);
+        }
+        if let Some(effect) = Effect::create_enemy_explosion(&self.pos, FLASH_ENEMY_FRAME, accessor.animations()) {
+            accessor.spawn_effect(effect);
+        }
     }
 }
 
@@ -124,15 +131,14 @@ impl EnemyBase {
     pub fn update_attack(&mut self, info: &EnemyInfo, shot_enable: bool, accessor: &mut dyn Accessor) -> bool {
         self.attack_frame_count += 1;
 
-        let stage_no = accessor.get_stage_no();
-        let shot_count = std::cmp::min(2 + stage_no / 8, 5) as u32;
-        let shot_interval = 20 - shot_count * 2;
+        let shot_count = attack_shot_count(accessor.get_level());
+        let shot_interval = attack_shot_interval(accessor.get_level());
 
         if self.attack_frame_count <= shot_interval * shot_count &&
             self.attack_frame_count % shot_interval == 0
         {
             if shot_enable {
-                accessor.spawn_ene_shot(&info.pos);
+                self.fire_shot(&info.pos, accessor);
             }
             true
         } else {
@@ -140,6 +146,16 @@ impl EnemyBase {
         }
     }
 
+    fn fire_shot(&mut self, pos: &Vec2I, accessor: &mut dyn Accessor) {
+        let pattern = self.traj.as_ref().map_or(ShotPattern::Aimed, |traj| traj.shot_pattern());
+        let delay = pattern.delay();
+        if delay == 0 {
+            accessor.spawn_ene_shot(pos, pattern);
+        } else if self.shot_wait.is_none() {
+            self.shot_wait = Some(delay);
+        }
+    }
+
     pub(super) fn move_to_formation(&mut self, info: &mut EnemyInfo, accessor: &dyn Accessor) -> bool {
         let target = accessor.get_formation_pos(&info.formation_index);
         let diff = &target - &info.pos;
@@ -170,15 +186,10 @@ impl EnemyBase {
 
     pub(super) fn set_assault(&mut self, info: &mut EnemyInfo, accessor: &dyn Accessor) {
         let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
-        let target_pos = [
-            Some(*accessor.get_player_pos()),
-            accessor.get_dual_player_pos(),
-        ];
-        let count = target_pos.iter().flatten().count();
-        let target: &Vec2I = target_pos.iter()
-            .flatten().nth(rng.random_range(0..count)).unwrap();
-
-        self.target_pos = *target;
+        let target_pos = accessor.get_player_target_pos();
+        let (_, target) = target_pos[rng.random_range(0..target_pos.len())];
+
+        self.target_pos = target;
         info.vangle = 0;
     }
 
@@ -201,7 +212,7 @@ impl EnemyBase {
                 if wait > 0 {
                     self.shot_wait = Some(wait - 1);
                 } else {
-                    accessor.spawn_ene_shot(&info.pos);
+                    accessor.spawn_ene_shot(&info.pos, traj.shot_pattern());
                     self.shot_wait = None;
                 }
             }
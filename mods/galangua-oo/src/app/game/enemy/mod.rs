@@ -2,6 +2,8 @@ mod accessor;
 pub mod ene_shot;
 pub mod enemy;
 mod enemy_base;
+// Declared after `enemy_base` to use its delegate macros.
+pub mod boss;
 mod owl;
 mod tractor_beam;
 mod zako;
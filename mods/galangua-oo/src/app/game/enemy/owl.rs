@@ -8,7 +8,11 @@ use super::{Accessor, DamageResult};
 use crate::app::game::manager::CaptureEventType;
 
 use galangua_common::app::consts::*;
+use galangua_common::app::game::attack_manager::AttackType;
 use galangua_common::app::game::formation_table::{X_COUNT, Y_COUNT};
+use galangua_common::app::game::score_rules::ScoreRules;
+use galangua_common::app::game::shot_pattern::ShotPattern;
+use galangua_common::app::game::tractor_beam_table::tractor_beam_sprite_names;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::traj_command_table::*;
 use galangua_common::app::game::{EnemyType, FormationIndex};
@@ -94,16 +98,12 @@ impl Owl {
         self.state = state;
     }
 
-    fn calc_point(&self) -> u32 {
-        if self.is_formation() {
-            150
-        } else {
-            let cap_fi = FormationIndex(self.info.formation_index.0, self.info.formation_index.1 - 1);
-            let count = self.troops.iter().flatten()
-                .filter(|index| **index != cap_fi)
-                .count();
-            (1 << count) * 400
-        }
+    fn calc_point(&self, rules: &ScoreRules) -> u32 {
+        let cap_fi = FormationIndex(self.info.formation_index.0, self.info.formation_index.1 - 1);
+        let count = self.troops.iter().flatten()
+            .filter(|index| **index != cap_fi)
+            .count();
+        rules.owl_point(self.is_formation(), count)
     }
 
     fn live_troops_exist(&self, accessor: &dyn Accessor) -> bool {
@@ -123,12 +123,9 @@ impl Owl {
 
     fn choose_troops(&mut self, accessor: &mut dyn Accessor) {
         let base = &self.info.formation_index;
-        let indices = [
-            FormationIndex(base.0 - 1, base.1 + 1),
-            FormationIndex(base.0 + 1, base.1 + 1),
-            FormationIndex(base.0, base.1 - 1),
-        ];
-        for index in indices.iter() {
+        let escorts = accessor.choose_escorts(base);
+        let captured_fighter = FormationIndex(base.0, base.1 - 1);
+        for index in escorts.iter().flatten().chain(std::iter::once(&captured_fighter)) {
             if let Some(enemy) = accessor.get_enemy_at_mut(index) {
                 if enemy.is_formation() {
                     self.add_troop(*index);
@@ -243,7 +240,8 @@ impl Owl {
             self.info.angle = ANGLE / 2 * ONE;
             self.info.vangle = 0;
 
-            self.tractor_beam = Some(TractorBeam::new(&(&self.info.pos + &Vec2I::new(0, 8 * ONE))));
+            let sprite_names = tractor_beam_sprite_names(accessor.animations());
+            self.tractor_beam = Some(TractorBeam::new(&(&self.info.pos + &Vec2I::new(0, 8 * ONE)), sprite_names));
             accessor.play_se(CH_JINGLE, SE_TRACTOR_BEAM1);
 
             self.set_state(OwlState::CaptureAttack(OwlAttackPhase::CaptureBeam));
@@ -256,10 +254,10 @@ impl Owl {
             self.tractor_beam = None;
             self.info.speed = 5 * ONE / 2;
             self.set_state(OwlState::CaptureAttack(OwlAttackPhase::NoCaptureGoOut));
-        } else if accessor.can_player_capture() &&
-                    tractor_beam.can_capture(accessor.get_player_pos())
+        } else if let Some((player, _)) = accessor.capturable_players().iter()
+                    .find(|(_, pos)| tractor_beam.can_capture(pos))
         {
-            accessor.capture_event(CaptureEventType::CapturePlayer(&self.info.pos + &Vec2I::new(0, 16 * ONE)));
+            accessor.capture_event(CaptureEventType::CapturePlayer(*player, &self.info.pos + &Vec2I::new(0, 16 * ONE)));
             accessor.play_se(CH_JINGLE, SE_TRACTOR_BEAM2);
             tractor_beam.start_capture();
             self.capturing_state = CapturingState::BeamTracting;
@@ -366,7 +364,7 @@ impl Owl {
                 let pos_opt = accessor.get_enemy_at(troop_fi)
                     .map(|troop| *troop.pos());
                 if let Some(pos) = pos_opt {
-                    accessor.spawn_ene_shot(&pos);
+                    accessor.spawn_ene_shot(&pos, ShotPattern::Aimed);
                 }
             }
         }
@@ -379,7 +377,7 @@ impl Owl {
             DamageResult { point: 0, keep_alive_as_ghost: false }
         } else {
             self.life = 0;
-            let point = self.calc_point();
+            let point = self.calc_point(accessor.score_rules());
 
             // Release capturing.
             match self.capturing_state {
@@ -392,6 +390,11 @@ impl Owl {
                         let angle = cap_fighter.angle();
                         accessor.capture_event(CaptureEventType::RecapturePlayer(cap_fi, angle));
                         *slot = None;
+
+                        let bonus = accessor.score_rules().rescue_bonus;
+                        if bonus > 0 {
+                            accessor.add_score(bonus);
+                        }
                     }
                 }
                 CapturingState::BeamTracting => {
@@ -407,6 +410,7 @@ impl Owl {
 
             self.info.explode(accessor, EnemyType::Owl);
             accessor.play_se(CH_BOMB, SE_BOMB_ZAKO);
+            accessor.drop_item(&self.info.pos);
 
             let keep_alive_as_ghost = self.live_troops_exist(accessor);  // To keep moving troops.
             DamageResult { point, keep_alive_as_ghost }
@@ -476,7 +480,7 @@ impl Enemy for Owl {
         panic!("Illegal");
     }
 
-    fn start_attack(&mut self, capture_attack: bool, accessor: &mut dyn Accessor) {
+    fn start_attack(&mut self, attack_type: AttackType, accessor: &mut dyn Accessor) {
         self.base.count = 0;
         self.base.attack_frame_count = 0;
         self.copy_angle_to_troops = true;
@@ -485,7 +489,7 @@ impl Enemy for Owl {
             *slot = None;
         }
         let flip_x = self.info.formation_index.0 >= (X_COUNT as u8) / 2;
-        if !capture_attack {
+        if attack_type != AttackType::Capture {
             self.capturing_state = CapturingState::None;
             self.copy_angle_to_troops = true;
             self.choose_troops(accessor);
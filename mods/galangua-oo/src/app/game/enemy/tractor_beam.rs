@@ -19,23 +19,25 @@ pub struct TractorBeam {
     count: u32,
     color_count: u32,
     size_count: i32,
+    sprite_names: Vec<String>,
 }
 
 impl TractorBeam {
-    pub fn new(pos: &Vec2I) -> Self {
+    pub fn new(pos: &Vec2I, sprite_names: Vec<String>) -> Self {
         Self {
             pos: *pos,
             state: State::Opening,
             count: 0,
             color_count: 0,
             size_count: 0,
+            sprite_names,
         }
     }
 
     pub fn update(&mut self) {
         self.color_count += 1;
 
-        let n = TRACTOR_BEAM_SPRITE_NAMES.len() as i32;
+        let n = TRACTOR_BEAM_Y_OFFSET_TABLE.len() as i32;
         match self.state {
             State::Opening => {
                 self.size_count += ONE / 3;
@@ -71,12 +73,14 @@ impl TractorBeam {
         if n > 0 {
             let hue = self.color_count * 64;
             let pos = &pos + &Vec2I::new(-24, 0);
-            for i in 0..n {
-                let sprite_name = TRACTOR_BEAM_SPRITE_NAMES[i];
+            let sprites = self.sprite_names.iter().zip(TRACTOR_BEAM_Y_OFFSET_TABLE.iter());
+            for (i, (sprite_name, &y)) in sprites.take(n).enumerate() {
                 set_hsv_color(renderer, sprite_name, hue + i as u32 * 160, 255, 255);
-                renderer.draw_sprite(sprite_name, &(&pos + &Vec2I::new(0, TRACTOR_BEAM_Y_OFFSET_TABLE[i])));
+                renderer.draw_sprite(sprite_name, &(&pos + &Vec2I::new(0, y)));
+            }
+            if let Some(sprite_name) = self.sprite_names.first() {
+                renderer.set_sprite_texture_color_mod(sprite_name, 255, 255, 255);
             }
-            renderer.set_sprite_texture_color_mod(TRACTOR_BEAM_SPRITE_NAMES[0], 255, 255, 255);
         }
     }
 
@@ -4,14 +4,16 @@ use super::enemy::Enemy;
 use super::enemy_base::{EnemyBase, EnemyInfo, CoordinateTrait, FormationTrait};
 use super::{Accessor, DamageResult};
 
-use crate::app::game::manager::CaptureEventType;
+use crate::app::game::manager::{CaptureEventType, TransformEventType};
 
 use galangua_common::ambassador_impl_Collidable;
 use galangua_common::app::consts::*;
+use galangua_common::app::game::attack_manager::AttackType;
 use galangua_common::app::game::formation_table::Y_COUNT;
 use galangua_common::app::game::traj::Traj;
 use galangua_common::app::game::traj_command::TrajCommand;
 use galangua_common::app::game::traj_command_table::*;
+use galangua_common::app::game::transform::is_transformed;
 use galangua_common::app::game::{EnemyType, FormationIndex};
 use galangua_common::app::util::collision::{CollBox, Collidable};
 use galangua_common::framework::types::{Vec2I, ZERO_VEC};
@@ -19,12 +21,15 @@ use galangua_common::framework::RendererTrait;
 
 const BEE_SPRITE_NAMES: [&str; 2] = ["gopher1", "gopher2"];
 const BUTTERFLY_SPRITE_NAMES: [&str; 2] = ["dman1", "dman2"];
+const SCORPION_SPRITE_NAMES: [&str; 2] = ["scorpion1", "scorpion2"];
+const STINGRAY_SPRITE_NAMES: [&str; 2] = ["stingray1", "stingray2"];
+const FLAGSHIP_SPRITE_NAMES: [&str; 2] = ["flagship1", "flagship2"];
 
 struct Vtable {
     rush_traj_table: &'static [TrajCommand],
 }
 
-const VTABLE: [Vtable; 4] = [
+const VTABLE: [Vtable; 7] = [
     // Bee
     Vtable {
         rush_traj_table: &BEE_RUSH_ATTACK_TABLE,
@@ -41,12 +46,26 @@ const VTABLE: [Vtable; 4] = [
     Vtable {
         rush_traj_table: &OWL_RUSH_ATTACK_TABLE,
     },
+    // Transformed enemies escape without rush, these are not used.
+    // Scorpion
+    Vtable {
+        rush_traj_table: &SCORPION_ATTACK_TABLE,
+    },
+    // Stingray
+    Vtable {
+        rush_traj_table: &STINGRAY_ATTACK_TABLE,
+    },
+    // Flagship
+    Vtable {
+        rush_traj_table: &FLAGSHIP_ATTACK_TABLE,
+    },
 ];
 
 #[derive(Clone, Copy, PartialEq)]
 pub(super) enum ZakoAttackType {
     BeeAttack,
     Traj,
+    Transform,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -118,6 +137,7 @@ impl Zako {
                 match t {
                     ZakoAttackType::BeeAttack => self.update_bee_attack(accessor),
                     ZakoAttackType::Traj => self.update_attack_traj(accessor),
+                    ZakoAttackType::Transform => self.update_transform(accessor),
                 }
             }
         }
@@ -143,6 +163,9 @@ impl Zako {
         if !self.base.update_trajectory(&mut self.info, accessor) {
             if self.enemy_type == EnemyType::CapturedFighter {
                 self.base.disappeared = true;
+            } else if is_transformed(self.enemy_type) {
+                accessor.transform_event(TransformEventType::Escaped);
+                self.base.disappeared = true;
             } else if accessor.is_rush() {
                 // Rush mode: Continue attacking
                 let table = VTABLE[self.enemy_type as usize].rush_traj_table;
@@ -154,6 +177,14 @@ impl Zako {
         }
     }
 
+    fn update_transform(&mut self, accessor: &mut dyn Accessor) {
+        if !self.base.update_trajectory(&mut self.info, accessor) {
+            let flip_x = self.info.formation_index.0 >= 5;
+            accessor.transform_event(TransformEventType::Transform(self.info.pos, flip_x));
+            self.base.disappeared = true;
+        }
+    }
+
     // start_attack
 
     fn start_bee_attack(&mut self) {
@@ -167,6 +198,17 @@ impl Zako {
         self.set_state(ZakoState::Attack(ZakoAttackType::BeeAttack));
     }
 
+    fn start_bee_transform(&mut self) {
+        let flip_x = self.info.formation_index.0 >= 5;
+        let mut traj = Traj::new(&BEE_TRANSFORM_TABLE, &ZERO_VEC, flip_x, self.info.formation_index);
+        traj.set_pos(&self.info.pos);
+
+        self.base.count = 0;
+        self.base.attack_frame_count = 0;
+        self.base.traj = Some(traj);
+        self.set_state(ZakoState::Attack(ZakoAttackType::Transform));
+    }
+
     fn start_butterfly_attack(&mut self) {
         let flip_x = self.info.formation_index.0 >= 5;
         let mut traj = Traj::new(&BUTTERFLY_ATTACK_TABLE, &ZERO_VEC, flip_x,
@@ -192,30 +234,25 @@ impl Zako {
 
     // set_damage
 
-    fn bee_set_damage(&mut self) -> DamageResult {
-        let point = self.calc_point();
+    fn bee_set_damage(&mut self, accessor: &mut dyn Accessor) -> DamageResult {
+        let point = self.calc_point(accessor);
         DamageResult { point, keep_alive_as_ghost: false }
     }
 
     fn captured_fighter_set_damage(&mut self, accessor: &mut dyn Accessor) -> DamageResult {
         accessor.capture_event(CaptureEventType::CapturedFighterDestroyed);
-        let point = self.calc_point();
+        let point = self.calc_point(accessor);
         DamageResult { point, keep_alive_as_ghost: false }
     }
 
-    fn calc_point(&self) -> u32 {
-        match self.enemy_type {
-            EnemyType::Bee => {
-                if self.is_formation() { 50 } else { 100 }
-            }
-            EnemyType::Butterfly => {
-                if self.is_formation() { 80 } else { 160 }
-            }
-            EnemyType::CapturedFighter => {
-                if self.is_formation() { 500 } else { 1000 }
-            }
-            _ => { panic!("Illegal"); }
-        }
+    fn transformed_set_damage(&mut self, accessor: &mut dyn Accessor) -> DamageResult {
+        accessor.transform_event(TransformEventType::Destroyed(self.info.pos));
+        let point = self.calc_point(accessor);
+        DamageResult { point, keep_alive_as_ghost: false }
+    }
+
+    fn calc_point(&self, accessor: &dyn Accessor) -> u32 {
+        accessor.score_rules().enemy_point(self.enemy_type, self.is_formation())
     }
 }
 
@@ -231,6 +268,9 @@ impl Enemy for Zako {
             EnemyType::Bee => BEE_SPRITE_NAMES[pat],
             EnemyType::Butterfly => BUTTERFLY_SPRITE_NAMES[pat],
             EnemyType::CapturedFighter => "rustacean_captured",
+            EnemyType::Scorpion => SCORPION_SPRITE_NAMES[pat],
+            EnemyType::Stingray => STINGRAY_SPRITE_NAMES[pat],
+            EnemyType::Flagship => FLAGSHIP_SPRITE_NAMES[pat],
             _ => { panic!("Illegal"); }
         };
         self.draw_sprite(renderer, sprite, &Vec2I::new(8, 8));
@@ -246,8 +286,11 @@ impl Enemy for Zako {
             accessor.play_se(CH_BOMB, SE_BOMB_ZAKO);
         }
         match self.enemy_type {
-            EnemyType::Bee | EnemyType::Butterfly => self.bee_set_damage(),
+            EnemyType::Bee | EnemyType::Butterfly => self.bee_set_damage(accessor),
             EnemyType::CapturedFighter => self.captured_fighter_set_damage(accessor),
+            EnemyType::Scorpion | EnemyType::Stingray | EnemyType::Flagship => {
+                self.transformed_set_damage(accessor)
+            }
             _ => { panic!("Illegal"); }
         }
     }
@@ -259,8 +302,9 @@ impl Enemy for Zako {
         }
     }
 
-    fn start_attack(&mut self, _capture_attack: bool, accessor: &mut dyn Accessor) {
+    fn start_attack(&mut self, attack_type: AttackType, accessor: &mut dyn Accessor) {
         match self.enemy_type {
+            EnemyType::Bee if attack_type == AttackType::Transform => self.start_bee_transform(),
             EnemyType::Bee => self.start_bee_attack(),
             EnemyType::Butterfly => self.start_butterfly_attack(),
             EnemyType::CapturedFighter => self.start_captured_fighter_attack(),
@@ -0,0 +1,40 @@
+use galangua_common::app::game::item::{draw_item, fall_item, ItemType, ITEM_SIZE};
+use galangua_common::app::util::collision::{CollBox, Collidable};
+use galangua_common::framework::types::Vec2I;
+use galangua_common::framework::RendererTrait;
+use galangua_common::util::math::round_vec;
+
+pub struct Item {
+    pos: Vec2I,
+    item_type: ItemType,
+}
+
+impl Item {
+    pub fn new(pos: &Vec2I, item_type: ItemType) -> Self {
+        Self {
+            pos: *pos,
+            item_type,
+        }
+    }
+
+    pub fn update(&mut self) -> bool {
+        fall_item(&mut self.pos)
+    }
+
+    pub fn draw(&self, renderer: &mut impl RendererTrait) {
+        draw_item(renderer, self.item_type, &self.pos);
+    }
+
+    pub fn item_type(&self) -> ItemType {
+        self.item_type
+    }
+}
+
+impl Collidable for Item {
+    fn get_collbox(&self) -> Option<CollBox> {
+        Some(CollBox {
+            top_left: &round_vec(&self.pos) - &Vec2I::new(ITEM_SIZE / 2, ITEM_SIZE / 2),
+            size: Vec2I::new(ITEM_SIZE, ITEM_SIZE),
+        })
+    }
+}
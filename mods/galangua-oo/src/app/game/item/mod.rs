@@ -0,0 +1,3 @@
+mod item;
+
+pub use self::item::*;
@@ -33,8 +33,8 @@ impl Index<usize> for EventQueue {
 
 #[derive(Clone)]
 pub(super) enum EventType {
-    AddScore(u32),
-    DeadPlayer,
+    AddScore(usize, u32),  // player, add
+    DeadPlayer(usize),
     StarEvent(StarEventType),
     PlaySe(u32, &'static str),
 }
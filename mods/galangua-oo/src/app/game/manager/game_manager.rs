@@ -1,27 +1,39 @@
 use super::event_queue::{EventQueue, EventType, StarEventType};
 use super::stage::stage_manager::StageManager;
-use super::CaptureEventType;
+use super::{CaptureEventType, TransformEventType};
 
 use crate::app::game::effect::Effect;
 use crate::app::game::enemy::Accessor as AccessorForEnemy;
 use crate::app::game::enemy::Enemy;
+use crate::app::game::item::Item;
 use crate::app::game::player::Accessor as AccessorForPlayer;
 use crate::app::game::player::{MyShot, Player};
 
 use galangua_common::app::consts::*;
+use galangua_common::app::game::attack_strategy::Escorts;
+use galangua_common::app::game::boss::is_boss_stage;
+use galangua_common::app::game::difficulty::{ene_shot_speed, Difficulty, DifficultyProfile};
+use galangua_common::app::game::effect_table::{builtin_animations, to_earned_point_type};
+use galangua_common::app::game::item::{roll_item_drop, ItemType, EXTRA_MYSHOT_COUNT};
+use galangua_common::app::game::player_turn::{PlayerProgress, PlayerTurns};
+use galangua_common::app::game::score_rules::ScoreRules;
+use galangua_common::app::game::shot_pattern::ShotPattern;
 use galangua_common::app::game::stage_indicator::StageIndicator;
 use galangua_common::app::game::star_manager::StarManager;
 use galangua_common::app::game::{CaptureState, FormationIndex};
-use galangua_common::app::score_holder::ScoreHolder;
+use galangua_common::app::score_holder::{PlayMode, ScoreHolder};
 use galangua_common::app::util::collision::Collidable;
+use galangua_common::framework::sprite_sheet::AnimationTable;
+use galangua_common::framework::text_layout::{draw_text, TextStyle};
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::{RendererTrait, SystemTrait};
-use galangua_common::util::math::ONE;
 use galangua_common::util::pad::Pad;
 use galangua_common::util::unsafe_util::peep;
 
 const MYSHOT_COUNT: usize = 2;
+const MAX_MYSHOT_COUNT: usize = MYSHOT_COUNT + EXTRA_MYSHOT_COUNT;
 const MAX_EFFECT_COUNT: usize = 16;
+const MAX_ITEM_COUNT: usize = 4;
 
 #[derive(PartialEq)]
 enum GameState {
@@ -34,6 +46,7 @@ enum GameState {
     Captured,
     Recapturing,
     StageClear,
+    SwitchPlayer,
     GameOver,
     Finished,
 
@@ -43,23 +56,53 @@ enum GameState {
 
 pub struct Params<'a> {
     pub star_manager: &'a mut StarManager,
-    pub pad: &'a Pad,
+    pub pads: &'a [Pad],
     pub score_holder: &'a mut ScoreHolder,
 }
 
+struct Fighter {
+    player: Player,
+    myshots: [Option<MyShot>; MAX_MYSHOT_COUNT],
+    left_ship: u32,
+}
+
+impl Fighter {
+    fn new(index: usize, home_x: i32) -> Self {
+        Self {
+            player: Player::new(index, home_x),
+            myshots: Default::default(),
+            left_ship: DEFAULT_LEFT_SHIP,
+        }
+    }
+
+    // Dead, but still has ships to restart.
+    fn is_waiting_restart(&self) -> bool {
+        self.player.is_dead() && self.left_ship > 0
+    }
+}
+
 pub struct GameManager {
     state: GameState,
     count: u32,
     stage_indicator: StageIndicator,
-    player: Player,
-    myshots: [Option<MyShot>; MYSHOT_COUNT],
+    fighters: Vec<Fighter>,
     stage_manager: StageManager,
     effects: [Option<Effect>; MAX_EFFECT_COUNT],
+    items: [Option<Item>; MAX_ITEM_COUNT],
     event_queue: EventQueue,
     stage: u16,
-    left_ship: u32,
     capture_state: CaptureState,
     capture_enemy_fi: FormationIndex,
+    capture_player: usize,  // Fighter captured or recapturing.
+    shot_owner: usize,      // Fighter which hits enemies, earns the score.
+    invincible: bool,
+    arrange: bool,
+    score_rules: ScoreRules,
+    animations: AnimationTable,
+    boss_interval: u16,
+    difficulty: DifficultyProfile,
+    turns: PlayerTurns,
+    survivors: Option<Vec<FormationIndex>>,
 }
 
 impl GameManager {
@@ -72,19 +115,71 @@ impl GameManager {
             state: GameState::StartStage,
             count: 0,
             stage_indicator,
-            player: Player::new(),
-            myshots: Default::default(),
+            fighters: vec![Fighter::new(0, CENTER_X)],
             stage_manager: StageManager::new(),
             event_queue: EventQueue::new(),
             effects: Default::default(),
+            items: Default::default(),
 
             stage,
-            left_ship: DEFAULT_LEFT_SHIP,
             capture_state: CaptureState::NoCapture,
             capture_enemy_fi: FormationIndex(0, 0),
+            capture_player: 0,
+            shot_owner: 0,
+            invincible: false,
+            arrange: false,
+            score_rules: ScoreRules::default(),
+            animations: builtin_animations(),
+            boss_interval: 0,
+            difficulty: DifficultyProfile::default(),
+            turns: PlayerTurns::default(),
+            survivors: None,
         }
     }
 
+    pub fn set_invincible(&mut self, invincible: bool) {
+        self.invincible = invincible;
+    }
+
+    pub fn set_arrange(&mut self, arrange: bool) {
+        self.arrange = arrange;
+    }
+
+    pub fn set_score_rules(&mut self, score_rules: ScoreRules) {
+        self.score_rules = score_rules;
+    }
+
+    pub fn set_animations(&mut self, animations: AnimationTable) {
+        self.animations = animations;
+    }
+
+    pub fn set_boss_interval(&mut self, interval: u16) {
+        self.boss_interval = interval;
+    }
+
+    pub fn set_difficulty(&mut self, difficulty: Difficulty) {
+        self.difficulty = DifficultyProfile::new(difficulty);
+    }
+
+    // Call after `set_difficulty`.
+    pub fn set_play_mode(&mut self, play_mode: PlayMode) {
+        match play_mode {
+            PlayMode::Single => {}
+            PlayMode::Alternate => {
+                self.turns = PlayerTurns::new(play_mode.player_count(), &PlayerProgress::new(self.difficulty.clone()));
+            }
+            PlayMode::Coop => {
+                self.fighters = COOP_PLAYER_X.iter().enumerate()
+                    .map(|(i, &x)| Fighter::new(i, x))
+                    .collect();
+            }
+        }
+    }
+
+    fn is_coop(&self) -> bool {
+        self.fighters.len() > 1
+    }
+
     #[cfg(debug_assertions)]
     pub fn stage_manager_mut(&mut self) -> &mut StageManager {
         &mut self.stage_manager
@@ -97,10 +192,10 @@ impl GameManager {
 
         self.stage_manager.reset_stable();
         self.event_queue.clear();
-        self.player = Player::new();
+        self.fighters = vec![Fighter::new(0, CENTER_X)];
 
-        self.myshots = Default::default();
         self.effects = Default::default();
+        self.items = Default::default();
 
         self.state = GameState::EditTraj;
     }
@@ -124,14 +219,29 @@ impl GameManager {
                     } else {
                         None
                     };
-                    self.stage_manager.start_next_stage(self.stage, captured_fighter);
+                    if is_boss_stage(self.stage, self.boss_interval) {
+                        // Boss comes back with full life.
+                        self.survivors = None;
+                        self.stage_manager.start_boss_stage(self.stage, &self.difficulty);
+                    } else if let Some(survivors) = self.survivors.take() {
+                        self.stage_manager.resume_stage(self.stage, &survivors, captured_fighter, &self.difficulty);
+                    } else {
+                        self.stage_manager.start_next_stage(self.stage, captured_fighter, &self.difficulty);
+                    }
                     self.state = GameState::Playing;
                 }
             }
             GameState::Playing => {
-                if self.stage_manager.all_destroyed() {
+                // Another fighter might be crashed during the capture sequence.
+                if self.fighters.iter().any(|fighter| fighter.is_waiting_restart()) {
+                    self.stage_manager.pause_attack(true);
+                    params.star_manager.set_stop(true);
+                    self.state = GameState::PlayerDead;
+                    self.count = 0;
+                } else if self.stage_manager.all_destroyed() {
                     self.state = GameState::StageClear;
                     self.count = 0;
+                    self.difficulty.stage_cleared();
                 }
             }
             GameState::Capturing | GameState::Recapturing => {}
@@ -156,7 +266,9 @@ impl GameManager {
             GameState::WaitReady2 => {
                 self.count += 1;
                 if self.count >= 60 {
-                    self.player.set_shot_enable(true);
+                    for fighter in self.fighters.iter_mut() {
+                        fighter.player.set_shot_enable(true);
+                    }
                     self.stage_manager.pause_attack(false);
                     params.star_manager.set_stop(false);
                     self.state = GameState::Playing;
@@ -173,6 +285,13 @@ impl GameManager {
                     self.count = 0;
                 }
             }
+            GameState::SwitchPlayer => {
+                // Shows game over of the player before switching.
+                self.count += 1;
+                if self.fighters[0].left_ship > 0 || self.count >= 35 * 60 / 10 {
+                    self.switch_player(params);
+                }
+            }
             GameState::GameOver => {
                 self.count += 1;
                 if self.count >= 35 * 60 / 10 {
@@ -187,24 +306,72 @@ impl GameManager {
     }
 
     fn next_player(&mut self) {
-        self.left_ship -= 1;
-        if self.left_ship == 0 {
+        for fighter in self.fighters.iter_mut().filter(|fighter| fighter.is_waiting_restart()) {
+            fighter.left_ship -= 1;
+            self.difficulty.ship_lost();
+        }
+        if self.turns.can_switch() {
+            self.stage_manager.pause_attack(true);
+            self.state = GameState::SwitchPlayer;
+            self.count = 0;
+        } else if self.fighters.iter().all(|fighter| fighter.left_ship == 0) {
             self.stage_manager.pause_attack(true);
             self.state = GameState::GameOver;
             self.count = 0;
         } else {
-            self.player.restart();
-            self.player.set_shot_enable(false);
+            for fighter in self.fighters.iter_mut().filter(|fighter| fighter.is_waiting_restart()) {
+                fighter.player.restart();
+                fighter.player.set_shot_enable(false);
+            }
             self.state = GameState::WaitReady2;
             self.count = 0;
         }
     }
 
+    fn switch_player(&mut self, params: &mut Params) {
+        let progress = self.turns.switch(self.progress());
+        self.restore_progress(progress);
+        params.score_holder.switch_player(self.turns.current());
+        params.star_manager.set_stop(false);
+
+        self.stage_manager.clear_stage();
+        let fighter = &mut self.fighters[0];
+        fighter.player.restart();
+        fighter.player.set_shot_enable(true);
+        fighter.myshots = Default::default();
+        self.effects = Default::default();
+        self.items = Default::default();
+
+        self.state = GameState::StartStage;
+        self.count = 0;
+    }
+
+    fn progress(&self) -> PlayerProgress {
+        PlayerProgress {
+            stage: self.stage,
+            left_ship: self.fighters[0].left_ship,
+            capture_enemy_fi: if self.capture_state == CaptureState::Captured { Some(self.capture_enemy_fi) } else { None },
+            difficulty: self.difficulty.clone(),
+            survivors: Some(self.stage_manager.survivors()),
+        }
+    }
+
+    fn restore_progress(&mut self, progress: PlayerProgress) {
+        self.stage = progress.stage;
+        self.stage_indicator.set_stage(std::cmp::min(self.stage, 255) + 1);
+        self.fighters[0].left_ship = progress.left_ship;
+        self.capture_state = if progress.capture_enemy_fi.is_some() { CaptureState::Captured } else { CaptureState::NoCapture };
+        self.capture_enemy_fi = progress.capture_enemy_fi.unwrap_or(FormationIndex(0, 0));
+        self.difficulty = progress.difficulty;
+        self.survivors = progress.survivors;
+    }
+
     fn update_common(&mut self, params: &mut Params, system: &mut impl SystemTrait) {
         self.update_player(params);
         self.update_myshots();
         self.update_enemies();
         self.update_effects();
+        self.update_items();
 
         // For MyShot.
         self.handle_event_queue(params, system);
@@ -217,12 +384,17 @@ impl GameManager {
     }
 
     fn update_player(&mut self, params: &mut Params) {
+        // Players take turns with their own pad.
+        let pads = if self.is_coop() { params.pads } else { &params.pads[self.turns.current()..] };
         let accessor = unsafe { peep(self) };
-        self.player.update(params.pad, accessor);
+        for (fighter, pad) in self.fighters.iter_mut().zip(pads.iter()) {
+            fighter.player.update(pad, accessor);
+        }
     }
 
     fn update_myshots(&mut self) {
-        for myshot_opt in self.myshots.iter_mut().filter(|x| x.is_some()) {
+        let myshots = self.fighters.iter_mut().flat_map(|fighter| fighter.myshots.iter_mut());
+        for myshot_opt in myshots.filter(|x| x.is_some()) {
             let myshot = myshot_opt.as_mut().unwrap();
             if !myshot.update() {
                 *myshot_opt = None;
@@ -244,45 +416,76 @@ impl GameManager {
         }
     }
 
+    fn update_items(&mut self) {
+        for item_opt in self.items.iter_mut().filter(|x| x.is_some()) {
+            if !item_opt.as_mut().unwrap().update() {
+                *item_opt = None;
+            }
+        }
+    }
+
     pub fn draw(&mut self, renderer: &mut impl RendererTrait) {
-        self.player.draw(renderer);
+        for fighter in self.fighters.iter() {
+            fighter.player.draw(renderer);
+        }
         self.stage_manager.draw(renderer);
-        for myshot in self.myshots.iter().flatten() {
+        for myshot in self.fighters.iter().flat_map(|fighter| fighter.myshots.iter().flatten()) {
             myshot.draw(renderer);
         }
 
         for effect in self.effects.iter().flatten() {
             effect.draw(renderer);
         }
+        for item in self.items.iter().flatten() {
+            item.draw(renderer);
+        }
         self.stage_indicator.draw(renderer);
 
-        if self.left_ship > 0 {
-            let disp_count = std::cmp::min(self.left_ship - 1, 8);
+        for (index, fighter) in self.fighters.iter().enumerate() {
+            let x = if index == 0 { 0 } else { WIDTH };
+            fighter.player.power_ups().draw(renderer, x, index != 0);
+        }
+
+        // Second fighter's ships are lined from the right.
+        for (index, fighter) in self.fighters.iter().enumerate().filter(|(_, fighter)| fighter.left_ship > 0) {
+            let disp_count = std::cmp::min(fighter.left_ship - 1, 8);
             for i in 0..disp_count {
-                renderer.draw_sprite("rustacean", &Vec2I::new(i as i32 * 16, HEIGHT - 16));
+                let x = if index == 0 { i as i32 * 16 } else { WIDTH - 16 - i as i32 * 16 };
+                renderer.draw_sprite("rustacean", &Vec2I::new(x, HEIGHT - 16));
             }
         }
 
+        match self.state {
+            GameState::StartStage | GameState::WaitReady2 | GameState::SwitchPlayer | GameState::GameOver
+                if self.turns.is_two_players() =>
+            {
+                draw_text(renderer, "font", [&Vec2I::new(0, 16 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((0, 255, 255)), &format!("PLAYER {}", self.turns.current() + 1));
+            }
+            _ => {}
+        }
+
         match self.state {
             GameState::StartStage => {
-                renderer.set_texture_color_mod("font", 0, 255, 255);
-                renderer.draw_str("font", 10 * 8, 18 * 8, &format!("STAGE {}", self.stage + 1));
+                draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((0, 255, 255)), &format!("STAGE {}", self.stage + 1));
             }
             GameState::WaitReady | GameState::WaitReady2 => {
-                if self.left_ship > 1 || self.state == GameState::WaitReady2 {
-                    renderer.set_texture_color_mod("font", 0, 255, 255);
-                    renderer.draw_str("font", (28 - 6) / 2 * 8, 18 * 8, "READY");
+                let left_ship = self.fighters.iter().map(|fighter| fighter.left_ship).max().unwrap();
+                if (left_ship > 1 && !self.turns.can_switch()) || self.state == GameState::WaitReady2 {
+                    draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                              &TextStyle::centered((0, 255, 255)), "READY");
                 }
             }
             GameState::Captured => {
                 if self.count < 120 {
-                    renderer.set_texture_color_mod("font", 255, 0, 0);
-                    renderer.draw_str("font", (28 - 16) / 2 * 8, 19 * 8, "FIGHTER CAPTURED");
+                    draw_text(renderer, "font", [&Vec2I::new(0, 19 * 8), &Vec2I::new(WIDTH, 8)],
+                              &TextStyle::centered((255, 0, 0)), "FIGHTER CAPTURED");
                 }
             }
-            GameState::GameOver => {
-                renderer.set_texture_color_mod("font", 0, 255, 255);
-                renderer.draw_str("font", (28 - 8) / 2 * 8, 18 * 8, "GAME OVER");
+            GameState::SwitchPlayer | GameState::GameOver if self.fighters[0].left_ship == 0 => {
+                draw_text(renderer, "font", [&Vec2I::new(0, 18 * 8), &Vec2I::new(WIDTH, 8)],
+                          &TextStyle::centered((0, 255, 255)), "GAME OVER");
             }
             _ => {}
         }
@@ -296,13 +499,16 @@ impl GameManager {
         let mut i = 0;
         while i < self.event_queue.len() {
             match self.event_queue[i] {
-                EventType::AddScore(add) => {
-                    self.add_score(params.score_holder.score, add, system);
-                    params.score_holder.add_score(add);
+                EventType::AddScore(fighter, add) => {
+                    let player = if self.is_coop() { fighter } else { params.score_holder.player() };
+                    self.add_score(fighter, params.score_holder.scores[player], add, system);
+                    params.score_holder.add_player_score(player, add);
                 }
-                EventType::DeadPlayer => {
+                EventType::DeadPlayer(_fighter) => {
                     params.star_manager.set_stop(true);
-                    if self.state != GameState::Recapturing {
+                    // Crashes in the capture sequence are handled after it.
+                    let in_capture = matches!(self.state, GameState::Capturing | GameState::Captured | GameState::Recapturing);
+                    if !in_capture {
                         self.stage_manager.pause_attack(true);
                         self.state = GameState::PlayerDead;
                         self.count = 0;
@@ -333,16 +539,17 @@ impl GameManager {
                 self.capture_state = CaptureState::NoCapture;
                 self.capture_enemy_fi = FormationIndex(0, 0);
             }
-            CaptureEventType::CapturePlayer(capture_pos) => {
+            CaptureEventType::CapturePlayer(player, capture_pos) => {
                 self.push_event(EventType::StarEvent(StarEventType::Capturing(true)));
                 self.stage_manager.pause_attack(true);
-                self.player.start_capture(&capture_pos);
+                self.capture_player = player;
+                self.fighters[player].player.start_capture(&capture_pos);
                 self.state = GameState::Capturing;
                 self.capture_state = CaptureState::Capturing;
             }
             CaptureEventType::CapturePlayerCompleted => {
                 self.push_event(EventType::StarEvent(StarEventType::Capturing(false)));
-                self.player.complete_capture();
+                self.fighters[self.capture_player].player.complete_capture();
                 self.capture_state = CaptureState::Captured;
                 self.state = GameState::Captured;
                 self.count = 0;
@@ -356,7 +563,9 @@ impl GameManager {
             CaptureEventType::RecapturePlayer(fi, angle) => {
                 if let Some(captured_fighter) = self.stage_manager.get_enemy_at(&fi) {
                     let pos = captured_fighter.pos();
-                    self.player.start_recapture_effect(pos, angle);
+                    // Fighter which shot down the owl takes the captured one.
+                    self.capture_player = self.shot_owner;
+                    self.fighters[self.capture_player].player.start_recapture_effect(pos, angle);
                     self.stage_manager.remove_enemy(&fi);
                     self.stage_manager.pause_attack(true);
                     self.play_se(CH_JINGLE, SE_RECAPTURE);
@@ -365,7 +574,7 @@ impl GameManager {
                 }
             }
             CaptureEventType::MovePlayerHomePos => {
-                self.player.start_move_home_pos();
+                self.fighters[self.capture_player].player.start_move_home_pos();
             }
             CaptureEventType::RecaptureEnded(dual) => {
                 self.stage_manager.pause_attack(false);
@@ -378,7 +587,7 @@ impl GameManager {
                 self.capture_state = CaptureState::NoCapture;
                 self.capture_enemy_fi = FormationIndex(0, 0);
                 self.push_event(EventType::StarEvent(StarEventType::Capturing(false)));
-                self.player.escape_capturing();
+                self.fighters[self.capture_player].player.escape_capturing();
             }
             CaptureEventType::EscapeEnded => {
                 self.stage_manager.pause_attack(false);
@@ -391,19 +600,36 @@ impl GameManager {
         }
     }
 
-    fn add_score(&mut self, before: u32, add: u32, system: &mut impl SystemTrait) {
-        let ext = if before < EXTEND_FIRST_SCORE {
-            EXTEND_FIRST_SCORE
-        } else {
-            (before + EXTEND_AFTER_SCORE - 1) / EXTEND_AFTER_SCORE * EXTEND_AFTER_SCORE
-        };
-        if before + add >= ext {
-            self.extend_ship(system);
+    fn do_transform_event(&mut self, event: TransformEventType) {
+        match event {
+            TransformEventType::Transform(pos, flip_x) => {
+                self.stage_manager.spawn_transformed_enemies(self.stage, &pos, flip_x);
+            }
+            TransformEventType::Destroyed(pos) => {
+                if let Some(group_type) = self.stage_manager.transformed_enemy_destroyed() {
+                    let bonus = self.score_rules.transformed_group_bonus(group_type);
+                    self.push_event(EventType::AddScore(self.shot_owner, bonus));
+                    if let Some(effect) = to_earned_point_type(bonus)
+                        .and_then(|point_type| Effect::create_earned_point(point_type, &pos, &self.animations))
+                    {
+                        self.spawn_effect(effect);
+                    }
+                }
+            }
+            TransformEventType::Escaped => {
+                self.stage_manager.transformed_enemy_escaped();
+            }
         }
     }
 
-    fn extend_ship(&mut self, system: &mut impl SystemTrait) {
-        self.left_ship += 1;
+    fn add_score(&mut self, fighter: usize, before: u32, add: u32, system: &mut impl SystemTrait) {
+        if self.score_rules.is_extended(before, add) {
+            self.extend_ship(fighter, system);
+        }
+    }
+
+    fn extend_ship(&mut self, fighter: usize, system: &mut impl SystemTrait) {
+        self.fighters[fighter].left_ship += 1;
         system.play_se(CH_JINGLE, SE_EXTEND_SHIP);
     }
 
@@ -415,12 +641,22 @@ impl GameManager {
 
         self.check_collision_myshot_enemy();
         self.check_collision_player_enemy();
+        self.check_collision_player_item();
     }
 
     fn check_collision_myshot_enemy(&mut self) {
         let power = 1;
         let accessor = unsafe { peep(self) };
-        for myshot_opt in self.myshots.iter_mut().filter(|x| x.is_some()) {
+        for index in 0..self.fighters.len() {
+            self.shot_owner = index;
+            let mut myshots = std::mem::take(&mut self.fighters[index].myshots);
+            self.check_collision_myshots(&mut myshots, power, accessor);
+            self.fighters[index].myshots = myshots;
+        }
+    }
+
+    fn check_collision_myshots(&mut self, myshots: &mut [Option<MyShot>], power: u32, accessor: &mut Self) {
+        for myshot_opt in myshots.iter_mut().filter(|x| x.is_some()) {
             let myshot = myshot_opt.as_ref().unwrap();
             let colls = [
                 myshot.get_collbox(),
@@ -441,35 +677,81 @@ impl GameManager {
     fn check_collision_player_enemy(&mut self) {
         let power = 100;
         let accessor = unsafe { peep(self) };
-        for i in 0..2 {
-            let dual = i != 0;
-            let collbox = if dual { self.player.dual_collbox() } else { self.player.get_collbox() };
-            if let Some(collbox) = collbox {
-                let hit = self.stage_manager.check_collision(
-                            &collbox, power, accessor) ||
-                    self.stage_manager.check_shot_collision(&collbox);
-
-                if hit {
-                    let player_pos = if dual { self.player.dual_pos().unwrap() } else { *self.player.pos() };
-                    self.spawn_effect(Effect::create_player_explosion(&player_pos));
-                    self.play_se(CH_BOMB, SE_BOMB_PLAYER);
-                    if self.player.crash(dual) {
-                        self.event_queue.push(EventType::DeadPlayer);
-                        break;
-                    } else {
-                        // Must be one of dual fighter crashed.
-                        assert!(self.capture_state == CaptureState::Dual);
-                        self.capture_state = CaptureState::NoCapture;
+        for index in 0..self.fighters.len() {
+            self.shot_owner = index;
+            for i in 0..2 {
+                let dual = i != 0;
+                let player = &self.fighters[index].player;
+                let collbox = if dual { player.dual_collbox() } else { player.get_collbox() };
+                if let Some(collbox) = collbox {
+                    let hit = self.stage_manager.check_collision(
+                                &collbox, power, accessor) ||
+                        (!self.invincible && self.stage_manager.check_shot_collision(&collbox));
+
+                    if hit && self.invincible {
+                        continue;  // Rammed enemy is destroyed, but not the player.
+                    }
+                    if hit && self.fighters[index].player.power_ups_mut().consume_shield() {
+                        self.play_se(CH_BOMB, SE_SHIELD);
+                        continue;
                     }
+                    if hit {
+                        let player = &mut self.fighters[index].player;
+                        let player_pos = if dual { player.dual_pos().unwrap() } else { *player.pos() };
+                        let dead = player.crash(dual);
+                        if let Some(effect) = Effect::create_player_explosion(&player_pos, &self.animations) {
+                            self.spawn_effect(effect);
+                        }
+                        self.play_se(CH_BOMB, SE_BOMB_PLAYER);
+                        if dead {
+                            self.event_queue.push(EventType::DeadPlayer(index));
+                            break;
+                        } else {
+                            // Must be one of dual fighter crashed.
+                            assert!(self.capture_state == CaptureState::Dual);
+                            self.capture_state = CaptureState::NoCapture;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_collision_player_item(&mut self) {
+        for item_opt in self.items.iter_mut().filter(|x| x.is_some()) {
+            let item_collbox = item_opt.as_ref().unwrap().get_collbox().unwrap();
+            let picker = self.fighters.iter_mut().find(|fighter| {
+                let player = &fighter.player;
+                [player.get_collbox(), player.dual_collbox()].iter().flatten()
+                    .any(|collbox| collbox.check_collision(&item_collbox))
+            });
+            if let Some(fighter) = picker {
+                let item_type = item_opt.take().unwrap().item_type();
+                fighter.player.power_ups_mut().apply(item_type);
+                if item_type == ItemType::Bomb {
+                    self.stage_manager.clear_shots();
+                    self.event_queue.push(EventType::PlaySe(CH_BOMB, SE_ITEM_BOMB));
                 }
+                self.event_queue.push(EventType::PlaySe(CH_JINGLE, SE_GET_ITEM));
             }
         }
     }
+
+    fn living_players(&self) -> impl Iterator<Item = &Player> {
+        self.fighters.iter()
+            .map(|fighter| &fighter.player)
+            .filter(|player| !player.is_dead())
+    }
 }
 
 impl AccessorForPlayer for GameManager {
-    fn spawn_myshot(&mut self, pos: &Vec2I, dual: bool, angle: i32) {
-        if let Some(myshot_opt) = self.myshots.iter_mut().find(|x| x.is_none()) {
+    fn spawn_myshot(&mut self, player: usize, pos: &Vec2I, dual: bool, angle: i32) {
+        let fighter = &mut self.fighters[player];
+        let count = fighter.player.power_ups().myshot_count(MYSHOT_COUNT);
+        if fighter.myshots.iter().flatten().count() >= count {
+            return;
+        }
+        if let Some(myshot_opt) = fighter.myshots.iter_mut().find(|x| x.is_none()) {
             *myshot_opt = Some(MyShot::new(pos, dual, angle));
             self.play_se(CH_SHOT, SE_MYSHOT);
         }
@@ -484,16 +766,13 @@ impl AccessorForPlayer for GameManager {
 
 impl AccessorForEnemy for GameManager {
     fn add_score(&mut self, add: u32) {
-        self.event_queue.push(EventType::AddScore(add));
+        self.event_queue.push(EventType::AddScore(self.shot_owner, add));
     }
 
-    fn spawn_ene_shot(&mut self, pos: &Vec2I) {
-        let player_pos = [
-            Some(*self.player.pos()),
-            self.player.dual_pos(),
-        ];
-        let speed = calc_ene_shot_speed(self.stage);
-        self.stage_manager.spawn_shot(pos, &player_pos, speed);
+    fn spawn_ene_shot(&mut self, pos: &Vec2I, pattern: ShotPattern) {
+        let player_pos = self.get_player_target_pos();
+        let speed = ene_shot_speed(self.difficulty.level(self.stage));
+        self.stage_manager.spawn_shot(pos, &player_pos, speed, pattern);
     }
 
     fn spawn_effect(&mut self, effect: Effect) {
@@ -502,24 +781,55 @@ impl AccessorForEnemy for GameManager {
         }
     }
 
-    fn get_player_pos(&self) -> &Vec2I {
-        self.player.pos()
+    fn drop_item(&mut self, pos: &Vec2I) {
+        if !self.arrange {
+            return;
+        }
+        if let Some(item_type) = roll_item_drop() {
+            if let Some(slot) = self.items.iter_mut().find(|x| x.is_none()) {
+                *slot = Some(Item::new(pos, item_type));
+            }
+        }
     }
 
-    fn get_dual_player_pos(&self) -> Option<Vec2I> {
-        self.player.dual_pos()
+    fn get_player_pos(&self) -> &Vec2I {
+        self.living_players().next()
+            .unwrap_or(&self.fighters[0].player)
+            .pos()
+    }
+
+    fn get_player_target_pos(&self) -> Vec<(usize, Vec2I)> {
+        let target_pos: Vec<(usize, Vec2I)> = self.fighters.iter().enumerate()
+            .filter(|(_, fighter)| !fighter.player.is_dead())
+            .flat_map(|(index, fighter)| {
+                [Some(*fighter.player.pos()), fighter.player.dual_pos()].to_vec().into_iter()
+                    .flatten()
+                    .map(move |pos| (index, pos))
+            })
+            .collect();
+        if target_pos.is_empty() {
+            vec![(0, *self.fighters[0].player.pos())]
+        } else {
+            target_pos
+        }
     }
 
-    fn can_player_capture(&self) -> bool {
+    fn capturable_players(&self) -> Vec<(usize, Vec2I)> {
         #[cfg(debug_assertions)]
         if self.state == GameState::EditTraj {
-            return false;
+            return vec![];
+        }
+        if self.state != GameState::Playing {
+            return vec![];
         }
-        self.state == GameState::Playing
+        self.fighters.iter().enumerate()
+            .filter(|(_, fighter)| fighter.player.get_collbox().is_some())
+            .map(|(index, fighter)| (index, *fighter.player.pos()))
+            .collect()
     }
 
     fn is_player_capture_completed(&self) -> bool {
-        self.player.is_captured()
+        self.fighters[self.capture_player].player.is_captured()
     }
 
     fn capture_state(&self) -> CaptureState {
@@ -558,8 +868,24 @@ impl AccessorForEnemy for GameManager {
         self.state == GameState::Playing && self.stage_manager.is_rush()
     }
 
-    fn get_stage_no(&self) -> u16 {
-        self.stage
+    fn choose_escorts(&self, leader: &FormationIndex) -> Escorts {
+        self.stage_manager.choose_escorts(leader, self)
+    }
+
+    fn get_level(&self) -> u16 {
+        self.difficulty.level(self.stage)
+    }
+
+    fn is_shot_pattern_enabled(&self) -> bool {
+        self.difficulty.is_shot_pattern_enabled()
+    }
+
+    fn score_rules(&self) -> &ScoreRules {
+        &self.score_rules
+    }
+
+    fn animations(&self) -> &AnimationTable {
+        &self.animations
     }
 
     fn play_se(&mut self, channel: u32, asset_path: &'static str) {
@@ -567,10 +893,5 @@ impl AccessorForEnemy for GameManager {
     }
 
     fn capture_event(&mut self, event: CaptureEventType) { self.do_capture_event(event); }
-}
-
-fn calc_ene_shot_speed(stage: u16) -> i32 {
-    const MAX_STAGE: i32 = 64;
-    let per = std::cmp::min(stage as i32, MAX_STAGE) * ONE / MAX_STAGE;
-    (ENE_SHOT_SPEED2 - ENE_SHOT_SPEED1) * per / ONE + ENE_SHOT_SPEED1
+    fn transform_event(&mut self, event: TransformEventType) { self.do_transform_event(event); }
 }
@@ -9,7 +9,7 @@ use galangua_common::framework::types::Vec2I;
 pub enum CaptureEventType {
     StartCaptureAttack(FormationIndex),
     EndCaptureAttack,
-    CapturePlayer(Vec2I),
+    CapturePlayer(usize, Vec2I),  // player, capture pos
     CapturePlayerCompleted,
     CaptureSequenceEnded,
     SpawnCapturedFighter(Vec2I, FormationIndex),
@@ -20,3 +20,10 @@ pub enum CaptureEventType {
     EscapeEnded,
     CapturedFighterDestroyed,
 }
+
+#[derive(Clone)]
+pub enum TransformEventType {
+    Transform(Vec2I, bool),  // pos, flip_x
+    Destroyed(Vec2I),
+    Escaped,
+}
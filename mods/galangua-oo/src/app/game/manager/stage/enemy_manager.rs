@@ -10,19 +10,22 @@ use crate::app::game::enemy::Accessor;
 use galangua_common::app::consts::*;
 use galangua_common::app::game::effect_table::to_earned_point_type;
 use galangua_common::app::game::formation_table::X_COUNT;
+use galangua_common::app::game::shot_pattern::{shot_velocities, update_target_trackers, ShotPattern, TargetTracker};
+use galangua_common::app::game::transform::TRANSFORM_FORMATION_Y;
 use galangua_common::app::game::{EnemyType, FormationIndex};
+use galangua_common::app::score_holder::MAX_PLAYER_COUNT;
 use galangua_common::app::util::collision::{CollBox, Collidable};
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
-use galangua_common::util::math::{atan2_lut, calc_velocity, clamp, ANGLE, ONE};
 
-const MAX_ENEMY_COUNT: usize = 70;
+const MAX_ENEMY_COUNT: usize = X_COUNT * (TRANSFORM_FORMATION_Y as usize + 1);
 
 pub struct EnemyManager {
     enemies: [Option<Box<dyn Enemy>>; MAX_ENEMY_COUNT],
     pub(super) alive_enemy_count: u32,
     shots: [Option<EneShot>; MAX_ENE_SHOT_COUNT],
     shot_paused_count: u32,
+    target_trackers: [TargetTracker; MAX_PLAYER_COUNT],
     frame_count: u32,
 }
 
@@ -33,6 +36,7 @@ impl EnemyManager {
             alive_enemy_count: 0,
             shots: Default::default(),
             shot_paused_count: 0,
+            target_trackers: Default::default(),
             frame_count: 0,
         }
     }
@@ -42,6 +46,7 @@ impl EnemyManager {
         self.alive_enemy_count = 0;
         self.shots = Default::default();
         self.shot_paused_count = 0;
+        self.target_trackers = Default::default();
         self.frame_count = 0;
     }
 
@@ -54,6 +59,7 @@ impl EnemyManager {
         if self.shot_paused_count > 0 {
             self.shot_paused_count -= 1;
         }
+        update_target_trackers(&mut self.target_trackers, &accessor.get_player_target_pos());
 
         self.update_enemies(accessor);
         self.update_shots();
@@ -81,8 +87,10 @@ impl EnemyManager {
                     if result.point > 0 {
                         accessor.add_score(result.point);
 
-                        if let Some(point_type) = to_earned_point_type(result.point) {
-                            accessor.spawn_effect(Effect::create_earned_point(point_type, &pos));
+                        if let Some(effect) = to_earned_point_type(result.point)
+                            .and_then(|point_type| Effect::create_earned_point(point_type, &pos, accessor.animations()))
+                        {
+                            accessor.spawn_effect(effect);
                         }
 
                         if !result.keep_alive_as_ghost {
@@ -139,27 +147,25 @@ impl EnemyManager {
         true
     }
 
-    pub fn spawn_shot(&mut self, pos: &Vec2I, target_pos: &[Option<Vec2I>], speed: i32) {
-        if self.shot_paused_count > 0 {
+    pub fn spawn_shot(&mut self, pos: &Vec2I, target_pos: &[(usize, Vec2I)], speed: i32, pattern: ShotPattern) {
+        if self.shot_paused_count > 0 || self.shots.iter().all(|x| x.is_some()) {
             return;
         }
 
-        if let Some(index) = self.shots.iter().position(|x| x.is_none()) {
-            let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
-            let count = target_pos.iter().flatten().count();
-            let target: &Vec2I = target_pos.iter()
-                .flatten().nth(rng.random_range(0..count)).unwrap();
-            let d = target - pos;
-
-            let limit = ANGLE * ONE * 30 / 360;
-            let angle = atan2_lut(d.y, -d.x);  // 0=down
-            let angle = clamp(angle, -limit, limit);
+        let mut rng = Xoshiro128Plus::from_seed(rand::rng().random());
+        let (player, target) = &target_pos[rng.random_range(0..target_pos.len())];
 
-            let vel = calc_velocity(angle + ANGLE * ONE / 2, speed);
-            self.shots[index] = Some(EneShot::new(pos, &vel));
+        let vels = shot_velocities(pattern, pos, target, self.target_trackers[*player].vel(), speed);
+        let slots = self.shots.iter_mut().filter(|x| x.is_none());
+        for (slot, vel) in slots.zip(vels.iter()) {
+            *slot = Some(EneShot::new(pos, vel));
         }
     }
 
+    pub fn clear_shots(&mut self) {
+        self.shots = Default::default();
+    }
+
     pub fn pause_enemy_shot(&mut self, wait: u32) {
         self.shot_paused_count = wait;
     }
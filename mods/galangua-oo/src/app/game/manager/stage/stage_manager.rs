@@ -1,23 +1,29 @@
 use super::enemy_manager::EnemyManager;
 
-use crate::app::game::enemy::enemy::{create_appearance_enemy, Enemy};
+use crate::app::game::enemy::boss::Boss;
+use crate::app::game::enemy::enemy::{create_appearance_enemy, create_transformed_enemy, Enemy};
 use crate::app::game::enemy::Accessor;
 use crate::app::game::manager::CaptureEventType;
 
-use galangua_common::app::consts::*;
 use galangua_common::app::game::appearance_manager::Accessor as AccessorForAppearance;
 use galangua_common::app::game::appearance_manager::AppearanceManager;
+use galangua_common::app::game::appearance_table::ORDER;
 use galangua_common::app::game::attack_manager::Accessor as AttackManagerAccessor;
-use galangua_common::app::game::attack_manager::AttackManager;
+use galangua_common::app::game::attack_manager::{AttackManager, AttackType};
+use galangua_common::app::game::attack_strategy::Escorts;
+use galangua_common::app::game::difficulty::DifficultyProfile;
 use galangua_common::app::game::formation::Formation;
-use galangua_common::app::game::{CaptureState, FormationIndex};
+use galangua_common::app::game::shot_pattern::ShotPattern;
+use galangua_common::app::game::traj::Traj;
+use galangua_common::app::game::transform::*;
+use galangua_common::app::game::{CaptureState, EnemyType, FormationIndex};
 use galangua_common::app::util::collision::CollBox;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
 use galangua_common::util::unsafe_util::peep;
 
 #[cfg(debug_assertions)]
-use galangua_common::app::game::appearance_table::{ENEMY_TYPE_TABLE, ORDER};
+use galangua_common::app::game::appearance_table::ENEMY_TYPE_TABLE;
 
 #[cfg(debug_assertions)]
 use crate::app::game::enemy::enemy::create_enemy;
@@ -36,6 +42,8 @@ pub struct StageManager {
     appearance_manager: AppearanceManager,
     attack_manager: AttackManager,
     stage_state: StageState,
+    transform_group: TransformGroup,
+    rush_threshold: u32,
 }
 
 impl StageManager {
@@ -46,15 +54,61 @@ impl StageManager {
             appearance_manager: AppearanceManager::default(),
             attack_manager: AttackManager::default(),
             stage_state: StageState::Appearance,
+            transform_group: TransformGroup::default(),
+            rush_threshold: 0,
         }
     }
 
-    pub fn start_next_stage(&mut self, stage: u16, captured_fighter: Option<FormationIndex>) {
+    pub fn start_next_stage(
+        &mut self, stage: u16, captured_fighter: Option<FormationIndex>, difficulty: &DifficultyProfile,
+    ) {
+        self.appearance_manager.restart(stage, captured_fighter, difficulty);
+        self.restart(stage, difficulty);
+    }
+
+    pub fn resume_stage(
+        &mut self, stage: u16, survivors: &[FormationIndex], captured_fighter: Option<FormationIndex>,
+        difficulty: &DifficultyProfile,
+    ) {
+        self.appearance_manager.resume(stage, survivors, captured_fighter, difficulty);
+        self.restart(stage, difficulty);
+    }
+
+    // Boss alone, without the formation and attackers.
+    pub fn start_boss_stage(&mut self, stage: u16, difficulty: &DifficultyProfile) {
+        self.appearance_manager = AppearanceManager::default();
+        self.restart(stage, difficulty);
+        self.rush_threshold = 0;
+        self.stage_state = StageState::Normal;
+        self.enemy_manager.spawn(Box::new(Boss::new(difficulty.level(stage))));
+    }
+
+    // Removes all enemies and stops the stage, used when the player is switched.
+    pub fn clear_stage(&mut self) {
+        self.enemy_manager.start_next_stage();
+        self.appearance_manager = AppearanceManager::default();
+        self.attack_manager.set_enable(false);
+        self.transform_group = TransformGroup::default();
+    }
+
+    // Enemies which come back when the stage is resumed.
+    pub fn survivors(&self) -> Vec<FormationIndex> {
+        let mut survivors = self.appearance_manager.pending_indices();
+        survivors.extend(ORDER.iter().filter(|fi| {
+            // Destroyed owl can be left as a ghost.
+            self.enemy_manager.get_enemy_at(fi)
+                .is_some_and(|enemy| enemy.get_collbox().is_some())
+        }));
+        survivors
+    }
+
+    fn restart(&mut self, stage: u16, difficulty: &DifficultyProfile) {
         self.enemy_manager.start_next_stage();
-        self.appearance_manager.restart(stage, captured_fighter);
-        self.formation.restart();
-        self.attack_manager.restart(stage);
+        self.formation.restart(stage);
+        self.attack_manager.restart(stage, difficulty);
+        self.rush_threshold = difficulty.rush_threshold();
         self.stage_state = StageState::Appearance;
+        self.transform_group = TransformGroup::default();
     }
 
     pub fn all_destroyed(&self) -> bool {
@@ -106,6 +160,26 @@ impl StageManager {
         self.enemy_manager.spawn_captured_fighter(pos, fi)
     }
 
+    pub fn spawn_transformed_enemies(&mut self, stage: u16, pos: &Vec2I, flip_x: bool) {
+        let enemy_type = transformed_type(stage);
+        let table = transformed_attack_table(enemy_type);
+        for (i, offset) in TRANSFORM_OFFSETS.iter().enumerate() {
+            let fi = FormationIndex(i as u8, TRANSFORM_FORMATION_Y);
+            let mut traj = Traj::new(table, offset, flip_x, fi);
+            traj.set_pos(pos);
+            self.enemy_manager.spawn(create_transformed_enemy(enemy_type, pos, &fi, traj));
+        }
+        self.transform_group.start(enemy_type);
+    }
+
+    pub fn transformed_enemy_destroyed(&mut self) -> Option<EnemyType> {
+        self.transform_group.destroyed()
+    }
+
+    pub fn transformed_enemy_escaped(&mut self) {
+        self.transform_group.escaped();
+    }
+
     pub fn remove_enemy(&mut self, formation_index: &FormationIndex) -> bool {
         self.enemy_manager.remove_enemy(formation_index)
     }
@@ -117,14 +191,14 @@ impl StageManager {
     fn update_attackers(&mut self, accessor: &mut impl Accessor) {
         let acc = AttackManagerAccessorImpl(accessor);
         let result = self.attack_manager.update(&acc);
-        if let Some((fi, capture_attack)) = result {
+        if let Some((fi, attack_type)) = result {
             self.attack_manager.put_attacker(&fi);
             let enemy = {
                 let accessor = unsafe { peep(accessor) };
                 accessor.get_enemy_at_mut(&fi).unwrap()
             };
-            enemy.start_attack(capture_attack, accessor);
-            if capture_attack {
+            enemy.start_attack(attack_type, accessor);
+            if attack_type == AttackType::Capture {
                 accessor.capture_event(CaptureEventType::StartCaptureAttack(fi));
             }
         }
@@ -136,9 +210,9 @@ impl StageManager {
         }
 
         let new_state = match self.enemy_manager.alive_enemy_count {
-            n if n == 0               => StageState::Cleared,
-            n if n <= RUSH_THRESHOLD  => StageState::Rush,
-            _                         => self.stage_state,
+            n if n == 0                   => StageState::Cleared,
+            n if n <= self.rush_threshold => StageState::Rush,
+            _                             => self.stage_state,
         };
         if new_state != self.stage_state {
             self.stage_state = new_state;
@@ -149,8 +223,12 @@ impl StageManager {
         self.enemy_manager.pause_enemy_shot(wait);
     }
 
-    pub fn spawn_shot(&mut self, pos: &Vec2I, target_pos: &[Option<Vec2I>], speed: i32) {
-        self.enemy_manager.spawn_shot(pos, target_pos, speed);
+    pub fn clear_shots(&mut self) {
+        self.enemy_manager.clear_shots();
+    }
+
+    pub fn spawn_shot(&mut self, pos: &Vec2I, target_pos: &[(usize, Vec2I)], speed: i32, pattern: ShotPattern) {
+        self.enemy_manager.spawn_shot(pos, target_pos, speed, pattern);
     }
 
     pub fn pause_attack(&mut self, value: bool) {
@@ -158,6 +236,10 @@ impl StageManager {
         self.appearance_manager.pause(value);
     }
 
+    pub fn choose_escorts(&self, leader: &FormationIndex, accessor: &impl Accessor) -> Escorts {
+        self.attack_manager.choose_escorts(leader, &AttackManagerAccessorImpl(accessor))
+    }
+
     pub fn is_no_attacker(&self) -> bool {
         self.attack_manager.is_no_attacker()
     }
@@ -185,11 +267,12 @@ impl StageManager {
         self.enemy_manager.reset_stable();
 
         let stage = 0;
-        self.appearance_manager.restart(stage, None);
+        let difficulty = DifficultyProfile::default();
+        self.appearance_manager.restart(stage, None, &difficulty);
         self.appearance_manager.done = true;
-        self.formation.restart();
+        self.formation.restart(stage);
         self.formation.done_appearance();
-        self.attack_manager.restart(stage);
+        self.attack_manager.restart(stage, &difficulty);
         self.attack_manager.set_enable(false);
         self.stage_state = StageState::Normal;
 
@@ -1,4 +1,5 @@
 pub mod effect;
 pub mod enemy;
+pub mod item;
 pub mod manager;
 pub mod player;
@@ -1,4 +1,5 @@
 use galangua_common::app::consts::*;
+use galangua_common::app::game::item::PowerUps;
 use galangua_common::app::util::collision::{CollBox, Collidable};
 use galangua_common::framework::types::{Vec2I, ZERO_VEC};
 use galangua_common::framework::RendererTrait;
@@ -24,6 +25,8 @@ enum State {
 }
 
 pub struct Player {
+    index: usize,
+    home_x: i32,
     pos: Vec2I,
     state: State,
     dual: bool,
@@ -31,24 +34,28 @@ pub struct Player {
     capture_pos: Vec2I,
     recaptured_fighter: Option<RecapturedFighter>,
     shot_enable: bool,
+    power_ups: PowerUps,
 }
 
 impl Player {
-    pub fn new() -> Self {
+    pub fn new(index: usize, home_x: i32) -> Self {
         Self {
-            pos: Vec2I::new(CENTER_X, PLAYER_Y),
+            index,
+            home_x,
+            pos: Vec2I::new(home_x, PLAYER_Y),
             state: State::Normal,
             dual: false,
             angle: 0,
             capture_pos: ZERO_VEC,
             recaptured_fighter: None,
             shot_enable: true,
+            power_ups: PowerUps::default(),
         }
     }
 
     pub fn restart(&mut self) {
         self.state = State::Normal;
-        self.pos = Vec2I::new(CENTER_X, PLAYER_Y);
+        self.pos = Vec2I::new(self.home_x, PLAYER_Y);
     }
 
     pub fn set_shot_enable(&mut self, value: bool) {
@@ -56,6 +63,7 @@ impl Player {
     }
 
     pub fn update(&mut self, pad: &Pad, accessor: &mut impl Accessor) {
+        self.power_ups.update();
         match self.state {
             State::Normal => self.update_normal(pad, accessor),
             State::Capturing => self.update_capture(pad, accessor),
@@ -69,7 +77,7 @@ impl Player {
                 }
             }
             State::MoveHomePos => {
-                let x = CENTER_X - 8 * ONE;
+                let x = self.home_x - 8 * ONE;
                 let speed = 2 * ONE;
                 self.pos.x += clamp(x - self.pos.x, -speed, speed);
                 if self.pos.x == x && self.recaptured_fighter.as_ref().unwrap().done() {
@@ -85,7 +93,7 @@ impl Player {
         if let Some(recaptured_fighter) = &mut self.recaptured_fighter {
             recaptured_fighter.update(self.state != State::Dead, accessor);
             if self.state == State::Dead && recaptured_fighter.done() {
-                self.pos.x = CENTER_X;
+                self.pos.x = self.home_x;
                 self.state = State::Normal;
                 self.recaptured_fighter = None;
                 accessor.capture_event(CaptureEventType::RecaptureEnded(false));
@@ -127,9 +135,9 @@ impl Player {
     }
 
     fn fire_bullet(&mut self, pad: &Pad, accessor: &mut impl Accessor) {
-        if self.shot_enable && pad.is_trigger(PadBit::A) {
+        if self.shot_enable && self.power_ups.is_fire(pad) {
             let pos = &self.pos + &calc_velocity(self.angle, 4 * ONE);
-            accessor.spawn_myshot(&pos, self.dual, self.angle);
+            accessor.spawn_myshot(self.index, &pos, self.dual, self.angle);
         }
     }
 
@@ -193,6 +201,7 @@ impl Player {
         } else {
             assert!(!dual);
             self.state = State::Dead;
+            self.power_ups.clear();
             true
         }
     }
@@ -203,12 +212,25 @@ impl Player {
         self.angle = 0;
     }
 
+    pub fn is_dead(&self) -> bool {
+        self.state == State::Dead
+    }
+
     pub fn is_captured(&self) -> bool {
         self.state == State::Captured
     }
 
     pub fn complete_capture(&mut self) {
         self.state = State::Dead;
+        self.power_ups.clear();
+    }
+
+    pub fn power_ups(&self) -> &PowerUps {
+        &self.power_ups
+    }
+
+    pub fn power_ups_mut(&mut self) -> &mut PowerUps {
+        &mut self.power_ups
     }
 
     pub fn escape_capturing(&mut self) {
@@ -217,7 +239,7 @@ impl Player {
     }
 
     pub fn start_recapture_effect(&mut self, pos: &Vec2I, angle: i32) {
-        self.recaptured_fighter = Some(RecapturedFighter::new(pos, angle));
+        self.recaptured_fighter = Some(RecapturedFighter::new(pos, angle, self.home_x));
     }
 
     pub fn start_move_home_pos(&mut self) {
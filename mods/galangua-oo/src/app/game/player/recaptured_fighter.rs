@@ -21,14 +21,16 @@ pub(super) struct RecapturedFighter {
     pos: Vec2I,
     state: State,
     angle: i32,
+    home_x: i32,
 }
 
 impl RecapturedFighter {
-    pub(super) fn new(pos: &Vec2I, angle: i32) -> Self {
+    pub(super) fn new(pos: &Vec2I, angle: i32, home_x: i32) -> Self {
         Self {
             pos: *pos,
             state: State::Rotate,
             angle: angle & (ANGLE * ONE - 1),
+            home_x,
         }
     }
 
@@ -45,7 +47,7 @@ impl RecapturedFighter {
                 }
             }
             State::SlideHorz => {
-                let x = CENTER_X + if player_living { 8 * ONE } else { 0 };
+                let x = self.home_x + if player_living { 8 * ONE } else { 0 };
                 self.pos.x += clamp(x - self.pos.x, -SPEED, SPEED);
                 if self.pos.x == x {
                     self.state = State::SlideDown;
@@ -6,24 +6,39 @@ use counted_array::counted_array;
 use lazy_static::lazy_static;
 use sdl2::keyboard::Keycode;
 use std::collections::HashMap;
+use std::path::Path;
 
+use galangua_common::app::accessibility::outline_sprite_names;
+use galangua_common::app::asset_check;
+use galangua_common::app::assets::{asset_manifest, set_asset_manifest};
 use galangua_common::app::consts::*;
+use galangua_common::app::game::effect_table::builtin_animations;
+use galangua_common::framework::asset_manifest::AssetManifest;
+use galangua_common::framework::color_profile::ColorProfile;
+use galangua_common::framework::sprite_sheet::AnimationTable;
+use galangua_common::framework::theme::{Theme, THEME_FILE_NAME};
 use galangua_common::framework::{AppTrait, VKey};
 use galangua_ecs::app::GalanguaEcsApp;
 use galangua_oo::app::GalanguaApp;
 
 use crate::sdl::SdlAppFramework;
 use crate::sdl::SdlAudio;
-use crate::sdl::SdlRenderer;
+use crate::sdl::{Orientation, ScaleFilter, ScreenSettings, SdlRenderer};
 use crate::std_system::StdSystem;
 use crate::std_timer::StdTimer;
 
 const APP_NAME: &str = "Galangua";
+const MANIFEST_PATH: &str = "assets/manifest.json";
+const THEMES_DIR: &str = "themes";
+const DEFAULT_THEME: &str = "default";
+const KEY_THEME: &str = "theme";
 
-fn run_app(app: impl AppTrait<SdlRenderer>, scale: u32, fullscreen: bool) -> Result<(), String> {
+fn run_app(app: impl AppTrait<SdlRenderer>, settings: &ScreenSettings) -> Result<(), String> {
     let mut framework = SdlAppFramework::new(app, map_key)?;
+    #[cfg(debug_assertions)]
+    framework.watch_assets(&asset_manifest());
     framework.run(APP_NAME,
-                  WIDTH as u32, HEIGHT as u32, scale, fullscreen)
+                  WIDTH as u32, HEIGHT as u32, settings)
 }
 
 pub fn main() -> Result<(), String> {
@@ -32,6 +47,10 @@ pub fn main() -> Result<(), String> {
         .about("2D shoot'em up game, writen in Rust.
   Move the fighter : Arrow keys (left or right)
   Shoot a bullet   : Space bar
+  Switch palette   : F8
+  Switch filter    : F9
+  Rotate screen    : F10
+  Toggle CRT look  : F11
   Quit the app     : Escape key")
         .arg(clap::Arg::new("full")
              .help("Use fullscreen")
@@ -42,27 +61,169 @@ pub fn main() -> Result<(), String> {
              .short('s')
              .long("scale")
              .takes_value(true))
+        .arg(clap::Arg::new("filter")
+             .help("Specify screen filter: nearest, linear or scanline (default: nearest)")
+             .long("filter")
+             .takes_value(true))
+        .arg(clap::Arg::new("rotate")
+             .help("Rotate screen for a sideways monitor: 0, 90 or 270 (default: 0)")
+             .short('r')
+             .long("rotate")
+             .takes_value(true))
+        .arg(clap::Arg::new("crt")
+             .help("Apply CRT effect (scanline, bloom, shadow mask and curvature)")
+             .long("crt"))
+        .arg(clap::Arg::new("palette")
+             .help("Specify color palette: normal, deuteranopia, protanopia, tritanopia or high-contrast (default: normal)")
+             .long("palette")
+             .takes_value(true))
+        .arg(clap::Arg::new("outline")
+             .help("Outline enemy shots and the tractor beam")
+             .long("outline"))
+        .arg(clap::Arg::new("theme")
+             .help("Use a theme in themes/<name>, kept for next time (`default` for built-in one)")
+             .long("theme")
+             .takes_value(true))
+        .arg(clap::Arg::new("check-assets")
+             .help("Validate sprite names and sound files, then exit")
+             .long("check-assets"))
          .arg(clap::Arg::new("oo")
              .help("Run OO version")
              .long("oo"))
         .get_matches();
 
+    load_manifest()?;
+    if matches.is_present("check-assets") {
+        return check_assets();
+    }
+
     let fullscreen = matches.is_present("full");
     let scale = if let Some(scale) = matches.value_of("scale") {
         String::from(scale).parse().unwrap()
     } else {
         3
     };
-
+    let filter = if let Some(filter) = matches.value_of("filter") {
+        ScaleFilter::from_name(filter)
+            .ok_or_else(|| format!("Unknown filter: {}", filter))?
+    } else {
+        ScaleFilter::Nearest
+    };
+    let orientation = if let Some(rotate) = matches.value_of("rotate") {
+        Orientation::from_degree(rotate)
+            .ok_or_else(|| format!("Unsupported rotation: {}", rotate))?
+    } else {
+        Orientation::Normal
+    };
+    let crt = matches.is_present("crt");
+    let color_profile = if let Some(palette) = matches.value_of("palette") {
+        ColorProfile::from_name(palette)
+            .ok_or_else(|| format!("Unknown palette: {}", palette))?
+    } else {
+        ColorProfile::Normal
+    };
     let audio = SdlAudio::new(CHANNEL_COUNT, BASE_VOLUME);
     let timer = StdTimer::new();
-    let system = StdSystem::new(audio);
+    let mut system = StdSystem::new(audio);
+    if let Some(name) = matches.value_of("theme") {
+        system.set_str(KEY_THEME, if name == DEFAULT_THEME { None } else { Some(name) });
+    }
+    let theme = match system.get_str(KEY_THEME).map(load_theme) {
+        Some(Ok(theme)) => Some(theme),
+        Some(Err(err)) => {
+            // Forgets the theme, not to fail on every launch.
+            println!("{}, use built-in theme", err);
+            system.set_str(KEY_THEME, None);
+            None
+        }
+        None => None,
+    };
+    if let Some(theme) = &theme {
+        for (from, to) in theme.sound_overrides(&asset_manifest()) {
+            system.audio_mut().set_override(from, to);
+        }
+    }
+    let animations = load_animations(theme.as_ref());
+
+    let outline_sprites = if matches.is_present("outline") { outline_sprite_names(&animations) } else { Vec::new() };
+    let settings = ScreenSettings { scale, fullscreen, filter, orientation, crt, color_profile, outline_sprites };
+
     if matches.is_present("oo") {
-        let app = GalanguaApp::new(timer, system);
-        run_app(app, scale, fullscreen)
+        let mut app = GalanguaApp::new(timer, system);
+        if let Some(theme) = theme {
+            app.set_theme(theme);
+        }
+        app.set_animations(animations);
+        run_app(app, &settings)
+    } else {
+        let mut app = GalanguaEcsApp::new(timer, system);
+        if let Some(theme) = theme {
+            app.set_theme(theme);
+        }
+        app.set_animations(animations);
+        run_app(app, &settings)
+    }
+}
+
+fn load_theme(name: &str) -> Result<Theme, String> {
+    let dir = format!("{}/{}", THEMES_DIR, name);
+    let path = format!("{}/{}", dir, THEME_FILE_NAME);
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| format!("{}: {}", path, e))?;
+    let mut theme = Theme::load(&dir, &text)
+        .ok_or_else(|| format!("{}: invalid theme", path))?;
+    for path in theme.remove_missing(|path| Path::new(path).exists()) {
+        println!("Missing file: {}, use built-in one", path);
+    }
+    Ok(theme)
+}
+
+// Clips in the sprite sheets of the manifest and the theme, the later ones replace the built-in ones.
+fn load_animations(theme: Option<&Theme>) -> AnimationTable {
+    let mut animations = builtin_animations();
+    let manifests = std::iter::once(asset_manifest()).chain(theme.map(|theme| theme.assets.clone()));
+    for manifest in manifests {
+        for sprite_sheet in manifest.sprite_sheets.iter() {
+            if let Ok(text) = std::fs::read_to_string(manifest.path(sprite_sheet)) {
+                animations.load(&text);
+            }
+        }
+    }
+    animations
+}
+
+// Uses the built-in manifest when the file is not found.
+fn load_manifest() -> Result<(), String> {
+    if let Ok(text) = std::fs::read_to_string(MANIFEST_PATH) {
+        let manifest = AssetManifest::load(&text)
+            .ok_or_else(|| format!("{}: invalid manifest", MANIFEST_PATH))?;
+        set_asset_manifest(manifest);
+    }
+    Ok(())
+}
+
+fn check_assets() -> Result<(), String> {
+    let manifest = asset_manifest();
+    let missing_files: Vec<String> = manifest.reloadable_files().iter()
+        .map(|filename| manifest.path(filename))
+        .filter(|path| !Path::new(path).exists())
+        .collect();
+    for path in missing_files.iter() {
+        println!("Missing file: {}", path);
+    }
+
+    let sprite_sheet_path = manifest.path(&manifest.sprite_sheets[0]);
+    let text = std::fs::read_to_string(&sprite_sheet_path)
+        .map_err(|e| format!("{}: {}", sprite_sheet_path, e))?;
+    let report = asset_check::check_assets(&text, |path| Path::new(&format!("{}.ogg", path)).exists());
+    for line in report.lines() {
+        println!("{}", line);
+    }
+    if report.is_ok() && missing_files.is_empty() {
+        println!("Assets OK");
+        Ok(())
     } else {
-        let app = GalanguaEcsApp::new(timer, system);
-        run_app(app, scale, fullscreen)
+        Err("Asset check failed".to_string())
     }
 }
 
@@ -0,0 +1,56 @@
+use std::fs;
+use std::time::SystemTime;
+
+use galangua_common::framework::asset_manifest::AssetManifest;
+
+const POLL_INTERVAL: u32 = 30;
+
+// Polls modification times of the asset files, for hot reloading.
+pub struct AssetWatcher {
+    base_path: String,
+    files: Vec<(String, Option<SystemTime>)>,
+    count: u32,
+}
+
+impl AssetWatcher {
+    pub fn new(manifest: &AssetManifest) -> Self {
+        let files = manifest.reloadable_files().into_iter()
+            .map(|filename| {
+                let modified = modified_time(&manifest.path(&filename));
+                (filename, modified)
+            })
+            .collect();
+        Self {
+            base_path: manifest.base_path.clone(),
+            files,
+            count: 0,
+        }
+    }
+
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    // Called every frame, returns filenames which are modified since the last check.
+    pub fn update(&mut self) -> Vec<String> {
+        self.count += 1;
+        if self.count < POLL_INTERVAL {
+            return Vec::new();
+        }
+        self.count = 0;
+
+        let mut changed = Vec::new();
+        for (filename, last_modified) in self.files.iter_mut() {
+            let modified = modified_time(&format!("{}/{}", self.base_path, filename));
+            if modified.is_some() && modified != *last_modified {
+                *last_modified = modified;
+                changed.push(filename.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
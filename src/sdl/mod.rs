@@ -1,9 +1,13 @@
+#[cfg(debug_assertions)]
+mod asset_watcher;
+mod orientation;
 mod resource_manager;
 mod sdl_app_framework;
 mod sdl_audio;
 mod sdl_renderer;
 mod sdl_texture_manager;
 
-pub use self::sdl_app_framework::SdlAppFramework;
+pub use self::orientation::Orientation;
+pub use self::sdl_app_framework::{ScreenSettings, SdlAppFramework};
 pub use self::sdl_audio::SdlAudio;
-pub use self::sdl_renderer::SdlRenderer;
+pub use self::sdl_renderer::{ScaleFilter, SdlRenderer};
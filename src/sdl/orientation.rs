@@ -0,0 +1,69 @@
+use galangua_common::framework::VKey;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    Normal,
+    Rotate90,   // Clockwise, for a landscape monitor mounted sideways.
+    Rotate270,
+}
+
+impl Orientation {
+    pub fn from_degree(degree: &str) -> Option<Self> {
+        match degree {
+            "0" => Some(Orientation::Normal),
+            "90" => Some(Orientation::Rotate90),
+            "270" => Some(Orientation::Rotate270),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Orientation::Normal => Orientation::Rotate90,
+            Orientation::Rotate90 => Orientation::Rotate270,
+            Orientation::Rotate270 => Orientation::Normal,
+        }
+    }
+
+    pub fn angle(self) -> f64 {
+        match self {
+            Orientation::Normal => 0.0,
+            Orientation::Rotate90 => 90.0,
+            Orientation::Rotate270 => 270.0,
+        }
+    }
+
+    pub fn is_rotated(self) -> bool {
+        self != Orientation::Normal
+    }
+
+    // Swap width and height when the screen is turned sideways.
+    pub fn rotate_size(self, size: (u32, u32)) -> (u32, u32) {
+        if self.is_rotated() { (size.1, size.0) } else { size }
+    }
+
+    // Map a direction on the physical monitor to the direction in the game screen.
+    pub fn remap_key(self, vkey: VKey) -> VKey {
+        match (self, vkey) {
+            (Orientation::Rotate90, VKey::Up) => VKey::Left,
+            (Orientation::Rotate90, VKey::Down) => VKey::Right,
+            (Orientation::Rotate90, VKey::Left) => VKey::Down,
+            (Orientation::Rotate90, VKey::Right) => VKey::Up,
+            (Orientation::Rotate270, VKey::Up) => VKey::Right,
+            (Orientation::Rotate270, VKey::Down) => VKey::Left,
+            (Orientation::Rotate270, VKey::Left) => VKey::Up,
+            (Orientation::Rotate270, VKey::Right) => VKey::Down,
+            _ => vkey,
+        }
+    }
+
+    pub fn remap_axis(self, axis_index: u8, dir: i8) -> (u8, i8) {
+        match (self, axis_index) {
+            (Orientation::Rotate90, 0) => (1, -dir),
+            (Orientation::Rotate90, 1) => (0, dir),
+            (Orientation::Rotate270, 0) => (1, dir),
+            (Orientation::Rotate270, 1) => (0, -dir),
+            _ => (axis_index, dir),
+        }
+    }
+}
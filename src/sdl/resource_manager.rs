@@ -12,16 +12,18 @@ impl<T> ResourceManager<T> {
         }
     }
 
+    // Returns replaced resources.
     pub fn load(
         &mut self, base_path: &str, filenames: &[&str], loader: impl Fn(&str) -> Result<T, String>,
-    ) -> Result<(), String> {
+    ) -> Result<Vec<T>, String> {
+        let mut replaced = Vec::new();
         for filename in filenames {
             let resource = loader(&format!("{base_path}/{filename}"))?;
             let key = Path::new(filename).file_stem().unwrap().to_str().unwrap();
-            self.map.insert(String::from(key), resource);
+            replaced.extend(self.map.insert(String::from(key), resource));
         }
 
-        Ok(())
+        Ok(replaced)
     }
 
     pub fn get(&self, key: &str) -> Option<&T> {
@@ -40,12 +42,17 @@ mod tests {
     #[test]
     fn test_resource_manager() {
         let mut resource_manager = ResourceManager::new();
-        assert_eq!(Ok(()), resource_manager.load(".", &vec!["key1.foo", "key2.bar"], |path| {
+        assert_eq!(Ok(vec![]), resource_manager.load(".", &vec!["key1.foo", "key2.bar"], |path| {
             Ok(String::from(path))
         }));
 
         assert_eq!(Some(&mut String::from("./key1.foo")), resource_manager.get_mut("key1"));
         assert_eq!(Some(&mut String::from("./key2.bar")), resource_manager.get_mut("key2"));
         assert_eq!(None, resource_manager.get_mut("non-exist-key"));
+
+        assert_eq!(Ok(vec![String::from("./key1.foo")]), resource_manager.load("..", &["key1.foo"], |path| {
+            Ok(String::from(path))
+        }));
+        assert_eq!(Some(&mut String::from("../key1.foo")), resource_manager.get_mut("key1"));
     }
 }
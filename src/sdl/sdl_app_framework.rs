@@ -4,27 +4,50 @@ use sdl2::joystick::Joystick;
 use sdl2::keyboard::Keycode;
 use sdl2::mixer::{AUDIO_S16LSB, DEFAULT_CHANNELS};
 use sdl2::Sdl;
+use std::collections::HashMap;
 use std::thread;
 use std::time::{Duration, SystemTime};
 
+use galangua_common::framework::color_profile::ColorProfile;
 use galangua_common::framework::{AppTrait, VKey};
 
-use crate::sdl::sdl_renderer::SdlRenderer;
+#[cfg(debug_assertions)]
+use galangua_common::framework::asset_manifest::AssetManifest;
+
+#[cfg(debug_assertions)]
+use crate::sdl::asset_watcher::AssetWatcher;
+use crate::sdl::orientation::Orientation;
+use crate::sdl::sdl_renderer::{ScaleFilter, SdlRenderer};
 
 type MapKeyFunc = fn(Keycode) -> Option<VKey>;
 
 const FPS: u32 = 60;
+const MAX_JOYSTICK_COUNT: usize = 2;
 const MIN_FPS: u32 = 15;
 
+pub struct ScreenSettings {
+    pub scale: u32,
+    pub fullscreen: bool,
+    pub filter: ScaleFilter,
+    pub orientation: Orientation,
+    pub crt: bool,
+    pub color_profile: ColorProfile,
+    pub outline_sprites: Vec<String>,
+}
+
 pub struct SdlAppFramework<App: AppTrait<SdlRenderer>> {
     sdl_context: Sdl,
     last_update_time: SystemTime,
 
     app: App,
     map_key: MapKeyFunc,
+    pressed_keys: HashMap<Keycode, VKey>,  // Mapped at key down, released as is.
+    joystick_ids: Vec<u32>,  // Instance ids, ordered by joystick index.
 
     #[cfg(debug_assertions)]
     fast_forward: bool,
+    #[cfg(debug_assertions)]
+    asset_watcher: Option<AssetWatcher>,
 }
 
 impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
@@ -36,21 +59,33 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
             last_update_time: SystemTime::now(),
             app,
             map_key,
+            pressed_keys: HashMap::new(),
+            joystick_ids: Vec::new(),
 
             #[cfg(debug_assertions)]
             fast_forward: false,
+            #[cfg(debug_assertions)]
+            asset_watcher: None,
         })
     }
 
-    pub fn run(&mut self, title: &str, width: u32, height: u32, scale: u32, fullscreen: bool) -> Result<(), String> {
+    // Reloads textures and sprite sheets in the manifest when they are modified on disk.
+    #[cfg(debug_assertions)]
+    pub fn watch_assets(&mut self, manifest: &AssetManifest) {
+        self.asset_watcher = Some(AssetWatcher::new(manifest));
+    }
+
+    pub fn run(&mut self, title: &str, width: u32, height: u32, settings: &ScreenSettings) -> Result<(), String> {
         let video_subsystem = self.sdl_context.video()?;
         let _image_context = sdl2::image::init(InitFlag::PNG | InitFlag::JPG)?;
 
-        let _joystick = self.set_up_joystick()?;
+        let _joysticks = self.set_up_joysticks()?;
 
+        let scale = settings.scale;
+        let (window_width, window_height) = settings.orientation.rotate_size((width, height));
         let mut window_builder = video_subsystem
-            .window(title, width * scale, height * scale);
-        if fullscreen {
+            .window(title, window_width * scale, window_height * scale);
+        if settings.fullscreen {
             window_builder.fullscreen();
         } else {
             window_builder
@@ -61,7 +96,7 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
             .opengl()
             .build()
             .map_err(|e| e.to_string())?;
-        if fullscreen {
+        if settings.fullscreen {
             self.sdl_context.mouse().show_cursor(false);
         }
 
@@ -86,17 +121,23 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
         // simultaneously.
         sdl2::mixer::allocate_channels(4);
 
-        let mut renderer = SdlRenderer::new(canvas, (width, height));
+        let mut renderer = SdlRenderer::new(canvas, (width, height), settings.filter, settings.orientation);
+        renderer.set_crt(settings.crt);
+        renderer.set_color_profile(settings.color_profile);
 
         self.app.init(&mut renderer);
+        renderer.set_outline_sprites(&settings.outline_sprites);
 
         self.last_update_time = SystemTime::now();
         let mut skip_count = 0;
         'running: loop {
-            if !self.pump_events()? {
+            if !self.pump_events(&mut renderer)? {
                 break 'running;
             }
 
+            #[cfg(debug_assertions)]
+            self.reload_assets(&mut renderer);
+
             #[cfg(debug_assertions)]
             let step = if self.fast_forward { 10 } else { 1 + skip_count };
             #[cfg(not(debug_assertions))]
@@ -110,24 +151,39 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
             self.app.draw(&mut renderer);
             renderer.present();
 
-            skip_count = self.wait_frame(Duration::from_micros(1_000_000 / FPS as u64));
+            let frame_micros = 1_000_000 * 100 / (FPS * self.app.game_speed().max(1)) as u64;
+            skip_count = self.wait_frame(Duration::from_micros(frame_micros));
         }
         Ok(())
     }
 
-    pub fn pump_events(&mut self) -> Result<bool, String> {
+    pub fn pump_events(&mut self, renderer: &mut SdlRenderer) -> Result<bool, String> {
         let mut event_pump = self.sdl_context.event_pump()?;
         for event in event_pump.poll_iter() {
             match event {
                 Event::Quit { .. } => {
                     return Ok(false);
                 }
+                Event::KeyDown { keycode: Some(Keycode::F8), repeat: false, .. } => {
+                    renderer.set_color_profile(renderer.color_profile().next());
+                }
+                Event::KeyDown { keycode: Some(Keycode::F9), repeat: false, .. } => {
+                    renderer.set_filter(renderer.filter().next());
+                }
+                Event::KeyDown { keycode: Some(Keycode::F10), repeat: false, .. } => {
+                    renderer.set_orientation(renderer.orientation().next());
+                }
+                Event::KeyDown { keycode: Some(Keycode::F11), repeat: false, .. } => {
+                    renderer.set_crt(!renderer.is_crt());
+                }
                 Event::KeyDown { keycode: Some(key), .. } => {
                     #[cfg(debug_assertions)]
                     if key == Keycode::LShift {
                         self.fast_forward = true;
                     }
                     if let Some(vkey) = (self.map_key)(key) {
+                        let vkey = renderer.orientation().remap_key(vkey);
+                        self.pressed_keys.insert(key, vkey);
                         self.app.on_key(vkey, true);
                     }
                 }
@@ -136,19 +192,26 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
                     if key == Keycode::LShift {
                         self.fast_forward = false;
                     }
-                    if let Some(vkey) = (self.map_key)(key) {
+                    if let Some(vkey) = self.pressed_keys.remove(&key) {
                         self.app.on_key(vkey, false);
                     }
                 }
-                Event::JoyAxisMotion { axis_idx, value, .. } => {
-                    let dir = if value > 10_000 { 1 } else if value < -10_000 { -1 } else { 0 };
-                    self.app.on_joystick_axis(axis_idx, dir);
+                Event::JoyAxisMotion { which, axis_idx, value, .. } => {
+                    if let Some(index) = self.joystick_index(which) {
+                        let dir = if value > 10_000 { 1 } else if value < -10_000 { -1 } else { 0 };
+                        let (axis_idx, dir) = renderer.orientation().remap_axis(axis_idx, dir);
+                        self.app.on_joystick_axis(index, axis_idx, dir);
+                    }
                 }
-                Event::JoyButtonDown { button_idx, .. } => {
-                    self.app.on_joystick_button(button_idx, true);
+                Event::JoyButtonDown { which, button_idx, .. } => {
+                    if let Some(index) = self.joystick_index(which) {
+                        self.app.on_joystick_button(index, button_idx, true);
+                    }
                 }
-                Event::JoyButtonUp { button_idx, .. } => {
-                    self.app.on_joystick_button(button_idx, false);
+                Event::JoyButtonUp { which, button_idx, .. } => {
+                    if let Some(index) = self.joystick_index(which) {
+                        self.app.on_joystick_button(index, button_idx, false);
+                    }
                 }
                 _ => {}
             }
@@ -177,15 +240,34 @@ impl<App: AppTrait<SdlRenderer>> SdlAppFramework<App> {
         }
     }
 
-    fn set_up_joystick(&mut self) -> Result<Option<Joystick>, String> {
+    #[cfg(debug_assertions)]
+    fn reload_assets(&mut self, renderer: &mut SdlRenderer) {
+        if let Some(watcher) = self.asset_watcher.as_mut() {
+            for filename in watcher.update() {
+                match renderer.reload_asset(watcher.base_path(), &filename) {
+                    Ok(()) => println!("Reloaded: {filename}"),
+                    Err(err) => println!("{err}: {filename}"),
+                }
+            }
+        }
+    }
+
+    fn set_up_joysticks(&mut self) -> Result<Vec<Joystick>, String> {
         let joystick_subsystem = self.sdl_context.joystick()?;
         let available = joystick_subsystem
             .num_joysticks()
             .map_err(|e| format!("can't enumerate joysticks: {e}"))?;
-        let joystick = (0..available).find_map(|id| match joystick_subsystem.open(id) {
-            Ok(c) => Some(c),
-            Err(_e) => None,
-        });
-        Ok(joystick)
+        let joysticks: Vec<Joystick> = (0..available)
+            .filter_map(|id| joystick_subsystem.open(id).ok())
+            .take(MAX_JOYSTICK_COUNT)
+            .collect();
+        self.joystick_ids = joysticks.iter().map(|joystick| joystick.instance_id()).collect();
+        Ok(joysticks)
+    }
+
+    fn joystick_index(&self, instance_id: u32) -> Option<u8> {
+        self.joystick_ids.iter()
+            .position(|&id| id == instance_id)
+            .map(|index| index as u8)
     }
 }
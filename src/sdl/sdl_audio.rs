@@ -1,8 +1,10 @@
 use sdl2::mixer::{Chunk, MAX_VOLUME};
+use std::collections::HashMap;
 
 pub struct SdlAudio {
     channels: Vec<Option<Chunk>>,
     base_volume: i32,
+    overrides: HashMap<String, String>,
 }
 
 impl SdlAudio {
@@ -13,11 +15,18 @@ impl SdlAudio {
         Self {
             channels,
             base_volume: (MAX_VOLUME as f32 * base_volume) as i32,
+            overrides: HashMap::new(),
         }
     }
 
+    // Plays `to` instead of `from`, for themes.
+    pub fn set_override(&mut self, from: String, to: String) {
+        self.overrides.insert(from, to);
+    }
+
     pub fn play_se(&mut self, channel: u32, filename: &str) {
         if channel < self.channels.len() as u32 {
+            let filename = self.overrides.get(filename).map_or(filename, |s| s.as_str());
             let path = format!("{filename}.ogg");
             match Chunk::from_file(path) {
                 Err(err) => println!("{err}: {filename}"),
@@ -1,62 +1,373 @@
-use sdl2::pixels::Color;
+use sdl2::image::LoadSurface;
+use sdl2::pixels::{Color, PixelFormatEnum};
 use sdl2::rect::{Point, Rect};
-use sdl2::render::WindowCanvas;
+use sdl2::render::{BlendMode, ScaleMode, Texture, WindowCanvas};
+use sdl2::surface::Surface;
 use std::collections::HashMap;
 
+use galangua_common::framework::color_profile::ColorProfile;
+use galangua_common::framework::crt_filter::{CrtFilter, CRT_SCALE};
+use galangua_common::framework::software_renderer::{Image, SoftwareRenderer};
 use galangua_common::framework::sprite_sheet::SpriteSheet;
 use galangua_common::framework::types::Vec2I;
 use galangua_common::framework::RendererTrait;
 
+use super::orientation::Orientation;
 use super::sdl_texture_manager::SdlTextureManager;
 
+const SCANLINE_ALPHA: u8 = 96;
+const OUTLINE_COLOR: (u8, u8, u8) = (255, 255, 0);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ScaleFilter {
+    Nearest,   // Integer scale, sharp pixels.
+    Linear,    // Fit to the window keeping aspect ratio, smoothed.
+    Scanline,  // Integer scale with darkened lines between rows.
+}
+
+impl ScaleFilter {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "nearest" => Some(ScaleFilter::Nearest),
+            "linear" => Some(ScaleFilter::Linear),
+            "scanline" => Some(ScaleFilter::Scanline),
+            _ => None,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ScaleFilter::Nearest => ScaleFilter::Linear,
+            ScaleFilter::Linear => ScaleFilter::Scanline,
+            ScaleFilter::Scanline => ScaleFilter::Nearest,
+        }
+    }
+
+    fn scale_mode(self) -> ScaleMode {
+        match self {
+            ScaleFilter::Linear => ScaleMode::Linear,
+            _ => ScaleMode::Nearest,
+        }
+    }
+}
+
 pub struct SdlRenderer {
     canvas: WindowCanvas,
     texture_manager: SdlTextureManager,
     sprite_sheet: SpriteSheet,
     tex_color_map: HashMap<String, (u8, u8, u8)>,
+    loaded_textures: Vec<(String, String)>,
+    color_profile: ColorProfile,
+    outline_sprites: Vec<String>,
+    outlines: HashMap<String, Texture>,
+    logical_size: (u32, u32),
+    screen: Texture,
+    filter: ScaleFilter,
+    orientation: Orientation,
+
+    // Software path for CRT effect, used instead of the canvas while `crt` is Some.
+    // Textures are loaded into it only while CRT or outlines need them.
+    soft: SoftwareRenderer,
+    soft_loaded: bool,
+    crt: Option<CrtFilter>,
+    crt_texture: Texture,
 }
 
 impl SdlRenderer {
-    pub fn new(mut canvas: WindowCanvas, logical_size: (u32, u32)) -> Self {
-        canvas.set_logical_size(logical_size.0, logical_size.1)
-            .expect("set_logical_size failed");
+    pub fn new(canvas: WindowCanvas, logical_size: (u32, u32), filter: ScaleFilter,
+               orientation: Orientation) -> Self {
+        let mut screen = canvas.texture_creator()
+            .create_texture_target(None, logical_size.0, logical_size.1)
+            .expect("create_texture_target failed");
+        screen.set_scale_mode(filter.scale_mode());
+        let mut crt_texture = canvas.texture_creator()
+            .create_texture_streaming(PixelFormatEnum::RGBA32,
+                                      logical_size.0 * CRT_SCALE, logical_size.1 * CRT_SCALE)
+            .expect("create_texture_streaming failed");
+        crt_texture.set_scale_mode(ScaleMode::Linear);
 
-        Self {
+        let mut renderer = Self {
             canvas,
             texture_manager: SdlTextureManager::new(),
             sprite_sheet: SpriteSheet::default(),
             tex_color_map: HashMap::new(),
+            loaded_textures: Vec::new(),
+            color_profile: ColorProfile::Normal,
+            outline_sprites: Vec::new(),
+            outlines: HashMap::new(),
+            logical_size,
+            screen,
+            filter,
+            orientation,
+            soft: SoftwareRenderer::new(logical_size.0, logical_size.1, Box::new(load_image)),
+            soft_loaded: false,
+            crt: None,
+            crt_texture,
+        };
+        renderer.set_render_target(true);
+        renderer
+    }
+
+    pub fn filter(&self) -> ScaleFilter {
+        self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: ScaleFilter) {
+        self.filter = filter;
+        self.screen.set_scale_mode(filter.scale_mode());
+    }
+
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    pub fn is_crt(&self) -> bool {
+        self.crt.is_some()
+    }
+
+    pub fn set_crt(&mut self, enable: bool) {
+        self.crt = if enable {
+            Some(CrtFilter::new(self.logical_size.0, self.logical_size.1))
+        } else {
+            None
+        };
+        self.sync_soft_textures();
+    }
+
+    pub fn color_profile(&self) -> ColorProfile {
+        self.color_profile
+    }
+
+    // Reloads textures to apply the profile.
+    pub fn set_color_profile(&mut self, color_profile: ColorProfile) {
+        self.color_profile = color_profile;
+        self.soft.set_color_profile(color_profile);
+        for (base_path, filename) in self.loaded_textures.iter() {
+            self.texture_manager.load(&mut self.canvas, base_path, &[filename], color_profile)
+                .expect("load_textures failed");
+            if self.soft_loaded {
+                self.soft.load_texture(base_path, filename)
+                    .expect("load_textures failed");
+            }
+        }
+        let color_mods: Vec<_> = self.tex_color_map.drain().collect();
+        for (tex_name, (r, g, b)) in color_mods {
+            self.set_texture_color_mod(&tex_name, r, g, b);
+        }
+        self.update_outlines();
+    }
+
+    // Draws outlines around the sprites, for visibility.
+    pub fn set_outline_sprites(&mut self, sprite_names: &[String]) {
+        self.outline_sprites = sprite_names.to_vec();
+        self.sync_soft_textures();
+        self.update_outlines();
+    }
+
+    // Loads textures into the software renderer when they are needed, releases them otherwise.
+    fn sync_soft_textures(&mut self) {
+        let needed = self.crt.is_some() || !self.outline_sprites.is_empty();
+        if needed == self.soft_loaded {
+            return;
+        }
+        if needed {
+            for (base_path, filename) in self.loaded_textures.iter() {
+                self.soft.load_texture(base_path, filename)
+                    .expect("load_textures failed");
+            }
+            for (tex_name, &(r, g, b)) in self.tex_color_map.iter() {
+                self.soft.set_texture_color_mod(tex_name, r, g, b);
+            }
+        } else {
+            self.soft.clear_textures();
+        }
+        self.soft_loaded = needed;
+    }
+
+    fn update_outlines(&mut self) {
+        self.soft.set_outline_sprites(&self.outline_sprites, OUTLINE_COLOR);
+        for (_, texture) in self.outlines.drain() {
+            // Not freed on drop with `unsafe_textures`.
+            unsafe { texture.destroy(); }
+        }
+        let texture_creator = self.canvas.texture_creator();
+        for sprite_name in self.outline_sprites.iter() {
+            if let Some(image) = self.soft.outline_image(sprite_name) {
+                let mut texture = texture_creator
+                    .create_texture_static(PixelFormatEnum::RGBA32, image.width, image.height)
+                    .expect("create_texture_static failed");
+                texture.update(None, &image.pixels, (image.width * 4) as usize)
+                    .expect("texture update failed");
+                texture.set_blend_mode(BlendMode::Blend);
+                self.outlines.insert(sprite_name.clone(), texture);
+            }
+        }
+    }
+
+    // Reloads a texture or a sprite sheet, keeps the current one if it fails.
+    #[cfg(debug_assertions)]
+    pub fn reload_asset(&mut self, base_path: &str, filename: &str) -> Result<(), String> {
+        use std::path::Path;
+
+        let path = format!("{base_path}/{filename}");
+        match Path::new(filename).extension().and_then(|ext| ext.to_str()) {
+            Some("png") => {
+                self.texture_manager.load(&mut self.canvas, base_path, &[filename], self.color_profile)?;
+                if self.soft_loaded {
+                    self.soft.load_texture(base_path, filename)?;
+                }
+                let key = Path::new(filename).file_stem().unwrap().to_str().unwrap();
+                self.tex_color_map.remove(key);
+                self.update_outlines();
+                Ok(())
+            }
+            Some("json") => {
+                let text = std::fs::read_to_string(&path).map_err(|e| e.to_string())?;
+                if !self.sprite_sheet.load_sprite_sheet(&text) {
+                    return Err(String::from("invalid sprite sheet"));
+                }
+                self.soft.load_sprite_sheet(&path);
+                self.update_outlines();
+                Ok(())
+            }
+            _ => Err(String::from("unknown asset type")),
         }
     }
 
     pub fn present(&mut self) {
+        self.set_render_target(false);
+        if let Some(crt) = &mut self.crt {
+            let output = crt.apply(self.soft.framebuffer());
+            self.crt_texture.update(None, &output.pixels, (output.width * 4) as usize)
+                .expect("texture update failed");
+        }
+
+        let (r, g, b) = self.canvas.draw_color().rgb();
+        self.canvas.set_draw_color(Color::RGB(0, 0, 0));
+        self.canvas.clear();
+
+        let (dst, scale) = self.calc_screen_rect();
+        let texture = if self.crt.is_some() { &self.crt_texture } else { &self.screen };
+        self.canvas.copy_ex(texture, None, Some(dst),
+                            self.orientation.angle(), None, false, false)
+            .expect("copy_ex failed");
+        if self.filter == ScaleFilter::Scanline && scale >= 2 && self.crt.is_none() {
+            self.draw_scanlines(&dst, scale);
+        }
         self.canvas.present();
+
+        self.canvas.set_draw_color(Color::RGB(r, g, b));
+        self.set_render_target(true);
+    }
+
+    // Largest integer scale which fits in the window, centered with black borders.
+    // Falls back to fractional scaling when the window is smaller than the screen.
+    // Returned rect is before rotation, copy_ex rotates it around its center.
+    fn calc_screen_rect(&self) -> (Rect, u32) {
+        let (w, h) = self.orientation.rotate_size(self.logical_size);
+        let (ow, oh) = self.canvas.output_size().expect("output_size failed");
+        let scale = std::cmp::min(ow / w, oh / h);
+        let (dw, dh) = if self.filter != ScaleFilter::Linear && scale >= 1 {
+            (w * scale, h * scale)
+        } else if ow * h < oh * w {
+            (ow, h * ow / w)
+        } else {
+            (w * oh / h, oh)
+        };
+        let (dw, dh) = self.orientation.rotate_size((dw, dh));
+        let x = (ow as i32 - dw as i32) / 2;
+        let y = (oh as i32 - dh as i32) / 2;
+        (Rect::new(x, y, dw, dh), scale)
+    }
+
+    fn draw_scanlines(&mut self, dst: &Rect, scale: u32) {
+        let thickness = scale / 3 + 1;
+        let (w, h) = (dst.width(), dst.height());
+        let left = dst.center().x() - h as i32 / 2;
+        let right = left + h as i32;
+        let rects: Vec<Rect> = (0..self.logical_size.1)
+            .map(|i| {
+                let offset = ((i + 1) * scale) as i32;
+                match self.orientation {
+                    Orientation::Normal => Rect::new(dst.x(), dst.y() + offset - thickness as i32, w, thickness),
+                    Orientation::Rotate90 => Rect::new(right - offset, dst.center().y() - w as i32 / 2, thickness, w),
+                    Orientation::Rotate270 => Rect::new(left + offset - thickness as i32, dst.center().y() - w as i32 / 2, thickness, w),
+                }
+            })
+            .collect();
+        self.canvas.set_blend_mode(BlendMode::Blend);
+        self.canvas.set_draw_color(Color::RGBA(0, 0, 0, SCANLINE_ALPHA));
+        self.canvas.fill_rects(&rects)
+            .expect("fill_rects failed");
+        self.canvas.set_blend_mode(BlendMode::None);
     }
+
+    fn set_render_target(&mut self, offscreen: bool) {
+        let texture = if offscreen { self.screen.raw() } else { std::ptr::null_mut() };
+        let result = unsafe { sdl2::sys::SDL_SetRenderTarget(self.canvas.raw(), texture) };
+        if result != 0 {
+            panic!("SDL_SetRenderTarget failed: {}", sdl2::get_error());
+        }
+    }
+}
+
+fn load_image(path: &str) -> Result<Image, String> {
+    let surface = Surface::from_file(path)?
+        .convert_format(PixelFormatEnum::RGBA32)?;
+    let (width, height) = (surface.width(), surface.height());
+    let pitch = surface.pitch() as usize;
+    let mut image = Image::new(width, height);
+    surface.with_lock(|pixels| {
+        let line = (width * 4) as usize;
+        for (y, dst) in image.pixels.chunks_exact_mut(line).enumerate() {
+            dst.copy_from_slice(&pixels[y * pitch..y * pitch + line]);
+        }
+    });
+    Ok(image)
 }
 
 impl RendererTrait for SdlRenderer {
     fn load_textures(&mut self, base_path: &str, filenames: &[&str]) {
-        self.texture_manager.load(&mut self.canvas, base_path, filenames)
+        self.texture_manager.load(&mut self.canvas, base_path, filenames, self.color_profile)
             .expect("load_textures failed");
+        if self.soft_loaded {
+            self.soft.load_textures(base_path, filenames);
+        }
+        for &filename in filenames {
+            let entry = (base_path.to_string(), filename.to_string());
+            if !self.loaded_textures.contains(&entry) {
+                self.loaded_textures.push(entry);
+            }
+        }
     }
 
     fn load_sprite_sheet(&mut self, filename: &str) {
         let text = std::fs::read_to_string(filename)
             .expect("load sprite sheet failed");
         self.sprite_sheet.load_sprite_sheet(&text);
+        self.soft.load_sprite_sheet(filename);
     }
 
     fn clear(&mut self) {
+        if self.crt.is_some() {
+            return self.soft.clear();
+        }
         self.canvas.clear();
     }
 
     fn set_texture_color_mod(&mut self, tex_name: &str, r: u8, g: u8, b: u8) {
+        self.soft.set_texture_color_mod(tex_name, r, g, b);
         let color = (r, g, b);
         if self.tex_color_map.contains_key(tex_name) && self.tex_color_map[tex_name] == color {
             return;
         }
         self.tex_color_map.insert(tex_name.to_string(), color);
 
+        // Color profile is already applied to the pixels.
         if let Some(texture) = self.texture_manager.get_mut(tex_name) {
             texture.set_color_mod(r, g, b);
         }
@@ -64,19 +375,15 @@ impl RendererTrait for SdlRenderer {
 
     fn set_sprite_texture_color_mod(&mut self, sprite_name: &str, r: u8, g: u8, b: u8) {
         if let Some((_sheet, tex_name)) = self.sprite_sheet.get(sprite_name) {
-            let color = (r, g, b);
-            if self.tex_color_map.contains_key(tex_name) && self.tex_color_map[tex_name] == color {
-                return;
-            }
-            self.tex_color_map.insert(tex_name.to_string(), color);
-
-            if let Some(texture) = self.texture_manager.get_mut(tex_name) {
-                texture.set_color_mod(r, g, b);
-            }
+            let tex_name = tex_name.to_string();
+            self.set_texture_color_mod(&tex_name, r, g, b);
         }
     }
 
     fn draw_str(&mut self, tex_name: &str, x: i32, y: i32, text: &str) {
+        if self.crt.is_some() {
+            return self.soft.draw_str(tex_name, x, y, text);
+        }
         let texture = self.texture_manager.get_mut(tex_name)
             .expect("No texture");
         let w = 8;
@@ -95,10 +402,19 @@ impl RendererTrait for SdlRenderer {
     }
 
     fn draw_sprite(&mut self, sprite_name: &str, pos: &Vec2I) {
+        if self.crt.is_some() {
+            return self.soft.draw_sprite(sprite_name, pos);
+        }
         let (sheet, tex_name) = self.sprite_sheet.get(sprite_name)
             .expect("No sprite");
         let pos = sheet.trim_pos(pos);
 
+        if let Some(outline) = self.outlines.get(sprite_name) {
+            self.canvas.copy(outline, None,
+                             Some(Rect::new(pos.x - 1, pos.y - 1,
+                                            sheet.frame.w + 2, sheet.frame.h + 2)))
+                .expect("copy failed");
+        }
         let texture = self.texture_manager.get(tex_name)
             .expect("No texture");
         self.canvas.copy(texture,
@@ -112,13 +428,23 @@ impl RendererTrait for SdlRenderer {
 
     fn draw_sprite_rot(&mut self, sprite_name: &str, pos: &Vec2I, angle: u8,
                        center: Option<&Vec2I>) {
+        if self.crt.is_some() {
+            return self.soft.draw_sprite_rot(sprite_name, pos, angle, center);
+        }
         let (sheet, tex_name) = self.sprite_sheet.get(sprite_name)
             .expect("No sprite");
         let pos = sheet.trim_pos(pos);
 
+        let center = center.map(|v| Point::new(v.x, v.y));
+        if let Some(outline) = self.outlines.get(sprite_name) {
+            self.canvas.copy_ex(outline, None,
+                                Some(Rect::new(pos.x - 1, pos.y - 1,
+                                               sheet.frame.w + 2, sheet.frame.h + 2)),
+                                (angle as f64) * (360.0 / 256.0), center.map(|c| c.offset(1, 1)), false, false)
+                .expect("copy_ex failed");
+        }
         let texture = self.texture_manager.get(tex_name)
             .expect("No texture");
-        let center = center.map(|v| Point::new(v.x, v.y));
         self.canvas.copy_ex(texture,
                             Some(Rect::new(sheet.frame.x, sheet.frame.y,
                                            sheet.frame.w, sheet.frame.h)),
@@ -130,10 +456,15 @@ impl RendererTrait for SdlRenderer {
     }
 
     fn set_draw_color(&mut self, r: u8, g: u8, b: u8) {
+        self.soft.set_draw_color(r, g, b);
+        let (r, g, b) = self.color_profile.map_color(r, g, b);
         self.canvas.set_draw_color(Color::RGB(r, g, b));
     }
 
     fn fill_rect(&mut self, dst: Option<[&Vec2I; 2]>) {
+        if self.crt.is_some() {
+            return self.soft.fill_rect(dst);
+        }
         if let Some(rect) = dst {
             self.canvas.fill_rect(Some(Rect::new(rect[0].x, rect[0].y,
                                                  rect[1].x as u32, rect[1].y as u32)))
@@ -1,5 +1,9 @@
-use sdl2::image::LoadTexture;
+use sdl2::image::{LoadSurface, LoadTexture};
+use sdl2::pixels::PixelFormatEnum;
 use sdl2::render::{Texture, WindowCanvas};
+use sdl2::surface::Surface;
+
+use galangua_common::framework::color_profile::ColorProfile;
 
 use super::resource_manager::ResourceManager;
 
@@ -14,12 +18,20 @@ impl SdlTextureManager {
         }
     }
 
+    // Replaces textures which are already loaded.
     pub fn load(&mut self, canvas: &mut WindowCanvas, base_path: &str,
-                filenames: &[&str]) -> Result<(), String> {
-        self.resource_manager.load(base_path, filenames, |path: &str| {
-            let texture_creator = canvas.texture_creator();
-            texture_creator.load_texture(path)
-        })
+                filenames: &[&str], color_profile: ColorProfile) -> Result<(), String> {
+        // One by one, not to lose replaced ones when loading fails.
+        for filename in filenames {
+            let replaced = self.resource_manager.load(base_path, &[filename], |path| {
+                load_texture(canvas, path, color_profile)
+            })?;
+            for texture in replaced {
+                // Not freed on drop with `unsafe_textures`.
+                unsafe { texture.destroy(); }
+            }
+        }
+        Ok(())
     }
 
     pub fn get(&self, key: &str) -> Option<&Texture> {
@@ -30,3 +42,26 @@ impl SdlTextureManager {
         self.resource_manager.get_mut(key)
     }
 }
+
+// Color profile is applied to the pixels, color mods are kept as is.
+fn load_texture(canvas: &WindowCanvas, path: &str, color_profile: ColorProfile) -> Result<Texture, String> {
+    let texture_creator = canvas.texture_creator();
+    if color_profile == ColorProfile::Normal {
+        return texture_creator.load_texture(path);
+    }
+
+    let mut surface = Surface::from_file(path)?
+        .convert_format(PixelFormatEnum::RGBA32)?;
+    let line = (surface.width() * 4) as usize;
+    let pitch = surface.pitch() as usize;
+    surface.with_lock_mut(|pixels| {
+        for row in pixels.chunks_mut(pitch) {
+            for p in row[..line].chunks_exact_mut(4) {
+                let (r, g, b) = color_profile.map_color(p[0], p[1], p[2]);
+                p[..3].copy_from_slice(&[r, g, b]);
+            }
+        }
+    });
+    texture_creator.create_texture_from_surface(&surface)
+        .map_err(|e| e.to_string())
+}
@@ -5,6 +5,7 @@ use std::io::Write;
 
 use super::sdl::SdlAudio;
 
+use galangua_common::framework::storage::Blob;
 use galangua_common::framework::SystemTrait;
 
 const SAVE_FILE_NAME: &str = ".savedata.json";
@@ -21,6 +22,23 @@ impl StdSystem {
             audio,
         }
     }
+
+    pub fn audio_mut(&mut self) -> &mut SdlAudio {
+        &mut self.audio
+    }
+
+    pub fn get_str(&self, key: &str) -> Option<&str> {
+        self.map.get(key).and_then(|value| value.as_str())
+    }
+
+    pub fn set_str(&mut self, key: &str, value: Option<&str>) {
+        if let Some(value) = value {
+            self.map.insert(String::from(key), Value::String(String::from(value)));
+        } else {
+            self.map.remove(key);
+        }
+        save_map(SAVE_FILE_NAME, &self.map);
+    }
 }
 
 impl SystemTrait for StdSystem {
@@ -36,6 +54,15 @@ impl SystemTrait for StdSystem {
         save_map(SAVE_FILE_NAME, &self.map);
     }
 
+    fn get_blob(&self, namespace: &str) -> Option<Blob> {
+        self.map.get(namespace).and_then(Blob::from_value)
+    }
+
+    fn set_blob(&mut self, namespace: &str, blob: &Blob) {
+        self.map.insert(String::from(namespace), blob.to_value());
+        save_map(SAVE_FILE_NAME, &self.map);
+    }
+
     fn is_touch_device(&self) -> bool { false }
 
     fn play_se(&mut self, channel: u32, filename: &str) {
@@ -0,0 +1,75 @@
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,  // RGBA
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height, pixels: vec![0; (width * height * 4) as usize] }
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> &[u8] {
+        let i = ((y * self.width + x) * 4) as usize;
+        &self.pixels[i..i + 4]
+    }
+}
+
+// Reads a PNG file, converted into 8bit RGBA.
+pub fn load(path: &Path) -> Result<Image, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut decoder = png::Decoder::new(file);
+    decoder.set_transformations(png::Transformations::normalize_to_color8() | png::Transformations::ALPHA);
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut buf = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut buf).map_err(|e| e.to_string())?;
+    buf.truncate(info.buffer_size());
+
+    let pixels = match info.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::GrayscaleAlpha => buf.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+        color_type => return Err(format!("Unsupported color type: {:?}", color_type)),
+    };
+    Ok(Image { width: info.width, height: info.height, pixels })
+}
+
+// Writes a PNG file, indexed if the image has no more than 256 colors to keep the file small.
+pub fn save(path: &Path, image: &Image) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width, image.height);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Best);
+    let data = if let Some((palette, indices)) = make_palette(image) {
+        encoder.set_color(png::ColorType::Indexed);
+        encoder.set_palette(palette.iter().flat_map(|c| [c[0], c[1], c[2]]).collect::<Vec<u8>>());
+        encoder.set_trns(palette.iter().map(|c| c[3]).collect::<Vec<u8>>());
+        indices
+    } else {
+        encoder.set_color(png::ColorType::Rgba);
+        image.pixels.clone()
+    };
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&data).map_err(|e| e.to_string())
+}
+
+fn make_palette(image: &Image) -> Option<(Vec<[u8; 4]>, Vec<u8>)> {
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut indices = Vec::with_capacity(image.pixels.len() / 4);
+    for p in image.pixels.chunks_exact(4) {
+        let color = [p[0], p[1], p[2], p[3]];
+        let index = match palette.iter().position(|c| *c == color) {
+            Some(index) => index,
+            None if palette.len() < 256 => {
+                palette.push(color);
+                palette.len() - 1
+            }
+            None => return None,
+        };
+        indices.push(index as u8);
+    }
+    Some((palette, indices))
+}
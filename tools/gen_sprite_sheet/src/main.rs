@@ -0,0 +1,161 @@
+// Packs img/chr/*.png into assets/chr.{png,json}.
+// Run in this directory: `cargo run`, or `cargo run -- --check` to verify the committed atlas is up to date.
+
+mod image;
+mod packer;
+
+use serde_json::Value;
+use std::fs;
+use std::path::Path;
+
+use image::Image;
+use packer::Rect;
+
+const NAME: &str = "chr";
+const IMG_DIR: &str = "img/chr";
+const ANIMATIONS_FILE: &str = "img/chr_animations.json";
+const OUTPUT_DIR: &str = "../../assets";
+
+struct Sprite {
+    filename: String,
+    image: Image,
+    trimmed: Rect,  // Opaque area in the source image.
+}
+
+fn main() {
+    let check = std::env::args().skip(1).any(|arg| arg == "--check");
+    if let Err(msg) = run(check) {
+        eprintln!("{}", msg);
+        std::process::exit(1);
+    }
+}
+
+fn run(check: bool) -> Result<(), String> {
+    let sprites = load_sprites(IMG_DIR)?;
+    let animations = fs::read_to_string(ANIMATIONS_FILE)
+        .map_err(|e| format!("{}: {}", ANIMATIONS_FILE, e))?;
+    let animations: Value = serde_json::from_str(&animations)
+        .map_err(|e| format!("{}: {}", ANIMATIONS_FILE, e))?;
+
+    let sizes: Vec<(u32, u32)> = sprites.iter().map(|s| (s.trimmed.w, s.trimmed.h)).collect();
+    let (rects, (width, height)) = packer::pack(&sizes);
+
+    let mut atlas = Image::new(width, height);
+    for (sprite, rect) in sprites.iter().zip(rects.iter()) {
+        blit(&mut atlas, rect, &sprite.image, &sprite.trimmed);
+    }
+    let json = format_json(&sprites, &rects, (width, height), &animations);
+
+    let json_path = format!("{}/{}.json", OUTPUT_DIR, NAME);
+    let png_path = format!("{}/{}.png", OUTPUT_DIR, NAME);
+    if check {
+        let current_json = fs::read_to_string(&json_path).map_err(|e| format!("{}: {}", json_path, e))?;
+        let current_png = image::load(Path::new(&png_path)).map_err(|e| format!("{}: {}", png_path, e))?;
+        if current_json != json {
+            return Err(format!("{} is stale, run gen_sprite_sheet", json_path));
+        }
+        if (current_png.width, current_png.height) != (width, height) || current_png.pixels != atlas.pixels {
+            return Err(format!("{} is stale, run gen_sprite_sheet", png_path));
+        }
+        println!("{} is up to date", NAME);
+    } else {
+        fs::write(&json_path, json).map_err(|e| format!("{}: {}", json_path, e))?;
+        image::save(Path::new(&png_path), &atlas).map_err(|e| format!("{}: {}", png_path, e))?;
+        println!("Wrote {} sprites into {}x{} atlas", sprites.len(), width, height);
+    }
+    Ok(())
+}
+
+fn load_sprites(dir: &str) -> Result<Vec<Sprite>, String> {
+    let mut filenames: Vec<String> = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir, e))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .filter(|filename| filename.ends_with(".png"))
+        .collect();
+    filenames.sort();
+
+    let mut sprites = Vec::new();
+    for filename in filenames {
+        let path = Path::new(dir).join(&filename);
+        let image = image::load(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let trimmed = calc_trim(&image);
+        sprites.push(Sprite { filename, image, trimmed });
+    }
+
+    // Larger first, ties broken by name for deterministic output.
+    sprites.sort_by(|a, b| {
+        let ka = (a.trimmed.w.max(a.trimmed.h), a.trimmed.w, a.trimmed.h);
+        let kb = (b.trimmed.w.max(b.trimmed.h), b.trimmed.w, b.trimmed.h);
+        kb.cmp(&ka).then_with(|| a.filename.cmp(&b.filename))
+    });
+    Ok(sprites)
+}
+
+fn calc_trim(image: &Image) -> Rect {
+    let opaque = |x, y| image.pixel(x, y)[3] != 0;
+    let rows: Vec<u32> = (0..image.height).filter(|&y| (0..image.width).any(|x| opaque(x, y))).collect();
+    let cols: Vec<u32> = (0..image.width).filter(|&x| (0..image.height).any(|y| opaque(x, y))).collect();
+    match (rows.first(), rows.last(), cols.first(), cols.last()) {
+        (Some(&top), Some(&bottom), Some(&left), Some(&right)) =>
+            Rect { x: left, y: top, w: right - left + 1, h: bottom - top + 1 },
+        _ => Rect { x: 0, y: 0, w: 1, h: 1 },  // Fully transparent.
+    }
+}
+
+fn blit(atlas: &mut Image, dst: &Rect, src: &Image, src_rect: &Rect) {
+    for y in 0..src_rect.h {
+        for x in 0..src_rect.w {
+            let p = src.pixel(src_rect.x + x, src_rect.y + y);
+            if p[3] == 0 {
+                continue;  // Keep transparent pixels black, so they share one palette entry.
+            }
+            let i = (((dst.y + y) * atlas.width + dst.x + x) * 4) as usize;
+            atlas.pixels[i..i + 4].copy_from_slice(p);
+        }
+    }
+}
+
+// Same layout as the one spritesheet-js emitted, so that diffs stay small.
+fn format_json(sprites: &[Sprite], rects: &[Rect], size: (u32, u32), animations: &Value) -> String {
+    let mut s = String::new();
+    s += "{\n\t\"meta\": {\n";
+    s += &format!("\t\t\"image\": \"{}.png\",\n", NAME);
+    s += &format!("\t\t\"size\": {{\"w\":{},\"h\":{}}},\n", size.0, size.1);
+    s += "\t\t\"scale\": \"1\",\n";
+    s += "\t\t\"animations\": {\n";
+    if let Some(clips) = animations.as_object() {
+        let clips: Vec<String> = clips.iter()
+            .map(|(name, clip)| format!("\t\t\t\"{}\": {}", name, format_clip(clip)))
+            .collect();
+        s += &clips.join(",\n");
+        s += "\n";
+    }
+    s += "\t\t}\n\t},\n\t\"frames\": {\n";
+
+    let frames: Vec<String> = sprites.iter().zip(rects.iter()).map(|(sprite, rect)| {
+        let t = &sprite.trimmed;
+        let trimmed = t.w != sprite.image.width || t.h != sprite.image.height;
+        format!("\t\t\"{}\":\n\t\t{{\n\
+                 \t\t\t\"frame\": {{\"x\":{},\"y\":{},\"w\":{},\"h\":{}}},\n\
+                 \t\t\t\"rotated\": false,\n\
+                 \t\t\t\"trimmed\": {},\n\
+                 \t\t\t\"spriteSourceSize\": {{\"x\":{},\"y\":{},\"w\":{},\"h\":{}}},\n\
+                 \t\t\t\"sourceSize\": {{\"w\":{},\"h\":{}}}\n\
+                 \t\t}}",
+                sprite.filename, rect.x, rect.y, rect.w, rect.h, trimmed,
+                t.x, t.y, t.w, t.h, sprite.image.width, sprite.image.height)
+    }).collect();
+    s += &frames.join(",\n");
+    s += "\n\t}\n}\n";
+    s
+}
+
+// One clip per line: {"frames": [...], "duration": n, "loop": "once"}
+fn format_clip(clip: &Value) -> String {
+    let fields: Vec<String> = ["frames", "duration", "durations", "loop"].iter()
+        .filter(|key| !clip[**key].is_null())
+        .map(|key| format!("\"{}\": {}", key, clip[*key]))
+        .collect();
+    format!("{{{}}}", fields.join(", "))
+}
@@ -0,0 +1,109 @@
+// Binary tree packer which grows the atlas to the right or downward as needed.
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rect {
+    pub x: u32,
+    pub y: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+struct Node {
+    rect: Rect,
+    used: bool,
+    right: Option<Box<Node>>,
+    down: Option<Box<Node>>,
+}
+
+impl Node {
+    fn new(x: u32, y: u32, w: u32, h: u32) -> Self {
+        Self { rect: Rect { x, y, w, h }, used: false, right: None, down: None }
+    }
+
+    fn find(&mut self, w: u32, h: u32) -> Option<&mut Node> {
+        if self.used {
+            if let Some(node) = self.right.as_mut().unwrap().find(w, h) {
+                return Some(node);
+            }
+            self.down.as_mut().unwrap().find(w, h)
+        } else if w <= self.rect.w && h <= self.rect.h {
+            Some(self)
+        } else {
+            None
+        }
+    }
+
+    fn split(&mut self, w: u32, h: u32) -> Rect {
+        let r = self.rect;
+        self.used = true;
+        self.down = Some(Box::new(Node::new(r.x, r.y + h, r.w, r.h - h)));
+        self.right = Some(Box::new(Node::new(r.x + w, r.y, r.w - w, h)));
+        Rect { x: r.x, y: r.y, w, h }
+    }
+}
+
+// Places rects of the given sizes in order, returns their positions and the atlas size.
+pub fn pack(sizes: &[(u32, u32)]) -> (Vec<Rect>, (u32, u32)) {
+    let mut root = match sizes.first() {
+        Some(&(w, h)) => Box::new(Node::new(0, 0, w, h)),
+        None => return (Vec::new(), (0, 0)),
+    };
+
+    let mut rects = Vec::with_capacity(sizes.len());
+    for &(w, h) in sizes {
+        let rect = if let Some(node) = root.find(w, h) {
+            node.split(w, h)
+        } else {
+            root = grow(root, w, h);
+            root.find(w, h).unwrap().split(w, h)
+        };
+        rects.push(rect);
+    }
+    (rects, (root.rect.w, root.rect.h))
+}
+
+fn grow(root: Box<Node>, w: u32, h: u32) -> Box<Node> {
+    let r = root.rect;
+    let can_right = h <= r.h;
+    let can_down = w <= r.w;
+    // Keep the atlas roughly square.
+    let should_right = can_right && r.h >= r.w + w;
+    let should_down = can_down && r.w >= r.h + h;
+
+    let right = should_right || (!should_down && can_right);
+    if right || !can_down {
+        let h = r.h.max(h);
+        Box::new(Node {
+            rect: Rect { x: 0, y: 0, w: r.w + w, h },
+            used: true,
+            down: Some(root),
+            right: Some(Box::new(Node::new(r.w, 0, w, h))),
+        })
+    } else {
+        Box::new(Node {
+            rect: Rect { x: 0, y: 0, w: r.w, h: r.h + h },
+            used: true,
+            down: Some(Box::new(Node::new(0, r.h, r.w, h))),
+            right: Some(root),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_overlap() {
+        let sizes = [(16, 16), (16, 16), (8, 4), (30, 2), (3, 8), (14, 14)];
+        let (rects, (w, h)) = pack(&sizes);
+        for (i, a) in rects.iter().enumerate() {
+            assert_eq!((sizes[i].0, sizes[i].1), (a.w, a.h));
+            assert!(a.x + a.w <= w && a.y + a.h <= h);
+            for b in rects.iter().skip(i + 1) {
+                let overlap = a.x < b.x + b.w && b.x < a.x + a.w && a.y < b.y + b.h && b.y < a.y + a.h;
+                assert!(!overlap, "{:?} {:?}", a, b);
+            }
+        }
+    }
+}